@@ -369,7 +369,9 @@ function displayRomajiCard(data) {
                         <div class="examples">
                             <h3>📝 例词 (Example Words)</h3>
                             ${data.examples.map((ex, i) => `
-                                <div class="example-item">${i + 1}. ${ex}</div>
+                                <div class="example-item">${i + 1}. ${ex}
+                                    <div class="accent-result" data-example="${i}"></div>
+                                </div>
                             `).join('')}
                         </div>
                     </div>
@@ -377,6 +379,16 @@ function displayRomajiCard(data) {
             </div>
         </div>
     `;
+
+    // Example words look like "愛(あい/ai) - love" or "ラーメン(raamen) - ramen"
+    data.examples.forEach((ex, i) => {
+        const match = ex.match(/^([^(]+)\(([^/)]+)/);
+        if (!match) {
+            return;
+        }
+        const reading = /^[a-z ]+$/i.test(match[2]) ? null : match[2];
+        loadAccent(match[1].trim(), reading, cardContainer.querySelector(`[data-example="${i}"]`));
+    });
 }
 
 function displayChineseCard(data) {
@@ -420,11 +432,35 @@ function displayChineseCard(data) {
                         <div class="arrow">↓</div>
                         <div class="japanese-text">${data.japanese}</div>
                         <div class="reading-text">${data.reading}</div>
+                        <div class="accent-result" id="accentResult"></div>
                     </div>
                 </div>
             </div>
         </div>
     `;
+
+    loadAccent(data.japanese, null, document.getElementById('accentResult'));
+}
+
+// Pitch accent graphs from the imported accent data (nothing is shown when none is imported)
+async function loadAccent(word, reading, container) {
+    if (!container) {
+        return;
+    }
+    try {
+        const graphs = await invoke('get_accent', { word, reading });
+        if (!graphs) {
+            return;
+        }
+        container.innerHTML = graphs.map(g => `
+            <div class="accent-item">
+                <div class="accent-label">${g.reading} [${g.pattern}] ${g.name}</div>
+                ${g.svg}
+            </div>
+        `).join('');
+    } catch (e) {
+        console.error('Failed to load pitch accent:', e);
+    }
 }
 
 // Flip card function
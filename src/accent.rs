@@ -0,0 +1,310 @@
+// 音调（アクセント）：导入开放的音调数据（Kanjium accents.txt 等 "词\t读音\t音调" 格式），
+// 保存为数据目录下的 accent.jsonl；在查询结果中用高低线标在假名上方，也可以输出 SVG 供网页和桌面端使用
+use crate::kana;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const ACCENT_FILE: &str = "accent.jsonl";
+
+// 图中用来表示后接助词的假名
+const PARTICLE: &str = "が";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AccentEntry {
+    #[serde(rename = "w")]
+    pub word: String,
+    // 平假名读音
+    #[serde(rename = "r")]
+    pub reading: String,
+    // 音调核的位置（第几拍后下降，0 为平板型），常用的在前
+    #[serde(rename = "a")]
+    pub patterns: Vec<u8>,
+}
+
+// 一行数据："日本\tにほん\t2"、"上手\tじょうず\t3,0"；Kanjium 的音调列可能带词性说明 "(名)0,(副)1"
+fn parse_line(line: &str) -> Option<AccentEntry> {
+    let mut fields = line.split('\t');
+    let word = fields.next()?.trim();
+    let reading = fields.next()?.trim();
+    let accent = fields.next()?;
+    if word.is_empty() || word.starts_with('#') {
+        return None;
+    }
+    let mut patterns: Vec<u8> = Vec::new();
+    for number in accent.split(|c: char| !c.is_ascii_digit()).filter(|n| !n.is_empty()) {
+        if let Ok(pattern) = number.parse() {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+    }
+    if patterns.is_empty() {
+        return None;
+    }
+    let reading = if reading.is_empty() { word } else { reading };
+    Some(AccentEntry {
+        word: word.to_string(),
+        reading: kana::to_hiragana(reading),
+        patterns,
+    })
+}
+
+// 导入音调数据，替换已导入的数据，返回词条数
+pub fn import(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let entries: Vec<AccentEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| parse_line(&line))
+        .collect();
+    if entries.is_empty() {
+        return Err(format!("no accent entries found in {} (expected word<TAB>reading<TAB>accent)", path.display()).into());
+    }
+
+    let target = accent_path();
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = target.with_extension("jsonl.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in &entries {
+        writeln!(writer, "{}", serde_json::to_string(entry)?)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, &target)?;
+    Ok(entries.len())
+}
+
+pub fn is_imported() -> bool {
+    accent_path().exists()
+}
+
+pub fn accent_path() -> std::path::PathBuf {
+    storage::data_file(ACCENT_FILE)
+}
+
+// 按写法或读音查询（片假名和平假名视为相同）
+pub fn lookup(word: &str) -> Vec<AccentEntry> {
+    let word = word.trim();
+    let Ok(file) = File::open(accent_path()) else {
+        return Vec::new();
+    };
+    let hiragana = kana::to_hiragana(word);
+    let needles = [serde_json::to_string(word).unwrap_or_default(), serde_json::to_string(&hiragana).unwrap_or_default()];
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| needles.iter().any(|n| line.contains(n.as_str())))
+        .filter_map(|line| serde_json::from_str::<AccentEntry>(&line).ok())
+        .filter(|entry| entry.word == word || entry.reading == hiragana)
+        .collect()
+}
+
+// 词条的音调：写法和读音都相同的优先，其次写法相同，最后只按读音（只有假名时）
+pub fn find(word: &str, reading: Option<&str>) -> Option<AccentEntry> {
+    let entries = lookup(word);
+    let reading = reading.map(kana::to_hiragana);
+    entries
+        .iter()
+        .find(|e| e.word == word && reading.as_deref().is_none_or(|r| e.reading == r))
+        .or_else(|| entries.iter().find(|e| e.word == word))
+        .or_else(|| entries.iter().find(|e| e.reading == kana::to_hiragana(word)))
+        .cloned()
+}
+
+// 拆分为拍：拗音的小写假名和前一个假名合为一拍，っ、ん、ー 各为一拍
+pub fn morae(reading: &str) -> Vec<String> {
+    let mut morae: Vec<String> = Vec::new();
+    for c in reading.chars() {
+        let small = matches!(kana::to_hiragana(&c.to_string()).as_str(), "ゃ" | "ゅ" | "ょ" | "ぁ" | "ぃ" | "ぅ" | "ぇ" | "ぉ" | "ゎ");
+        match morae.last_mut() {
+            Some(last) if small => last.push(c),
+            _ => morae.push(c.to_string()),
+        }
+    }
+    morae
+}
+
+// 每一拍的高低（true 为高），最后一个是后接助词
+pub fn pitch_levels(mora_count: usize, pattern: u8) -> Vec<bool> {
+    let pattern = usize::from(pattern);
+    (0..=mora_count)
+        .map(|i| match pattern {
+            0 => i > 0,
+            1 => i == 0,
+            n => i > 0 && i < n,
+        })
+        .collect()
+}
+
+pub fn pattern_name(pattern: u8, mora_count: usize) -> &'static str {
+    match usize::from(pattern) {
+        0 => "平板型",
+        1 => "头高型",
+        n if n >= mora_count => "尾高型",
+        _ => "中高型",
+    }
+}
+
+// 终端中的音调图：第一行是高音的线，第二行是假名和后接助词，拍之间空一格；
+// 升降标在两拍之间的空格上（┌ 上升、┐ 下降），尾高型的 ┐ 落在最后一拍和助词之间
+pub fn diagram(reading: &str, pattern: u8) -> (String, String) {
+    let mut cells = morae(reading);
+    cells.push(PARTICLE.to_string());
+    let levels = pitch_levels(cells.len() - 1, pattern);
+
+    let mut line = String::new();
+    let mut text = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+        let cell_width = UnicodeWidthStr::width(cell.as_str());
+        let width = cell_width.max(2);
+        if i > 0 {
+            text.push(' ');
+            line.push(match (levels[i - 1], levels[i]) {
+                (true, true) => '─',
+                (false, true) => '┌',
+                (true, false) => '┐',
+                (false, false) => ' ',
+            });
+        }
+        text.push_str(cell);
+        text.push_str(&" ".repeat(width - cell_width));
+        let fill = if levels[i] { "─" } else { " " };
+        line.push_str(&fill.repeat(width));
+    }
+    (line.trim_end().to_string(), text.trim_end().to_string())
+}
+
+const SVG_HEIGHT: usize = 76;
+
+// SVG 音调图的内容和宽度：高低两条线上的圆点连线，空心圆点为后接助词
+fn svg_body(reading: &str, pattern: u8) -> (usize, String) {
+    const STEP: usize = 36;
+    const HIGH: usize = 14;
+    const LOW: usize = 38;
+    let mut cells = morae(reading);
+    cells.push(PARTICLE.to_string());
+    let levels = pitch_levels(cells.len() - 1, pattern);
+    let x = |i: usize| i * STEP + STEP / 2;
+    let y = |high: bool| if high { HIGH } else { LOW };
+
+    let points: Vec<String> = levels.iter().enumerate().map(|(i, &high)| format!("{},{}", x(i), y(high))).collect();
+    let mut body = format!(
+        "  <polyline points=\"{}\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\"/>\n",
+        points.join(" ")
+    );
+    for (i, &high) in levels.iter().enumerate() {
+        let particle = i + 1 == levels.len();
+        body.push_str(&format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"5\" fill=\"{}\" stroke=\"currentColor\" stroke-width=\"2\"/>\n",
+            x(i),
+            y(high),
+            if particle { "white" } else { "currentColor" }
+        ));
+    }
+    for (i, cell) in cells.iter().enumerate() {
+        let particle = i + 1 == cells.len();
+        body.push_str(&format!(
+            "  <text x=\"{}\" y=\"68\" text-anchor=\"middle\" font-size=\"16\" fill=\"currentColor\"{}>{}</text>\n",
+            x(i),
+            if particle { " opacity=\"0.5\"" } else { "" },
+            cell
+        ));
+    }
+    (cells.len() * STEP, body)
+}
+
+fn svg_open(width: usize, height: usize) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" class=\"pitch-accent\">\n",
+        width, height, width, height
+    )
+}
+
+// 一个音调的 SVG 图
+pub fn svg(reading: &str, pattern: u8) -> String {
+    let (width, body) = svg_body(reading, pattern);
+    format!("{}{}</svg>\n", svg_open(width, SVG_HEIGHT), body)
+}
+
+// 多个音调上下排列在一个 SVG 中
+pub fn svg_sheet(items: &[(&str, u8)]) -> String {
+    let bodies: Vec<(usize, String)> = items.iter().map(|(reading, pattern)| svg_body(reading, *pattern)).collect();
+    let width = bodies.iter().map(|(w, _)| *w).max().unwrap_or(0);
+    let mut svg = svg_open(width, SVG_HEIGHT * bodies.len());
+    for (i, (_, body)) in bodies.iter().enumerate() {
+        svg.push_str(&format!("<g transform=\"translate(0 {})\">\n{}</g>\n", i * SVG_HEIGHT, body));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// 在查询结果框中输出音调（没有导入数据或查不到时不输出）
+pub fn print_accent(word: &str, reading: Option<&str>) {
+    if !is_imported() {
+        return;
+    }
+    let Some(entry) = find(word, reading) else {
+        return;
+    };
+    let mora_count = morae(&entry.reading).len();
+    for pattern in &entry.patterns {
+        let (line, text) = diagram(&entry.reading, *pattern);
+        println!("║ Accent (音调): [{}] {}", pattern, pattern_name(*pattern, mora_count));
+        println!("║     {}", line);
+        println!("║     {}", text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_names() {
+        assert_eq!(pattern_name(0, 3), "平板型");
+        assert_eq!(pattern_name(1, 3), "头高型");
+        assert_eq!(pattern_name(2, 3), "中高型");
+        assert_eq!(pattern_name(2, 2), "尾高型");
+    }
+
+    #[test]
+    fn heiban_stays_high_on_the_particle() {
+        assert_eq!(pitch_levels(3, 0), [false, true, true, true]);
+        assert_eq!(diagram("さくら", 0), ("  ┌────────".to_string(), "さ く ら が".to_string()));
+    }
+
+    #[test]
+    fn atamadaka_falls_after_the_first_mora() {
+        assert_eq!(pitch_levels(2, 1), [true, false, false]);
+        assert_eq!(diagram("はし", 1), ("──┐".to_string(), "は し が".to_string()));
+    }
+
+    #[test]
+    fn nakadaka_falls_inside_the_word() {
+        assert_eq!(pitch_levels(3, 2), [false, true, false, false]);
+        assert_eq!(diagram("こころ", 2), ("  ┌──┐".to_string(), "こ こ ろ が".to_string()));
+    }
+
+    #[test]
+    fn odaka_falls_before_the_particle() {
+        assert_eq!(pitch_levels(2, 2), [false, true, false]);
+        let (line, text) = diagram("はし", 2);
+        assert_eq!(line, "  ┌──┐");
+        assert_eq!(text, "は し が");
+        // ┐ 在 し 和 が 之间的空格上
+        let fall = line.chars().position(|c| c == '┐').unwrap();
+        assert_eq!(fall, UnicodeWidthStr::width("は し"));
+    }
+
+    #[test]
+    fn youon_is_one_mora() {
+        assert_eq!(morae("きょう"), ["きょ", "う"]);
+        assert_eq!(diagram("きょう", 1), ("────┐".to_string(), "きょ う が".to_string()));
+    }
+}
@@ -0,0 +1,147 @@
+// LLM 翻译结果的本地缓存，按 (输入, 翻译方向, 模型, 提示词版本) 存储
+use crate::storage;
+use crate::{Direction, Translation};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+const CACHE_FILE: &str = "translation_cache.json";
+
+// 默认缓存有效期（天），可通过 JP_CACHE_TTL_DAYS 修改，0 表示永不过期
+const DEFAULT_TTL_DAYS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CacheEntry {
+    pub input: String,
+    #[serde(default)]
+    pub direction: Direction,
+    pub model: String,
+    pub prompt_version: u32,
+    pub created_at: u64,
+    pub translation: Translation,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct TranslationCache {
+    entries: Vec<CacheEntry>,
+}
+
+pub struct CacheStats {
+    pub total: usize,
+    pub expired: usize,
+    pub models: Vec<(String, usize)>,
+    pub oldest: Option<u64>,
+    pub newest: Option<u64>,
+}
+
+fn ttl_secs() -> Option<u64> {
+    let days = env::var("JP_CACHE_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TTL_DAYS);
+
+    if days == 0 {
+        None
+    } else {
+        Some(days * 24 * 60 * 60)
+    }
+}
+
+impl CacheEntry {
+    fn is_expired(&self, now: u64) -> bool {
+        match ttl_secs() {
+            Some(ttl) => now.saturating_sub(self.created_at) > ttl,
+            None => false,
+        }
+    }
+}
+
+impl TranslationCache {
+    // 缓存文件损坏时不影响翻译，只打印警告并从空缓存开始
+    pub fn load() -> Self {
+        storage::load_json(CACHE_FILE).unwrap_or_else(|e| {
+            eprintln!("⚠️  Ignoring translation cache: {}", e);
+            TranslationCache::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        storage::save_json(CACHE_FILE, self)
+    }
+
+    // 查找未过期、且模型和提示词版本都匹配的缓存
+    pub fn get(&self, input: &str, direction: Direction, model: &str, prompt_version: u32) -> Option<&Translation> {
+        let now = storage::now_secs();
+        self.entries
+            .iter()
+            .find(|e| {
+                e.input == input
+                    && e.direction == direction
+                    && e.model == model
+                    && e.prompt_version == prompt_version
+                    && !e.is_expired(now)
+            })
+            .map(|e| &e.translation)
+    }
+
+    // 离线模式：不区分模型和版本、忽略有效期，返回最新的一条
+    pub fn get_any(&self, input: &str, direction: Direction) -> Option<&CacheEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.input == input && e.direction == direction)
+            .max_by_key(|e| e.created_at)
+    }
+
+    pub fn insert(&mut self, input: &str, direction: Direction, model: &str, prompt_version: u32, translation: Translation) {
+        self.entries.retain(|e| {
+            !(e.input == input && e.direction == direction && e.model == model && e.prompt_version == prompt_version)
+        });
+        self.entries.push(CacheEntry {
+            input: input.to_string(),
+            direction,
+            model: model.to_string(),
+            prompt_version,
+            created_at: storage::now_secs(),
+            translation,
+        });
+    }
+
+    // 清除缓存，返回删除的条目数
+    pub fn clear(&mut self, expired_only: bool) -> usize {
+        let before = self.entries.len();
+        if expired_only {
+            let now = storage::now_secs();
+            self.entries.retain(|e| !e.is_expired(now));
+        } else {
+            self.entries.clear();
+        }
+        before - self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[CacheEntry] {
+        &self.entries
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let now = storage::now_secs();
+        let mut models: Vec<(String, usize)> = Vec::new();
+        for entry in &self.entries {
+            match models.iter_mut().find(|(m, _)| *m == entry.model) {
+                Some((_, count)) => *count += 1,
+                None => models.push((entry.model.clone(), 1)),
+            }
+        }
+        models.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        CacheStats {
+            total: self.entries.len(),
+            expired: self.entries.iter().filter(|e| e.is_expired(now)).count(),
+            models,
+            oldest: self.entries.iter().map(|e| e.created_at).min(),
+            newest: self.entries.iter().map(|e| e.created_at).max(),
+        }
+    }
+}
+
+pub fn cache_path() -> std::path::PathBuf {
+    storage::data_file(CACHE_FILE)
+}
@@ -0,0 +1,271 @@
+// CC-CEDICT 汉英词典：导入为数据目录下的 cedict.jsonl，用来把中文输入桥接到 JMdict 的日语词条。
+// 候选词来自两方面：汉字写法相同的词条，以及英文释义相同的词条；按匹配程度排序
+use crate::hanzi;
+use crate::jmdict::{self, JmEntry};
+use crate::kana::Romanizer;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+const CEDICT_FILE: &str = "cedict.jsonl";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CedictEntry {
+    #[serde(rename = "t")]
+    pub traditional: String,
+    #[serde(rename = "s")]
+    pub simplified: String,
+    // 数字声调的拼音：gong1 yuan2
+    #[serde(rename = "py")]
+    pub pinyin: String,
+    #[serde(rename = "g")]
+    pub glosses: Vec<String>,
+}
+
+impl CedictEntry {
+    // 可以用来匹配日语释义的英文释义（去掉量词、异体字、姓氏之类的说明）
+    fn bridge_glosses(&self) -> Vec<String> {
+        const SKIP: &[&str] = &["cl:", "variant of", "old variant", "see ", "surname ", "abbr. for", "used in ", "also written"];
+        let mut glosses: Vec<String> = Vec::new();
+        for gloss in self.glosses.iter().flat_map(|g| g.split(';')) {
+            let lower = gloss.trim().to_lowercase();
+            if SKIP.iter().any(|s| lower.starts_with(s)) {
+                continue;
+            }
+            let normalized = jmdict::normalize_gloss(gloss);
+            if !normalized.is_empty() && !glosses.contains(&normalized) {
+                glosses.push(normalized);
+            }
+        }
+        glosses
+    }
+}
+
+// CC-CEDICT 的一行：傳統 传统 [chuan2 tong3] /tradition/traditional/
+fn parse_line(line: &str) -> Option<CedictEntry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (head, rest) = line.split_once(" [")?;
+    let (traditional, simplified) = head.split_once(' ')?;
+    let (pinyin, definitions) = rest.split_once("] ")?;
+    let glosses: Vec<String> = definitions
+        .trim_matches('/')
+        .split('/')
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+        .collect();
+    if glosses.is_empty() {
+        return None;
+    }
+    Some(CedictEntry {
+        traditional: traditional.to_string(),
+        simplified: simplified.to_string(),
+        pinyin: pinyin.to_string(),
+        glosses,
+    })
+}
+
+// 导入 CC-CEDICT（cedict_ts.u8），替换已导入的词典，返回词条数
+pub fn import(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let entries: Vec<CedictEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| parse_line(&line))
+        .collect();
+    if entries.is_empty() {
+        return Err(format!("no CC-CEDICT entries found in {}", path.display()).into());
+    }
+
+    let target = cedict_path();
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = target.with_extension("jsonl.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in &entries {
+        writeln!(writer, "{}", serde_json::to_string(entry)?)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, &target)?;
+    Ok(entries.len())
+}
+
+// 按简体或繁体查询
+pub fn lookup(word: &str) -> Vec<CedictEntry> {
+    let Ok(file) = File::open(cedict_path()) else {
+        return Vec::new();
+    };
+    let needle = serde_json::to_string(word).unwrap_or_default();
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| line.contains(&needle))
+        .filter_map(|line| serde_json::from_str::<CedictEntry>(&line).ok())
+        .filter(|entry| entry.simplified == word || entry.traditional == word)
+        .collect()
+}
+
+// 数字声调转为声调符号：gong1 yuan2 → gōng yuán
+pub fn pinyin_marks(pinyin: &str) -> String {
+    const MARKS: [[char; 4]; 6] = [
+        ['ā', 'á', 'ǎ', 'à'],
+        ['ē', 'é', 'ě', 'è'],
+        ['ī', 'í', 'ǐ', 'ì'],
+        ['ō', 'ó', 'ǒ', 'ò'],
+        ['ū', 'ú', 'ǔ', 'ù'],
+        ['ǖ', 'ǘ', 'ǚ', 'ǜ'],
+    ];
+    let syllables: Vec<String> = pinyin
+        .split_whitespace()
+        .map(|syllable| {
+            let syllable = syllable.replace("u:", "ü").replace('v', "ü");
+            let Some(tone) = syllable.chars().last().and_then(|c| c.to_digit(10)) else {
+                return syllable;
+            };
+            let base = &syllable[..syllable.len() - 1];
+            if !(1..=4).contains(&tone) {
+                return base.to_string();
+            }
+            // 标调规则：有 a 或 e 标在 a/e 上，ou 标在 o 上，否则标在最后一个元音上
+            let lower = base.to_lowercase();
+            let position = lower
+                .find(['a', 'e'])
+                .or_else(|| lower.find("ou"))
+                .or_else(|| lower.char_indices().rfind(|(_, c)| "iouü".contains(*c)).map(|(i, _)| i));
+            let Some(position) = position else {
+                return base.to_string();
+            };
+            base.char_indices()
+                .map(|(i, c)| {
+                    if i != position {
+                        return c;
+                    }
+                    let row = match c.to_ascii_lowercase() {
+                        'a' => 0,
+                        'e' => 1,
+                        'i' => 2,
+                        'o' => 3,
+                        'u' => 4,
+                        _ => 5,
+                    };
+                    MARKS[row][tone as usize - 1]
+                })
+                .collect()
+        })
+        .collect();
+    syllables.join(" ")
+}
+
+pub struct BridgeCandidate {
+    pub entry: JmEntry,
+    pub score: u32,
+    // 汉字写法相同
+    pub same_form: bool,
+    // 相同的英文释义
+    pub shared_glosses: Vec<String>,
+}
+
+// 中文 → 日语候选词：汉字写法相同（含日本新字体写法）+3，每个相同的英文释义 +2（最多 3 个），常用词 +1
+pub fn bridge(chinese: &str, cedict_entries: &[CedictEntry], limit: usize) -> Vec<BridgeCandidate> {
+    let mut forms: Vec<String> = Vec::new();
+    let mut glosses: Vec<String> = Vec::new();
+    let written = cedict_entries.iter().flat_map(|e| [e.simplified.as_str(), e.traditional.as_str()]);
+    for form in std::iter::once(chinese).chain(written) {
+        for form in [form.to_string(), hanzi::to_japanese(form)] {
+            if !forms.contains(&form) {
+                forms.push(form);
+            }
+        }
+    }
+    for entry in cedict_entries {
+        for gloss in entry.bridge_glosses() {
+            if !glosses.contains(&gloss) {
+                glosses.push(gloss);
+            }
+        }
+    }
+
+    let form_needles: Vec<String> = forms.iter().map(|f| serde_json::to_string(f).unwrap_or_default()).collect();
+    let mut candidates: Vec<BridgeCandidate> = Vec::new();
+    jmdict::scan(
+        |line| {
+            form_needles.iter().any(|n| line.contains(n.as_str())) || {
+                let lower = line.to_lowercase();
+                glosses.iter().any(|g| lower.contains(g.as_str()))
+            }
+        },
+        |entry| {
+            let same_form = entry.kanji.iter().any(|k| forms.contains(k));
+            let mut shared_glosses: Vec<String> = Vec::new();
+            for gloss in entry.senses.iter().flat_map(|s| &s.glosses).flat_map(|g| g.split(';')) {
+                let normalized = jmdict::normalize_gloss(gloss);
+                if glosses.contains(&normalized) && !shared_glosses.contains(&normalized) {
+                    shared_glosses.push(normalized);
+                }
+            }
+            if same_form || !shared_glosses.is_empty() {
+                let score = if same_form { 3 } else { 0 }
+                    + 2 * shared_glosses.len().min(3) as u32
+                    + if entry.is_common() { 1 } else { 0 };
+                candidates.push(BridgeCandidate { entry, score, same_form, shared_glosses });
+            }
+            true
+        },
+    );
+    // 稳定排序：分数相同时保持 JMdict 中的常用程度顺序
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.score));
+    candidates.truncate(limit);
+    candidates
+}
+
+pub fn cedict_path() -> std::path::PathBuf {
+    storage::data_file(CEDICT_FILE)
+}
+
+// 在查询结果框中输出中文词条和日语候选词
+pub fn print_bridge(chinese: &str, cedict_entries: &[CedictEntry], candidates: &[BridgeCandidate], romanizer: &Romanizer) {
+    let pinyin: Vec<String> = cedict_entries.iter().map(|e| pinyin_marks(&e.pinyin)).collect();
+    if pinyin.is_empty() {
+        println!("║ Chinese (中文): {}", chinese);
+    } else {
+        println!("║ Chinese (中文): {}  {}", chinese, pinyin.join(" / "));
+    }
+    hanzi::print_comparison(chinese);
+    let english: Vec<&str> = cedict_entries
+        .iter()
+        .flat_map(|e| &e.glosses)
+        .map(String::as_str)
+        .filter(|g| !g.starts_with("CL:"))
+        .take(6)
+        .collect();
+    if !english.is_empty() {
+        println!("║ English (英文): {}", english.join("; "));
+    }
+    println!("╟───────────────────────────────────────────────");
+    for (i, candidate) in candidates.iter().enumerate() {
+        let entry = &candidate.entry;
+        let glosses: Vec<&str> = entry.senses.iter().flat_map(|s| &s.glosses).map(String::as_str).take(3).collect();
+        let romaji = romanizer.romanize(entry.reading());
+        let reading = if entry.kanji.is_empty() { romaji } else { format!("{} / {}", entry.reading(), romaji) };
+        println!("║ {}. {}（{}）  {}", i + 1, entry.headword(), reading, glosses.join("; "));
+        let mut reasons = Vec::new();
+        if candidate.same_form {
+            reasons.push("汉字相同".to_string());
+        }
+        if !candidate.shared_glosses.is_empty() {
+            reasons.push(format!("释义相同: {}", candidate.shared_glosses.join(", ")));
+        }
+        if entry.is_common() {
+            reasons.push("常用词".to_string());
+        }
+        println!("║    {}", reasons.join(" · "));
+    }
+    println!("║");
+    println!("║ 📚 CC-CEDICT → JMdict 离线候选（按匹配程度排序）");
+}
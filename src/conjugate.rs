@@ -0,0 +1,547 @@
+// 活用：判断动词的类型（五段、一段、する、来る）和形容词的类型（い形容词、な形容词），生成各种活用形，
+// 用于 jp conjugate 和活用练习
+use crate::explain::render_table;
+use crate::jmdict;
+use crate::kana::{self, Romanizer};
+use crate::quiz;
+use crate::tokenizer::{Lexicon, Pos, GODAN_ROWS};
+use serde::Serialize;
+
+// 动词的活用形，顺序与各类动词的词尾表一致
+const VERB_FORMS: [&str; 15] = [
+    "辞书形",
+    "ます形",
+    "ました（过去）",
+    "ません（否定）",
+    "て形",
+    "た形（过去）",
+    "ない形（否定）",
+    "なかった（过去否定）",
+    "可能形",
+    "被动形",
+    "使役形",
+    "意志形",
+    "ば形（条件）",
+    "たら（条件）",
+    "命令形",
+];
+
+// 一段动词（接在词干后面）
+const ICHIDAN_ENDINGS: [&str; 15] = [
+    "る", "ます", "ました", "ません", "て", "た", "ない", "なかった", "られる", "られる", "させる", "よう", "れば", "たら", "ろ",
+];
+
+// する（接在 する 前面的部分后面）
+const SURU_ENDINGS: [&str; 15] = [
+    "する", "します", "しました", "しません", "して", "した", "しない", "しなかった", "できる", "される", "させる", "しよう", "すれば", "したら",
+    "しろ",
+];
+
+// 一个汉字 + する 中按 す 活用的动词（愛さない、愛せる、愛せ），接在汉字后面
+const SU_SAHEN_ENDINGS: [&str; 15] = [
+    "する", "します", "しました", "しません", "して", "した", "さない", "さなかった", "せる", "される", "させる", "そう", "すれば", "したら", "せ",
+];
+
+// 按 す 活用的一字サ变动词
+const SU_SAHEN_VERBS: &[&str] = &["愛する", "訳する", "課する", "略する", "属する", "欲する", "察する", "害する", "模する"];
+
+// 和 勉強する 一样活用的一字サ变动词（得しない、得できる）
+const REGULAR_ONE_KANJI_SURU: &[&str] = &["得する", "損する", "楽する", "恋する", "罰する"];
+
+// 来る（假名）；写作汉字时第一个假名换成 来
+const KURU_ENDINGS: [&str; 15] = [
+    "くる", "きます", "きました", "きません", "きて", "きた", "こない", "こなかった", "こられる", "こられる", "こさせる", "こよう", "くれば",
+    "きたら", "こい",
+];
+
+// 形容词的活用形
+const ADJECTIVE_FORMS: [&str; 9] = [
+    "辞书形",
+    "否定",
+    "过去",
+    "过去否定",
+    "て形",
+    "副词形",
+    "ば形（条件）",
+    "たら（条件）",
+    "です形（礼貌）",
+];
+
+// い形容词（接在去掉 い 的词干后面）
+const I_ADJECTIVE_ENDINGS: [&str; 9] = ["い", "くない", "かった", "くなかった", "くて", "く", "ければ", "かったら", "いです"];
+
+// な形容词（接在词干后面）
+const NA_ADJECTIVE_ENDINGS: [&str; 9] = ["だ", "じゃない", "だった", "じゃなかった", "で", "に", "なら", "だったら", "です"];
+
+// 以 い 结尾的な形容词
+const NA_ADJECTIVES_ENDING_IN_I: &[&str] = &["きれい", "嫌い", "きらい", "ていねい", "ゆうめい", "とくい", "しつれい", "あいまい", "れいせい"];
+
+// 由 いい 构成、活用时同样换成 よ 的形容词（かっこよくない）；かわいい 这样只是以 いい 结尾的词不在此列
+const II_COMPOUNDS: &[&str] = &["かっこいい", "格好いい", "カッコいい", "気持ちいい", "きもちいい", "ちょうどいい", "丁度いい", "心地いい", "ここちいい"];
+
+// ます形的词干是 い 的敬语动词（いらっしゃる → いらっしゃいます）
+const HONORIFIC_VERBS: &[&str] = &["いらっしゃる", "おっしゃる", "くださる", "なさる", "ござる", "下さる", "仰る", "為さる"];
+
+// る 前面是 い段 / え段 的五段动词（按写法判断，かえる 可能是 帰る 也可能是 変える）
+const GODAN_RU_VERBS: &[&str] = &[
+    "帰る", "入る", "走る", "知る", "切る", "要る", "減る", "限る", "喋る", "滑る", "蹴る", "握る", "参る", "照る", "散る", "焦る", "遮る",
+    "罵る", "捻る", "翻る", "陥る", "湿る", "茂る", "練る", "混じる", "覆る", "嘲る",
+];
+
+// 练习用的常用动词
+const DRILL_VERBS: &[(&str, &str, Pos)] = &[
+    ("書く", "かく", Pos::GodanVerb),
+    ("行く", "いく", Pos::GodanVerb),
+    ("泳ぐ", "およぐ", Pos::GodanVerb),
+    ("話す", "はなす", Pos::GodanVerb),
+    ("待つ", "まつ", Pos::GodanVerb),
+    ("死ぬ", "しぬ", Pos::GodanVerb),
+    ("遊ぶ", "あそぶ", Pos::GodanVerb),
+    ("飲む", "のむ", Pos::GodanVerb),
+    ("読む", "よむ", Pos::GodanVerb),
+    ("買う", "かう", Pos::GodanVerb),
+    ("会う", "あう", Pos::GodanVerb),
+    ("帰る", "かえる", Pos::GodanVerb),
+    ("分かる", "わかる", Pos::GodanVerb),
+    ("食べる", "たべる", Pos::IchidanVerb),
+    ("見る", "みる", Pos::IchidanVerb),
+    ("起きる", "おきる", Pos::IchidanVerb),
+    ("寝る", "ねる", Pos::IchidanVerb),
+    ("教える", "おしえる", Pos::IchidanVerb),
+    ("する", "する", Pos::SuruVerb),
+    ("勉強する", "べんきょうする", Pos::SuruVerb),
+    ("来る", "くる", Pos::KuruVerb),
+];
+
+// 练习用的常用形容词
+const DRILL_ADJECTIVES: &[(&str, &str, Pos)] = &[
+    ("高い", "たかい", Pos::IAdjective),
+    ("安い", "やすい", Pos::IAdjective),
+    ("大きい", "おおきい", Pos::IAdjective),
+    ("新しい", "あたらしい", Pos::IAdjective),
+    ("楽しい", "たのしい", Pos::IAdjective),
+    ("難しい", "むずかしい", Pos::IAdjective),
+    ("暑い", "あつい", Pos::IAdjective),
+    ("いい", "いい", Pos::IAdjective),
+    ("静か", "しずか", Pos::NaAdjective),
+    ("元気", "げんき", Pos::NaAdjective),
+    ("有名", "ゆうめい", Pos::NaAdjective),
+    ("好き", "すき", Pos::NaAdjective),
+    ("綺麗", "きれい", Pos::NaAdjective),
+    ("便利", "べんり", Pos::NaAdjective),
+];
+
+#[derive(Serialize)]
+pub struct Conjugation {
+    pub form: &'static str,
+    pub surface: String,
+    // 平假名读音
+    pub reading: String,
+}
+
+#[derive(Serialize)]
+pub struct Conjugations {
+    pub word: String,
+    pub reading: String,
+    pub pos: Pos,
+    pub forms: Vec<Conjugation>,
+}
+
+fn is_verb(pos: Pos) -> bool {
+    matches!(pos, Pos::GodanVerb | Pos::IchidanVerb | Pos::SuruVerb | Pos::KuruVerb)
+}
+
+// 五段动词的活用形；surface 是写法，用来判断 行く、ある 这样的例外
+fn godan_endings(text: &str, surface: &str) -> Option<Vec<String>> {
+    let ending = text.chars().last()?;
+    let stem = &text[..text.len() - ending.len_utf8()];
+    let &(_, i, a, e, o, te, ta) = GODAN_ROWS.iter().find(|row| row.0.starts_with(ending))?;
+    let iku = surface.ends_with("行く") || surface.ends_with("逝く") || surface == "いく";
+    let (te, ta) = if iku { ("って", "った") } else { (te, ta) };
+    let honorific = HONORIFIC_VERBS.iter().any(|v| surface.ends_with(v));
+    let (masu, imperative) = if honorific { ("い", "い") } else { (i, e) };
+    let aru = matches!(surface, "ある" | "有る" | "在る");
+
+    let mut forms: Vec<String> = [
+        String::new(),
+        format!("{}ます", masu),
+        format!("{}ました", masu),
+        format!("{}ません", masu),
+        te.to_string(),
+        ta.to_string(),
+        format!("{}ない", a),
+        format!("{}なかった", a),
+        format!("{}る", e),
+        format!("{}れる", a),
+        format!("{}せる", a),
+        format!("{}う", o),
+        format!("{}ば", e),
+        format!("{}ら", ta),
+        imperative.to_string(),
+    ]
+    .iter()
+    .map(|suffix| format!("{}{}", stem, suffix))
+    .collect();
+    forms[0] = text.to_string();
+    // ある 的否定是 ない
+    if aru {
+        forms[6] = "ない".to_string();
+        forms[7] = "なかった".to_string();
+    }
+    Some(forms)
+}
+
+fn is_adjective(pos: Pos) -> bool {
+    matches!(pos, Pos::IAdjective | Pos::NaAdjective)
+}
+
+// いい 本身、II_COMPOUNDS 中的词和“名词 + が + いい”（頭がいい）的词干是 よ；按写法 surface 判断
+fn takes_yo_stem(text: &str, surface: &str) -> bool {
+    text == "いい" || (text.ends_with("いい") && (surface.ends_with("がいい") || II_COMPOUNDS.contains(&surface)))
+}
+
+// い形容词的活用形；いい 的词干是 よ（よくない、よかった）
+fn i_adjective_endings(text: &str, surface: &str) -> Option<Vec<String>> {
+    let stem = match text.strip_suffix("いい").filter(|_| takes_yo_stem(text, surface)) {
+        Some(prefix) => format!("{}よ", prefix),
+        None => text.strip_suffix('い')?.to_string(),
+    };
+    let mut forms: Vec<String> = I_ADJECTIVE_ENDINGS.iter().map(|e| format!("{}{}", stem, e)).collect();
+    forms[0] = text.to_string();
+    forms[8] = format!("{}です", text);
+    Some(forms)
+}
+
+// 一个汉字 + する（愛する、得する）
+fn is_one_kanji_suru(surface: &str) -> bool {
+    surface.strip_suffix("する").is_some_and(|stem| {
+        let mut chars = stem.chars();
+        chars.next().is_some_and(kana::is_kanji) && chars.next().is_none()
+    })
+}
+
+// 按词性生成全部活用形（写法或读音）
+fn inflect(text: &str, pos: Pos, surface: &str) -> Option<Vec<String>> {
+    let with_stem = |stem: &str, endings: &[&str]| endings.iter().map(|e| format!("{}{}", stem, e)).collect();
+    match pos {
+        Pos::IAdjective => i_adjective_endings(text, surface),
+        Pos::NaAdjective => Some(with_stem(text, &NA_ADJECTIVE_ENDINGS)),
+        Pos::GodanVerb => godan_endings(text, surface),
+        Pos::IchidanVerb => Some(with_stem(text.strip_suffix('る')?, &ICHIDAN_ENDINGS)),
+        Pos::SuruVerb if SU_SAHEN_VERBS.contains(&surface) => Some(with_stem(text.strip_suffix("する")?, &SU_SAHEN_ENDINGS)),
+        // 其他一字サ变动词的活用因词而异（愛しない/愛さない），不猜测
+        Pos::SuruVerb if is_one_kanji_suru(surface) && !REGULAR_ONE_KANJI_SURU.contains(&surface) => None,
+        Pos::SuruVerb => Some(with_stem(text.strip_suffix("する")?, &SURU_ENDINGS)),
+        Pos::KuruVerb => {
+            if let Some(prefix) = text.strip_suffix("来る") {
+                let forms = KURU_ENDINGS.iter().map(|e| format!("{}来{}", prefix, e.chars().skip(1).collect::<String>()));
+                Some(forms.collect())
+            } else {
+                Some(with_stem(text.strip_suffix("くる")?, &KURU_ENDINGS))
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn conjugate(word: &str, reading: &str, pos: Pos) -> Option<Conjugations> {
+    let surfaces = inflect(word, pos, word)?;
+    let readings = inflect(reading, pos, word)?;
+    let labels: &[&'static str] = if is_adjective(pos) { &ADJECTIVE_FORMS } else { &VERB_FORMS };
+    let forms = labels
+        .iter()
+        .zip(surfaces.into_iter().zip(readings))
+        .map(|(&form, (surface, reading))| Conjugation { form, surface, reading })
+        .collect();
+    Some(Conjugations {
+        word: word.to_string(),
+        reading: reading.to_string(),
+        pos,
+        forms,
+    })
+}
+
+// 单词在句子中可能出现的写法：辞书形和各活用形，加上后面还能再接词尾的部分——
+// ます形的连用形（食べたい、書きながら）、ない形的未然形（書かず）、可能/被动/使役形去掉 る（食べられた、書かせて）。
+// 不是动词或形容词时只有原词
+pub fn inflected_surfaces(word: &str) -> Vec<String> {
+    let mut surfaces = vec![word.to_string()];
+    let conjugations = match classify(word) {
+        Ok((_, pos)) => conjugate(word, word, pos),
+        Err(_) if word.ends_with('い') => classify_adjective(word).ok().and_then(|(stem, _, pos)| conjugate(&stem, &stem, pos)),
+        Err(_) => None,
+    };
+    let Some(conjugations) = conjugations else {
+        return surfaces;
+    };
+    for Conjugation { form, surface, .. } in conjugations.forms {
+        let open = match form {
+            "ます形" => surface.strip_suffix("ます"),
+            "ない形（否定）" => surface.strip_suffix("ない"),
+            "可能形" | "被动形" | "使役形" => surface.strip_suffix('る'),
+            _ => None,
+        };
+        surfaces.extend(open.filter(|stem| !stem.is_empty()).map(str::to_string));
+        surfaces.push(surface);
+    }
+    surfaces.sort();
+    surfaces.dedup();
+    surfaces
+}
+
+// 只有假名或词典中没有的动词：按词尾猜测类型
+fn guess_verb_pos(word: &str, reading: &str) -> Option<Pos> {
+    if word == "くる" || word.ends_with("来る") {
+        return Some(Pos::KuruVerb);
+    }
+    if word.ends_with("する") {
+        return Some(Pos::SuruVerb);
+    }
+    let last = reading.chars().last()?;
+    if last == 'る' && !GODAN_RU_VERBS.contains(&word) {
+        let before = reading.chars().rev().nth(1).unwrap_or(' ');
+        if "いきしちにひみりぎじびぴえけせてねへめれげぜべぺ".contains(before) {
+            return Some(Pos::IchidanVerb);
+        }
+    }
+    "うくぐすつぬぶむる".contains(last).then_some(Pos::GodanVerb)
+}
+
+// 查询动词的读音和类型：JMdict → 内置词汇 → 按词尾猜测
+pub fn classify(word: &str) -> Result<(String, Pos), Box<dyn std::error::Error>> {
+    if is_one_kanji_suru(word) && !SU_SAHEN_VERBS.contains(&word) && !REGULAR_ONE_KANJI_SURU.contains(&word) {
+        return Err(format!("'{}' is a one-kanji する verb; their forms differ from verb to verb (愛さない, 得しない), so it is not supported yet", word).into());
+    }
+    let hiragana = kana::to_hiragana(word);
+    if jmdict::is_imported() {
+        let entry = jmdict::lookup(word)
+            .into_iter()
+            .find(|e| e.main_pos().is_some_and(is_verb) && (e.kanji.iter().any(|k| k == word) || e.kana.contains(&hiragana)));
+        if let Some(entry) = entry {
+            let pos = entry.main_pos().unwrap_or(Pos::GodanVerb);
+            let reading = if word.chars().any(kana::is_kanji) { kana::to_hiragana(entry.reading()) } else { hiragana };
+            return Ok((reading, pos));
+        }
+    }
+
+    let lexicon = Lexicon::builtin();
+    if let Some(entry) = lexicon.lookup(word).iter().find(|e| is_verb(e.pos)) {
+        // 内置词汇中没有词性的词是按词尾猜测的，五段的例外需要再判断一次
+        let pos = if GODAN_RU_VERBS.contains(&word) { Pos::GodanVerb } else { entry.pos };
+        return Ok((entry.reading.clone(), pos));
+    }
+
+    // 名词 + する
+    if let Some(noun) = word.strip_suffix("する").filter(|n| !n.is_empty()) {
+        let noun_reading = if noun.chars().any(kana::is_kanji) {
+            let from_jmdict = jmdict::lookup(noun).into_iter().next().map(|e| kana::to_hiragana(e.reading()));
+            from_jmdict.or_else(|| lexicon.lookup(noun).first().map(|e| e.reading.clone()))
+        } else {
+            Some(kana::to_hiragana(noun))
+        };
+        if let Some(noun_reading) = noun_reading {
+            return Ok((format!("{}する", noun_reading), Pos::SuruVerb));
+        }
+    }
+
+    if word.chars().any(kana::is_kanji) {
+        if word == "来る" {
+            return Ok(("くる".to_string(), Pos::KuruVerb));
+        }
+        if word.ends_with(['い', 'な']) {
+            return Err(format!("'{}' is not a known verb; for adjectives use: jp conjugate --adj {}", word, word).into());
+        }
+        return Err(format!("'{}' is not a known verb; try its kana spelling or import JMdict: jp import jmdict JMdict_e.xml", word).into());
+    }
+    let pos = guess_verb_pos(word, &hiragana).ok_or_else(|| format!("'{}' is not a verb in dictionary form (e.g. 書く, 食べる)", word))?;
+    Ok((hiragana, pos))
+}
+
+// 只有假名或词典中没有的形容词：以 い 结尾的是い形容词（きれい 等例外除外）
+fn guess_adjective_pos(word: &str) -> Pos {
+    if word.ends_with('い') && !NA_ADJECTIVES_ENDING_IN_I.contains(&word) {
+        Pos::IAdjective
+    } else {
+        Pos::NaAdjective
+    }
+}
+
+// 以 い 结尾的词干是否是な形容词（きれい、有名 这样的词去掉 な/だ 后仍以 い 结尾）
+fn is_na_adjective_stem(stem: &str) -> bool {
+    if NA_ADJECTIVES_ENDING_IN_I.contains(&stem) {
+        return true;
+    }
+    let from_jmdict = jmdict::lookup(stem)
+        .iter()
+        .any(|e| e.senses.iter().flat_map(|s| &s.pos).any(|code| Pos::from_code(code) == Some(Pos::NaAdjective)));
+    from_jmdict || Lexicon::builtin().lookup(stem).iter().any(|e| e.pos == Pos::NaAdjective)
+}
+
+// 查询形容词的词干写法、读音和类型："静かな"、"静かだ"、"きれいな" 按词干处理
+pub fn classify_adjective(word: &str) -> Result<(String, String, Pos), Box<dyn std::error::Error>> {
+    let word = match word.strip_suffix(['な', 'だ']) {
+        Some(stem) if !stem.is_empty() && (!stem.ends_with('い') || is_na_adjective_stem(stem)) => stem,
+        _ => word,
+    };
+    let hiragana = kana::to_hiragana(word);
+    if jmdict::is_imported() {
+        for entry in jmdict::lookup(word) {
+            if !(entry.kanji.iter().any(|k| k == word) || entry.kana.contains(&hiragana)) {
+                continue;
+            }
+            let codes = entry.senses.iter().flat_map(|s| &s.pos);
+            if let Some(pos) = codes.filter_map(|code| Pos::from_code(code)).find(|&pos| is_adjective(pos)) {
+                let reading = if word.chars().any(kana::is_kanji) { kana::to_hiragana(entry.reading()) } else { hiragana };
+                return Ok((word.to_string(), reading, pos));
+            }
+        }
+    }
+
+    if let Some(entry) = Lexicon::builtin().lookup(word).iter().find(|e| is_adjective(e.pos)) {
+        return Ok((word.to_string(), entry.reading.clone(), entry.pos));
+    }
+    if word.chars().any(kana::is_kanji) {
+        return Err(format!("'{}' is not a known adjective; try its kana spelling or import JMdict: jp import jmdict JMdict_e.xml", word).into());
+    }
+    Ok((word.to_string(), hiragana, guess_adjective_pos(word)))
+}
+
+pub fn print_conjugations(conjugations: &Conjugations, romanizer: &Romanizer) {
+    let romaji = romanizer.romanize(&conjugations.reading);
+    if conjugations.reading == conjugations.word {
+        println!("📖 {}（{}）· {}", conjugations.word, romaji, conjugations.pos.label());
+    } else {
+        println!("📖 {}（{} / {}）· {}", conjugations.word, conjugations.reading, romaji, conjugations.pos.label());
+    }
+    let rows: Vec<Vec<String>> = conjugations
+        .forms
+        .iter()
+        .map(|c| {
+            let reading = if c.reading == c.surface { String::new() } else { c.reading.clone() };
+            vec![c.form.to_string(), c.surface.clone(), reading, romanizer.romanize(&c.reading)]
+        })
+        .collect();
+    for line in render_table(&["活用形", "写法", "读音", "罗马音"], &rows) {
+        println!("{}", line);
+    }
+}
+
+// 活用练习的题目：随机选一个词和一个活用形（不考辞书形），写法和读音都算对
+pub fn drill_questions(words: &[Conjugations], count: usize, romanizer: &Romanizer) -> Vec<quiz::Question> {
+    let mut shuffler = quiz::Shuffler::new();
+    let mut questions: Vec<quiz::Question> = Vec::new();
+    if words.is_empty() {
+        return questions;
+    }
+    while questions.len() < count {
+        let word = &words[shuffler.next(words.len())];
+        let conjugation = &word.forms[1 + shuffler.next(word.forms.len() - 1)];
+        let prompt = if word.reading == word.word {
+            format!("{} → {}？", word.word, conjugation.form)
+        } else {
+            format!("{}（{}）→ {}？", word.word, word.reading, conjugation.form)
+        };
+        let reading = if conjugation.reading == conjugation.surface {
+            String::new()
+        } else {
+            format!("{} / ", conjugation.reading)
+        };
+        // じゃない 也可以写作 ではない
+        let mut answers = vec![conjugation.surface.clone(), conjugation.reading.clone()];
+        if conjugation.surface.contains("じゃ") {
+            answers.push(conjugation.surface.replace("じゃ", "では"));
+            answers.push(conjugation.reading.replace("じゃ", "では"));
+        }
+        questions.push(quiz::Question {
+            prompt,
+            answers,
+            explanation: format!("{}（{}{}）· {}", conjugation.surface, reading, romanizer.romanize(&conjugation.reading), word.pos.label()),
+        });
+    }
+    questions
+}
+
+// 练习用的常用动词或形容词的活用
+pub fn drill_words(adjectives: bool) -> Vec<Conjugations> {
+    let words = if adjectives { DRILL_ADJECTIVES } else { DRILL_VERBS };
+    words.iter().filter_map(|&(word, reading, pos)| conjugate(word, reading, pos)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form<'a>(conjugations: &'a Conjugations, label: &str) -> (&'a str, &'a str) {
+        let form = conjugations.forms.iter().find(|f| f.form == label).unwrap();
+        (&form.surface, &form.reading)
+    }
+
+    #[test]
+    fn irregular_verbs() {
+        let iku = conjugate("行く", "いく", Pos::GodanVerb).unwrap();
+        assert_eq!(form(&iku, "て形"), ("行って", "いって"));
+        assert_eq!(form(&iku, "た形（过去）"), ("行った", "いった"));
+        assert_eq!(form(&iku, "ない形（否定）"), ("行かない", "いかない"));
+
+        let kaku = conjugate("書く", "かく", Pos::GodanVerb).unwrap();
+        assert_eq!(form(&kaku, "た形（过去）"), ("書いた", "かいた"));
+
+        let suru = conjugate("勉強する", "べんきょうする", Pos::SuruVerb).unwrap();
+        assert_eq!(form(&suru, "ます形"), ("勉強します", "べんきょうします"));
+        assert_eq!(form(&suru, "可能形"), ("勉強できる", "べんきょうできる"));
+
+        let aisuru = conjugate("愛する", "あいする", Pos::SuruVerb).unwrap();
+        assert_eq!(form(&aisuru, "可能形"), ("愛せる", "あいせる"));
+        assert_eq!(form(&aisuru, "命令形"), ("愛せ", "あいせ"));
+        assert_eq!(form(&aisuru, "ない形（否定）"), ("愛さない", "あいさない"));
+        assert_eq!(form(&conjugate("得する", "とくする", Pos::SuruVerb).unwrap(), "可能形"), ("得できる", "とくできる"));
+        assert!(conjugate("熱する", "ねっする", Pos::SuruVerb).is_none());
+        assert!(classify("熱する").is_err());
+
+        let kuru = conjugate("来る", "くる", Pos::KuruVerb).unwrap();
+        assert_eq!(form(&kuru, "ない形（否定）"), ("来ない", "こない"));
+        assert_eq!(form(&kuru, "ます形"), ("来ます", "きます"));
+        assert_eq!(form(&kuru, "ば形（条件）"), ("来れば", "くれば"));
+
+        let aru = conjugate("ある", "ある", Pos::GodanVerb).unwrap();
+        assert_eq!(form(&aru, "ない形（否定）"), ("ない", "ない"));
+    }
+
+    #[test]
+    fn guesses_verb_class() {
+        assert_eq!(guess_verb_pos("たべる", "たべる"), Some(Pos::IchidanVerb));
+        assert_eq!(guess_verb_pos("帰る", "かえる"), Some(Pos::GodanVerb));
+        assert_eq!(guess_verb_pos("まつ", "まつ"), Some(Pos::GodanVerb));
+        assert_eq!(guess_verb_pos("くる", "くる"), Some(Pos::KuruVerb));
+        assert_eq!(guess_verb_pos("そうじする", "そうじする"), Some(Pos::SuruVerb));
+    }
+
+    #[test]
+    fn adjectives() {
+        let ii = conjugate("いい", "いい", Pos::IAdjective).unwrap();
+        assert_eq!(form(&ii, "否定"), ("よくない", "よくない"));
+        assert_eq!(form(&ii, "过去"), ("よかった", "よかった"));
+        let kakkoii = conjugate("かっこいい", "かっこいい", Pos::IAdjective).unwrap();
+        assert_eq!(form(&kakkoii, "否定"), ("かっこよくない", "かっこよくない"));
+        let kawaii = conjugate("可愛い", "かわいい", Pos::IAdjective).unwrap();
+        assert_eq!(form(&kawaii, "否定"), ("可愛くない", "かわいくない"));
+        assert_eq!(form(&kawaii, "过去"), ("可愛かった", "かわいかった"));
+        let kawaii = conjugate("かわいい", "かわいい", Pos::IAdjective).unwrap();
+        assert_eq!(form(&kawaii, "否定"), ("かわいくない", "かわいくない"));
+
+        let takai = conjugate("高い", "たかい", Pos::IAdjective).unwrap();
+        assert_eq!(form(&takai, "过去否定"), ("高くなかった", "たかくなかった"));
+        assert_eq!(form(&takai, "て形"), ("高くて", "たかくて"));
+
+        let shizuka = conjugate("静か", "しずか", Pos::NaAdjective).unwrap();
+        assert_eq!(form(&shizuka, "否定"), ("静かじゃない", "しずかじゃない"));
+        assert_eq!(form(&shizuka, "副词形"), ("静かに", "しずかに"));
+
+        assert_eq!(guess_adjective_pos("きれい"), Pos::NaAdjective);
+        let (stem, reading, pos) = classify_adjective("きれいな").unwrap();
+        assert_eq!((stem.as_str(), reading.as_str(), pos), ("きれい", "きれい", Pos::NaAdjective));
+        assert_eq!(classify_adjective("静かだ").unwrap().0, "静か");
+        assert_eq!(classify_adjective("高い").unwrap().2, Pos::IAdjective);
+        assert_eq!(guess_adjective_pos("おいしい"), Pos::IAdjective);
+    }
+}
@@ -0,0 +1,356 @@
+// 量词（助数词）：常用量词的读音规则和例外（いっぽん、さんぼん、ひとり、みっつ），用于 jp counter 和量词练习
+use crate::explain::render_table;
+use crate::kana::Romanizer;
+use crate::numbers;
+use crate::quiz;
+
+pub struct Counter {
+    pub kanji: &'static str,
+    // 量词本身的读音
+    pub reading: &'static str,
+    // 用途
+    pub usage: &'static str,
+    // 整个数的特殊读音
+    exact: &'static [(u64, &'static str)],
+    // 个位数 1-9 接量词的读音和 10 接量词的读音
+    ones: ([&'static str; 10], &'static str),
+    // 百、千后面的读音变化：ひゃく → ひゃっぽん，せん → せんぼん
+    after_hyaku: Option<&'static str>,
+    after_sen: Option<&'static str>,
+    // 常用的对象：(日文, 读音, 中文)
+    pub objects: &'static [(&'static str, &'static str, &'static str)],
+}
+
+const COUNTERS: &[Counter] = &[
+    Counter {
+        kanji: "本",
+        reading: "ほん",
+        usage: "细长的东西",
+        exact: &[],
+        ones: (["", "いっぽん", "にほん", "さんぼん", "よんほん", "ごほん", "ろっぽん", "ななほん", "はっぽん", "きゅうほん"], "じゅっぽん"),
+        after_hyaku: Some("っぽん"),
+        after_sen: Some("ぼん"),
+        objects: &[("鉛筆", "えんぴつ", "铅笔"), ("傘", "かさ", "伞"), ("瓶", "びん", "瓶子"), ("木", "き", "树"), ("バナナ", "ばなな", "香蕉")],
+    },
+    Counter {
+        kanji: "枚",
+        reading: "まい",
+        usage: "薄而平的东西",
+        exact: &[],
+        ones: (["", "いちまい", "にまい", "さんまい", "よんまい", "ごまい", "ろくまい", "ななまい", "はちまい", "きゅうまい"], "じゅうまい"),
+        after_hyaku: None,
+        after_sen: None,
+        objects: &[("紙", "かみ", "纸"), ("切手", "きって", "邮票"), ("シャツ", "しゃつ", "衬衫"), ("皿", "さら", "盘子"), ("写真", "しゃしん", "照片")],
+    },
+    Counter {
+        kanji: "匹",
+        reading: "ひき",
+        usage: "小动物、鱼、虫",
+        exact: &[],
+        ones: (["", "いっぴき", "にひき", "さんびき", "よんひき", "ごひき", "ろっぴき", "ななひき", "はっぴき", "きゅうひき"], "じゅっぴき"),
+        after_hyaku: Some("っぴき"),
+        after_sen: Some("びき"),
+        objects: &[("猫", "ねこ", "猫"), ("犬", "いぬ", "狗"), ("魚", "さかな", "鱼"), ("虫", "むし", "虫子")],
+    },
+    Counter {
+        kanji: "人",
+        reading: "にん",
+        usage: "人",
+        exact: &[(1, "ひとり"), (2, "ふたり")],
+        ones: (["", "いちにん", "ににん", "さんにん", "よにん", "ごにん", "ろくにん", "ななにん", "はちにん", "きゅうにん"], "じゅうにん"),
+        after_hyaku: None,
+        after_sen: None,
+        objects: &[("学生", "がくせい", "学生"), ("子供", "こども", "孩子"), ("友達", "ともだち", "朋友"), ("先生", "せんせい", "老师")],
+    },
+    Counter {
+        kanji: "つ",
+        reading: "つ",
+        usage: "一般的东西（只用于 1-10，11 以上直接用数字）",
+        exact: &[
+            (1, "ひとつ"),
+            (2, "ふたつ"),
+            (3, "みっつ"),
+            (4, "よっつ"),
+            (5, "いつつ"),
+            (6, "むっつ"),
+            (7, "ななつ"),
+            (8, "やっつ"),
+            (9, "ここのつ"),
+            (10, "とお"),
+        ],
+        ones: (["", "いち", "に", "さん", "よん", "ご", "ろく", "なな", "はち", "きゅう"], "じゅう"),
+        after_hyaku: None,
+        after_sen: None,
+        objects: &[("質問", "しつもん", "问题"), ("箱", "はこ", "箱子"), ("アイデア", "あいであ", "主意")],
+    },
+    Counter {
+        kanji: "個",
+        reading: "こ",
+        usage: "小而圆的东西",
+        exact: &[],
+        ones: (["", "いっこ", "にこ", "さんこ", "よんこ", "ごこ", "ろっこ", "ななこ", "はっこ", "きゅうこ"], "じゅっこ"),
+        after_hyaku: Some("っこ"),
+        after_sen: None,
+        objects: &[("りんご", "りんご", "苹果"), ("卵", "たまご", "鸡蛋"), ("消しゴム", "けしごむ", "橡皮")],
+    },
+    Counter {
+        kanji: "冊",
+        reading: "さつ",
+        usage: "书、本子",
+        exact: &[],
+        ones: (["", "いっさつ", "にさつ", "さんさつ", "よんさつ", "ごさつ", "ろくさつ", "ななさつ", "はっさつ", "きゅうさつ"], "じゅっさつ"),
+        after_hyaku: Some("っさつ"),
+        after_sen: None,
+        objects: &[("本", "ほん", "书"), ("雑誌", "ざっし", "杂志"), ("ノート", "のーと", "笔记本"), ("辞書", "じしょ", "词典")],
+    },
+    Counter {
+        kanji: "台",
+        reading: "だい",
+        usage: "机器、车辆",
+        exact: &[],
+        ones: (["", "いちだい", "にだい", "さんだい", "よんだい", "ごだい", "ろくだい", "ななだい", "はちだい", "きゅうだい"], "じゅうだい"),
+        after_hyaku: None,
+        after_sen: None,
+        objects: &[("車", "くるま", "汽车"), ("パソコン", "ぱそこん", "电脑"), ("自転車", "じてんしゃ", "自行车"), ("テレビ", "てれび", "电视")],
+    },
+    Counter {
+        kanji: "杯",
+        reading: "はい",
+        usage: "杯、碗（饮料和饭）",
+        exact: &[],
+        ones: (["", "いっぱい", "にはい", "さんばい", "よんはい", "ごはい", "ろっぱい", "ななはい", "はっぱい", "きゅうはい"], "じゅっぱい"),
+        after_hyaku: Some("っぱい"),
+        after_sen: Some("ばい"),
+        objects: &[("コーヒー", "こーひー", "咖啡"), ("水", "みず", "水"), ("ご飯", "ごはん", "米饭"), ("ビール", "びーる", "啤酒")],
+    },
+    Counter {
+        kanji: "回",
+        reading: "かい",
+        usage: "次数",
+        exact: &[],
+        ones: (["", "いっかい", "にかい", "さんかい", "よんかい", "ごかい", "ろっかい", "ななかい", "はっかい", "きゅうかい"], "じゅっかい"),
+        after_hyaku: Some("っかい"),
+        after_sen: None,
+        objects: &[],
+    },
+    Counter {
+        kanji: "階",
+        reading: "かい",
+        usage: "楼层",
+        exact: &[],
+        ones: (["", "いっかい", "にかい", "さんがい", "よんかい", "ごかい", "ろっかい", "ななかい", "はっかい", "きゅうかい"], "じゅっかい"),
+        after_hyaku: Some("っかい"),
+        after_sen: None,
+        objects: &[],
+    },
+    Counter {
+        kanji: "歳",
+        reading: "さい",
+        usage: "年龄",
+        exact: &[(20, "はたち")],
+        ones: (["", "いっさい", "にさい", "さんさい", "よんさい", "ごさい", "ろくさい", "ななさい", "はっさい", "きゅうさい"], "じゅっさい"),
+        after_hyaku: Some("っさい"),
+        after_sen: None,
+        objects: &[],
+    },
+    Counter {
+        kanji: "足",
+        reading: "そく",
+        usage: "鞋、袜子（成双的）",
+        exact: &[],
+        ones: (["", "いっそく", "にそく", "さんぞく", "よんそく", "ごそく", "ろくそく", "ななそく", "はっそく", "きゅうそく"], "じゅっそく"),
+        after_hyaku: None,
+        after_sen: Some("ぞく"),
+        objects: &[("靴", "くつ", "鞋"), ("靴下", "くつした", "袜子")],
+    },
+    Counter {
+        kanji: "頭",
+        reading: "とう",
+        usage: "大型动物",
+        exact: &[],
+        ones: (["", "いっとう", "にとう", "さんとう", "よんとう", "ごとう", "ろくとう", "ななとう", "はっとう", "きゅうとう"], "じゅっとう"),
+        after_hyaku: None,
+        after_sen: None,
+        objects: &[("牛", "うし", "牛"), ("馬", "うま", "马"), ("象", "ぞう", "大象")],
+    },
+    Counter {
+        kanji: "軒",
+        reading: "けん",
+        usage: "房屋、店铺",
+        exact: &[],
+        ones: (["", "いっけん", "にけん", "さんげん", "よんけん", "ごけん", "ろっけん", "ななけん", "はっけん", "きゅうけん"], "じゅっけん"),
+        after_hyaku: None,
+        after_sen: Some("げん"),
+        objects: &[("家", "いえ", "房子"), ("店", "みせ", "商店")],
+    },
+];
+
+// 按量词（本、ほん、hon）或对象（鉛筆、铅笔）查找
+pub fn find(text: &str, romanizer: &Romanizer) -> Vec<&'static Counter> {
+    let text = text.trim();
+    let by_counter: Vec<&Counter> = COUNTERS
+        .iter()
+        .filter(|c| c.kanji == text || c.reading == text || romanizer.romanize(c.reading) == text.to_lowercase())
+        .collect();
+    if !by_counter.is_empty() {
+        return by_counter;
+    }
+    COUNTERS
+        .iter()
+        .filter(|c| c.objects.iter().any(|(word, reading, chinese)| *word == text || *reading == text || *chinese == text))
+        .collect()
+}
+
+impl Counter {
+    // 数量的读音：3 本 → さんぼん
+    pub fn reading_of(&self, n: u64) -> String {
+        let number = numbers::number(n).kana;
+        if n > 0 && n.is_multiple_of(100) {
+            if let Some(suffix) = self.after_hyaku.filter(|_| number.ends_with("ゃく")) {
+                return format!("{}{}", number.strip_suffix('く').unwrap_or(&number), suffix);
+            }
+            if let Some(suffix) = self.after_sen.filter(|_| number.ends_with("せん") || number.ends_with("ぜん")) {
+                return format!("{}{}", number, suffix);
+            }
+        }
+        let base = if self.kanji == "つ" { "" } else { self.reading };
+        numbers::with_counter(n, self.exact, &self.ones, base)
+    }
+
+    // 写法：3本；つ 只用于 9 以下（三つ），10 是 十，11 以上只写数字
+    pub fn written(&self, n: u64) -> String {
+        if self.kanji == "つ" {
+            return match n {
+                1..=9 => format!("{}つ", numbers::number(n).written),
+                10 => "十".to_string(),
+                _ => n.to_string(),
+            };
+        }
+        format!("{}{}", n, self.kanji)
+    }
+
+    // 1-10 中和规则读音（数字 + 量词读音）不同的数
+    fn irregular(&self, n: u64) -> bool {
+        let regular = format!("{}{}", numbers::number(n).kana, self.reading);
+        self.reading_of(n) != regular
+    }
+}
+
+pub fn print_reading(counter: &Counter, n: u64, romanizer: &Romanizer) {
+    let reading = counter.reading_of(n);
+    println!("╔═══════════════════════════════════════════════");
+    println!("║ Counter (量词): {}（{}）· {}", counter.kanji, counter.reading, counter.usage);
+    println!("║ Japanese (日文): {}", counter.written(n));
+    println!("║ Kana (假名): {}", reading);
+    println!("║ Romaji (罗马音): {}", romanizer.romanize(&reading));
+    if !counter.objects.is_empty() {
+        let objects: Vec<String> = counter.objects.iter().map(|(word, _, chinese)| format!("{}（{}）", word, chinese)).collect();
+        println!("║ Examples (例子): {}", objects.join("、"));
+    }
+    println!("╚═══════════════════════════════════════════════");
+}
+
+// 1-10 的读音表，音变的数标 ⚠
+pub fn print_table(counter: &Counter, romanizer: &Romanizer) {
+    println!("📏 {}（{}）· {}", counter.kanji, counter.reading, counter.usage);
+    let rows: Vec<Vec<String>> = (1..=10)
+        .map(|n| {
+            let reading = counter.reading_of(n);
+            let mark = if counter.irregular(n) { "⚠" } else { "" };
+            vec![counter.written(n), reading.clone(), romanizer.romanize(&reading), mark.to_string()]
+        })
+        .collect();
+    for line in render_table(&["写法", "读音", "罗马音", "音变"], &rows) {
+        println!("{}", line);
+    }
+    if !counter.objects.is_empty() {
+        let objects: Vec<String> = counter.objects.iter().map(|(word, _, chinese)| format!("{}（{}）", word, chinese)).collect();
+        println!("💡 例子: {}", objects.join("、"));
+    }
+}
+
+pub fn print_list() {
+    let rows: Vec<Vec<String>> = COUNTERS
+        .iter()
+        .map(|c| {
+            let objects: Vec<&str> = c.objects.iter().map(|(word, _, _)| *word).collect();
+            vec![c.kanji.to_string(), c.reading.to_string(), c.usage.to_string(), objects.join("、")]
+        })
+        .collect();
+    for line in render_table(&["量词", "读音", "用途", "例子"], &rows) {
+        println!("{}", line);
+    }
+}
+
+// 量词练习：给出对象和数量，回答带量词的读音（要先选对量词）；指定量词时只练习它的读音
+pub fn drill_questions(counter: Option<&Counter>, count: usize, romanizer: &Romanizer) -> Vec<quiz::Question> {
+    let mut shuffler = quiz::Shuffler::new();
+    let objects: Vec<(&Counter, &(&str, &str, &str))> = COUNTERS
+        .iter()
+        .filter(|c| counter.is_none_or(|only| std::ptr::eq(*c, only)))
+        .flat_map(|c| c.objects.iter().map(move |object| (c, object)))
+        .collect();
+    (0..count)
+        .map(|_| {
+            let n = 1 + shuffler.next(10) as u64;
+            let (counter, prompt) = match (counter, objects.is_empty()) {
+                (Some(only), true) => (only, format!("{} × {} 怎么读？", n, only.kanji)),
+                _ => {
+                    let (c, (word, _, chinese)) = objects[shuffler.next(objects.len())];
+                    (c, format!("{}（{}）× {}，用量词怎么说？", word, chinese, n))
+                }
+            };
+            let reading = counter.reading_of(n);
+            quiz::Question {
+                prompt,
+                answers: vec![reading.clone(), counter.written(n)],
+                explanation: format!(
+                    "{}（{} / {}）· {}：{}",
+                    counter.written(n),
+                    reading,
+                    romanizer.romanize(&reading),
+                    counter.kanji,
+                    counter.usage
+                ),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(kanji: &str) -> &'static Counter {
+        COUNTERS.iter().find(|c| c.kanji == kanji).unwrap()
+    }
+
+    #[test]
+    fn sound_changes() {
+        let hon = counter("本");
+        assert_eq!(hon.reading_of(1), "いっぽん");
+        assert_eq!(hon.reading_of(3), "さんぼん");
+        assert_eq!(hon.reading_of(6), "ろっぽん");
+        assert_eq!(hon.reading_of(10), "じゅっぽん");
+        assert_eq!(hon.reading_of(100), "ひゃっぽん");
+        assert_eq!(hon.reading_of(1000), "せんぼん");
+        assert!(hon.irregular(3) && !hon.irregular(2));
+    }
+
+    #[test]
+    fn exceptions() {
+        let nin = counter("人");
+        assert_eq!(nin.reading_of(1), "ひとり");
+        assert_eq!(nin.reading_of(2), "ふたり");
+        assert_eq!(nin.reading_of(4), "よにん");
+
+        let tsu = counter("つ");
+        assert_eq!(tsu.reading_of(3), "みっつ");
+        assert_eq!(tsu.written(3), "三つ");
+        assert_eq!(tsu.written(10), "十");
+
+        let romanizer = Romanizer::new(&crate::init_romaji_map());
+        assert_eq!(find("hon", &romanizer)[0].kanji, "本");
+        assert_eq!(find("铅笔", &romanizer)[0].kanji, "本");
+    }
+}
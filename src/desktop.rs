@@ -11,6 +11,17 @@ use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
 use tauri_plugin_store::StoreExt;
 use tauri_plugin_notification::NotificationExt;
 
+// 与命令行版共用音调数据和 SVG 图（jp import accent 导入的 accent.jsonl）；桌面端只用到其中一部分
+#[allow(dead_code)]
+#[path = "accent.rs"]
+mod accent;
+#[allow(dead_code)]
+#[path = "kana.rs"]
+mod kana;
+#[allow(dead_code)]
+#[path = "storage.rs"]
+mod storage;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Settings {
     interval: u64,       // in seconds
@@ -63,6 +74,33 @@ async fn handle_close_action(behavior: &str, state: &AppState, app: &AppHandle)
     }
 }
 
+// 卡片背面的音调图，与 web 版 /api/accent 返回的内容相同
+#[derive(Serialize)]
+struct AccentGraph {
+    reading: String,
+    pattern: u8,
+    name: &'static str,
+    svg: String,
+}
+
+#[tauri::command]
+fn get_accent(word: String, reading: Option<String>) -> Vec<AccentGraph> {
+    let Some(entry) = accent::find(&word, reading.as_deref()) else {
+        return Vec::new();
+    };
+    let mora_count = accent::morae(&entry.reading).len();
+    entry
+        .patterns
+        .iter()
+        .map(|&pattern| AccentGraph {
+            reading: entry.reading.clone(),
+            pattern,
+            name: accent::pattern_name(pattern, mora_count),
+            svg: accent::svg(&entry.reading, pattern),
+        })
+        .collect()
+}
+
 #[tauri::command]
 fn get_settings(state: tauri::State<AppState>) -> Result<Settings, String> {
     let settings = state.settings.lock().map_err(|e| e.to_string())?;
@@ -379,7 +417,7 @@ fn main() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![get_settings, save_settings])
+        .invoke_handler(tauri::generate_handler![get_settings, save_settings, get_accent])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
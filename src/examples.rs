@@ -0,0 +1,272 @@
+// LLM 生成的分级例句：校验目标词是否出现、缓存结果，并可以添加到假名单词卡的例词列表
+use crate::{conjugate, prompts, storage};
+use crate::{
+    chat_completion, extract_json_object, json_response_format, llm_model, ChatMessage, ChatRequest,
+    JapaneseChar, JlptLevel, LlmError,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const EXAMPLE_CACHE_FILE: &str = "example_cache.json";
+const CARD_EXAMPLES_FILE: &str = "card_examples.json";
+
+// 例句提示词模板的版本，模板修改后旧的缓存不再命中
+fn prompt_version() -> u32 {
+    prompts::load("examples").map(|t| t.version()).unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExampleSentence {
+    pub japanese: String,
+    // 括号注音格式：猫[ねこ]が好[す]きです
+    #[serde(default)]
+    pub furigana: Option<String>,
+    pub translation: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExampleSet {
+    pub word: String,
+    // 目标词的平假名读音
+    #[serde(default)]
+    pub reading: String,
+    pub examples: Vec<ExampleSentence>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedExamples {
+    level: JlptLevel,
+    model: String,
+    prompt_version: u32,
+    created_at: u64,
+    // 请求的例句数；LLM 返回的可能更少，请求同样多或更少时也直接使用缓存
+    #[serde(default)]
+    requested: usize,
+    set: ExampleSet,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ExampleCache {
+    entries: Vec<CachedExamples>,
+}
+
+impl ExampleSentence {
+    // 单词卡例词的格式，与 init_romaji_map 中的 "愛(あい/ai) - love" 一样用 " - " 分隔释义
+    pub fn card_line(&self) -> String {
+        let sentence = self.furigana.as_deref().unwrap_or(&self.japanese);
+        format!("{} - {}", sentence, self.translation)
+    }
+}
+
+// 去掉括号注音，得到原句
+fn strip_furigana(furigana: &str) -> String {
+    let mut result = String::new();
+    let mut in_reading = false;
+    for c in furigana.chars() {
+        match c {
+            '[' => in_reading = true,
+            ']' => in_reading = false,
+            _ if !in_reading => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+// 句子中是否出现目标词；动词、形容词按词干加已知的活用词尾匹配（surfaces 来自 conjugate::inflected_surfaces）
+fn contains_word(sentence: &str, surfaces: &[String]) -> bool {
+    surfaces.iter().any(|surface| sentence.contains(surface.as_str()))
+}
+
+// 校验 LLM 返回的例句：没有出现目标词的句子丢弃，注音与原句不一致时去掉注音
+fn validate_examples(word: &str, examples: Vec<ExampleSentence>) -> (Vec<ExampleSentence>, usize) {
+    let total = examples.len();
+    let surfaces = conjugate::inflected_surfaces(word);
+    let valid: Vec<ExampleSentence> = examples
+        .into_iter()
+        .map(|mut example| {
+            example.japanese = example.japanese.trim().to_string();
+            example.translation = example.translation.trim().to_string();
+            example.furigana = example
+                .furigana
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty() && strip_furigana(f) == example.japanese);
+            example
+        })
+        .filter(|example| !example.translation.is_empty() && contains_word(&example.japanese, &surfaces))
+        .collect();
+    let dropped = total - valid.len();
+    (valid, dropped)
+}
+
+fn example_response_format() -> Option<serde_json::Value> {
+    json_response_format("examples", serde_json::json!({
+        "type": "object",
+        "properties": {
+            "reading": { "type": "string" },
+            "examples": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "japanese": { "type": "string" },
+                        "furigana": { "type": "string" },
+                        "translation": { "type": "string" }
+                    },
+                    "required": ["japanese", "furigana", "translation"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["reading", "examples"],
+        "additionalProperties": false
+    }))
+}
+
+// 调用 LLM 生成例句，返回通过校验的例句和被丢弃的数量
+pub async fn generate_examples(word: &str, count: usize, level: JlptLevel) -> Result<(ExampleSet, usize), LlmError> {
+    let count_text = count.to_string();
+    let level_text = level.to_string();
+    let (system_prompt, prompt) = prompts::load("examples")
+        .and_then(|t| t.render(&[("word", word), ("count", &count_text), ("level", &level_text)]))
+        .map_err(LlmError::Config)?;
+
+    let request = ChatRequest {
+        model: llm_model(),
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt,
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ],
+        temperature: 0.7,
+        response_format: example_response_format(),
+        stream: false,
+        stream_options: None,
+        feature: "examples",
+    };
+
+    #[derive(Deserialize)]
+    struct ExampleResponse {
+        #[serde(default)]
+        reading: String,
+        examples: Vec<ExampleSentence>,
+    }
+
+    let content = chat_completion(request, None).await?;
+    let json = extract_json_object(&content)
+        .ok_or_else(|| LlmError::BadResponse(format!("no JSON object in response: {}", content)))?;
+    let response: ExampleResponse = serde_json::from_str(json)
+        .map_err(|e| LlmError::BadResponse(format!("{}: {}", e, content)))?;
+
+    let (mut examples, dropped) = validate_examples(word, response.examples);
+    if examples.is_empty() {
+        return Err(LlmError::BadResponse(format!("none of the examples contain '{}'", word)));
+    }
+    examples.truncate(count);
+
+    let set = ExampleSet {
+        word: word.to_string(),
+        reading: response.reading.trim().to_string(),
+        examples,
+    };
+    Ok((set, dropped))
+}
+
+// 查找缓存的例句；离线模式下不区分模型和提示词版本
+pub fn cached_examples(word: &str, level: JlptLevel, count: usize, any_model: bool) -> Option<ExampleSet> {
+    let cache: ExampleCache = storage::load_json(EXAMPLE_CACHE_FILE).ok()?;
+    let model = llm_model();
+    let version = prompt_version();
+    cache
+        .entries
+        .into_iter()
+        .filter(|e| e.set.word == word && e.level == level && (e.set.examples.len() >= count || e.requested >= count))
+        .filter(|e| any_model || (e.model == model && e.prompt_version == version))
+        .max_by_key(|e| e.created_at)
+        .map(|e| {
+            let mut set = e.set;
+            set.examples.truncate(count);
+            set
+        })
+}
+
+pub fn cache_examples(level: JlptLevel, count: usize, set: &ExampleSet) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cache: ExampleCache = storage::load_json(EXAMPLE_CACHE_FILE).unwrap_or_default();
+    let model = llm_model();
+    let version = prompt_version();
+    cache.entries.retain(|e| {
+        !(e.set.word == set.word && e.level == level && e.model == model && e.prompt_version == version)
+    });
+    cache.entries.push(CachedExamples {
+        level,
+        model,
+        prompt_version: version,
+        created_at: storage::now_secs(),
+        requested: count,
+        set: set.clone(),
+    });
+    storage::save_json(EXAMPLE_CACHE_FILE, &cache)
+}
+
+// 根据读音的第一个假名找到对应的单词卡（罗马音）
+pub fn card_for_reading(romaji_map: &HashMap<String, JapaneseChar>, reading: &str) -> Option<String> {
+    let first = reading.chars().next()?.to_string();
+    romaji_map
+        .values()
+        .find(|c| c.hiragana == first || c.katakana == first)
+        .map(|c| c.romaji.clone())
+}
+
+// 添加到单词卡的例句，返回新增的数量（已存在的不重复添加）
+pub fn attach_to_card(romaji: &str, examples: &[ExampleSentence]) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut cards: HashMap<String, Vec<String>> = storage::load_json(CARD_EXAMPLES_FILE)?;
+    let lines = cards.entry(romaji.to_string()).or_default();
+    let mut added = 0;
+    for example in examples {
+        let line = example.card_line();
+        if !lines.contains(&line) {
+            lines.push(line);
+            added += 1;
+        }
+    }
+    storage::save_json(CARD_EXAMPLES_FILE, &cards)?;
+    Ok(added)
+}
+
+// 用户添加到某张单词卡的例句
+pub fn attached_examples(romaji: &str) -> Vec<String> {
+    storage::load_json::<HashMap<String, Vec<String>>>(CARD_EXAMPLES_FILE)
+        .ok()
+        .and_then(|mut cards| cards.remove(romaji))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_inflected_forms_only() {
+        let taberu = conjugate::inflected_surfaces("食べる");
+        assert!(contains_word("毎朝パンを食べます。", &taberu));
+        assert!(contains_word("寿司が食べたい。", &taberu));
+        assert!(contains_word("魚は食べられなかった。", &taberu));
+        assert!(!contains_word("食事の時間です。", &taberu));
+
+        let iku = conjugate::inflected_surfaces("行く");
+        assert!(contains_word("昨日学校へ行った。", &iku));
+        assert!(contains_word("行かなければならない。", &iku));
+
+        let takai = conjugate::inflected_surfaces("高い");
+        assert!(contains_word("この本は高くない。", &takai));
+        assert!(contains_word("昔は高かった。", &takai));
+        assert!(!contains_word("高校に通う。", &takai));
+
+        assert_eq!(conjugate::inflected_surfaces("学生"), ["学生"]);
+    }
+}
@@ -0,0 +1,181 @@
+// 句子语法讲解：让 LLM 把日语句子拆成词，给出读音、词性、活用形和语气说明
+use crate::prompts;
+use crate::{
+    chat_completion, extract_json_object, json_response_format, llm_model, ChatMessage, ChatRequest, LlmError,
+};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TokenInfo {
+    pub surface: String,
+    #[serde(default)]
+    pub reading: String,
+    #[serde(default)]
+    pub part_of_speech: String,
+    // 词典形（原形），与 surface 相同时可为空
+    #[serde(default)]
+    pub base_form: Option<String>,
+    // 活用形，如 "ます形"、"て形"；助词则说明其作用
+    #[serde(default)]
+    pub conjugation: Option<String>,
+    #[serde(default)]
+    pub meaning: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Explanation {
+    pub sentence: String,
+    pub translation: String,
+    pub tokens: Vec<TokenInfo>,
+    #[serde(default)]
+    pub grammar_points: Vec<String>,
+    #[serde(default)]
+    pub nuance: String,
+}
+
+fn explanation_response_format() -> Option<serde_json::Value> {
+    json_response_format("explanation", serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sentence": { "type": "string" },
+            "translation": { "type": "string" },
+            "tokens": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "surface": { "type": "string" },
+                        "reading": { "type": "string" },
+                        "part_of_speech": { "type": "string" },
+                        "base_form": { "type": ["string", "null"] },
+                        "conjugation": { "type": ["string", "null"] },
+                        "meaning": { "type": "string" }
+                    },
+                    "required": ["surface", "reading", "part_of_speech", "base_form", "conjugation", "meaning"],
+                    "additionalProperties": false
+                }
+            },
+            "grammar_points": { "type": "array", "items": { "type": "string" } },
+            "nuance": { "type": "string" }
+        },
+        "required": ["sentence", "translation", "tokens", "grammar_points", "nuance"],
+        "additionalProperties": false
+    }))
+}
+
+pub async fn explain_sentence(sentence: &str) -> Result<Explanation, LlmError> {
+    let (system_prompt, prompt) = prompts::load("explain")
+        .and_then(|t| t.render(&[("sentence", sentence)]))
+        .map_err(LlmError::Config)?;
+
+    let request = ChatRequest {
+        model: llm_model(),
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt,
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ],
+        temperature: 0.2,
+        response_format: explanation_response_format(),
+        stream: false,
+        stream_options: None,
+        feature: "explain",
+    };
+
+    let content = chat_completion(request, None).await?;
+    let json = extract_json_object(&content)
+        .ok_or_else(|| LlmError::BadResponse(format!("no JSON object in response: {}", content)))?;
+    let mut explanation: Explanation = serde_json::from_str(json)
+        .map_err(|e| LlmError::BadResponse(format!("{}: {}", e, content)))?;
+
+    explanation.tokens.retain(|t| !t.surface.trim().is_empty());
+    if explanation.tokens.is_empty() {
+        return Err(LlmError::BadResponse(format!("no tokens in explanation: {}", content)));
+    }
+    if explanation.sentence.trim().is_empty() {
+        explanation.sentence = sentence.to_string();
+    }
+    Ok(explanation)
+}
+
+fn pad_right(text: &str, width: usize) -> String {
+    let w = UnicodeWidthStr::width(text);
+    if w >= width { text.to_string() } else { format!("{}{}", text, " ".repeat(width - w)) }
+}
+
+// 按列宽输出表格，每列宽度取表头和内容中最宽的一项
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> Vec<String> {
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| UnicodeWidthStr::width(row[i].as_str()))
+                .chain(std::iter::once(UnicodeWidthStr::width(headers[i])))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let border = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}", left, segments.join(mid), right)
+    };
+    let line = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!(" {} ", pad_right(cell, *w)))
+            .collect();
+        format!("│{}│", cells.join("│"))
+    };
+
+    let mut lines = vec![border("┌", "┬", "┐"), line(headers.to_vec()), border("├", "┼", "┤")];
+    for row in rows {
+        lines.push(line(row.iter().map(String::as_str).collect()));
+    }
+    lines.push(border("└", "┴", "┘"));
+    lines
+}
+
+pub fn print_explanation(explanation: &Explanation) {
+    println!("📖 {}", explanation.sentence);
+    println!("   {}", explanation.translation);
+    println!();
+
+    let rows: Vec<Vec<String>> = explanation
+        .tokens
+        .iter()
+        .map(|t| {
+            vec![
+                t.surface.clone(),
+                t.reading.clone(),
+                t.part_of_speech.clone(),
+                t.base_form.clone().unwrap_or_default(),
+                t.conjugation.clone().unwrap_or_default(),
+                t.meaning.clone(),
+            ]
+        })
+        .collect();
+    for line in render_table(&["词", "读音", "词性", "原形", "活用/作用", "含义"], &rows) {
+        println!("{}", line);
+    }
+
+    if !explanation.grammar_points.is_empty() {
+        println!();
+        println!("📌 语法点:");
+        for point in &explanation.grammar_points {
+            println!("   - {}", point);
+        }
+    }
+    if !explanation.nuance.trim().is_empty() {
+        println!();
+        println!("💬 语气: {}", explanation.nuance);
+    }
+    println!();
+    println!("💡 提示：这是由 AI 生成的讲解");
+}
@@ -0,0 +1,81 @@
+// 汉字大字 ASCII art：和假名单词卡（scripts/generate_ascii_art.py）一样，用日文字体渲染后按灰度转换成字符。
+// 汉字太多，不能预先生成，所以在运行时读取系统中的日文字体；可以用 JP_KANJI_FONT 指定字体文件
+use ab_glyph::{Font, FontVec, PxScale};
+
+// 与假名 ASCII art 相同的大小：15 行 × 50 字符
+const ART_WIDTH: usize = 50;
+const ART_HEIGHT: usize = 15;
+// 每个字符对应的像素（终端字符的宽高比约为 1:2）
+const CELL_WIDTH: usize = 6;
+const CELL_HEIGHT: usize = 20;
+// 灰度从浅到深，只使用 ASCII 字符以避免显示宽度问题
+const SHADES: &[u8] = b" .',:;-=+*#%@";
+
+const FONT_PATHS: &[&str] = &[
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+    "C:\\Windows\\Fonts\\msmincho.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/takao-gothic/TakaoGothic.ttf",
+    "/usr/share/fonts/opentype/ipafont-gothic/ipag.ttf",
+];
+
+// 第一个包含该字的字体
+fn load_font(c: char) -> Option<FontVec> {
+    let custom = std::env::var("JP_KANJI_FONT").ok();
+    for path in custom.iter().map(String::as_str).chain(FONT_PATHS.iter().copied()) {
+        let Ok(data) = std::fs::read(path) else {
+            continue;
+        };
+        if let Ok(font) = FontVec::try_from_vec_and_index(data, 0) {
+            if font.glyph_id(c).0 != 0 {
+                return Some(font);
+            }
+        }
+    }
+    None
+}
+
+// 渲染一个字，找不到字体时返回 None
+pub fn render(c: char) -> Option<Vec<String>> {
+    let font = load_font(c)?;
+    let width = ART_WIDTH * CELL_WIDTH;
+    let height = ART_HEIGHT * CELL_HEIGHT;
+    // 字号为画布的 80%，和生成假名时一样按笔画的实际范围居中
+    let glyph = font.glyph_id(c).with_scale(PxScale::from(height as f32 * 0.8));
+    let outlined = font.outline_glyph(glyph)?;
+    let bounds = outlined.px_bounds();
+    let left = (width as f32 - bounds.width()).max(0.0) as usize / 2;
+    let top = (height as f32 - bounds.height()).max(0.0) as usize / 2;
+
+    let mut coverage = vec![0.0f32; width * height];
+    outlined.draw(|x, y, c| {
+        let (x, y) = (x as usize + left, y as usize + top);
+        if x < width && y < height {
+            coverage[y * width + x] = c;
+        }
+    });
+
+    let lines = (0..ART_HEIGHT)
+        .map(|row| {
+            (0..ART_WIDTH)
+                .map(|col| {
+                    let mut sum = 0.0;
+                    for y in row * CELL_HEIGHT..(row + 1) * CELL_HEIGHT {
+                        for x in col * CELL_WIDTH..(col + 1) * CELL_WIDTH {
+                            sum += coverage[y * width + x];
+                        }
+                    }
+                    let shade = (sum / (CELL_WIDTH * CELL_HEIGHT) as f32).min(1.0);
+                    SHADES[(shade * (SHADES.len() - 1) as f32) as usize] as char
+                })
+                .collect()
+        })
+        .collect();
+    Some(lines)
+}
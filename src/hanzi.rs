@@ -0,0 +1,162 @@
+// 汉字字形对照：简体中文、繁体中文（与日本旧字体基本相同）、日本新字体。
+// 查询前把中文输入规范化（繁体 → 简体，日文写法 → 简体），并在结果中显示 "书 → 書" 这样的对照
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// 每项三个字：简体、繁体、日本新字体；三者相同的字不列出
+const CHARACTER_TABLE: &str = "\
+学學学 习習習 书書書 语語語 说說説 话話話 读讀読 写寫写 听聽聴 见見見 车車車 东東東 门門門 问問問 间間間 们們們 \
+时時時 长長長 马馬馬 鸟鳥鳥 鱼魚魚 电電電 飞飛飛 气氣気 汉漢漢 国國国 图圖図 团團団 园園園 圆圓円 会會会 来來来 \
+对對対 发發発 关關関 实實実 经經経 战戰戦 卖賣売 买買買 应應応 铁鐵鉄 总總総 广廣広 欢歡歓 观觀観 权權権 劝勸勧 \
+变變変 恋戀恋 湾灣湾 单單単 弹彈弾 兽獸獣 乐樂楽 药藥薬 济濟済 齐齊斉 剂劑剤 斋齋斎 师師師 帅帥帥 归歸帰 妇婦婦 \
+扫掃掃 当當当 党黨党 赏賞賞 偿償償 杂雜雑 亲親親 边邊辺 过過過 还還還 进進進 运運運 远遠遠 这這這 违違違 选選選 \
+迟遲遅 适適適 递遞逓 达達達 连連連 迁遷遷 页頁頁 顶頂頂 项項項 顺順順 须須須 预預預 领領領 题題題 颜顏顔 额額額 \
+顾顧顧 频頻頻 类類類 显顯顕 愿願願 贝貝貝 负負負 财財財 贵貴貴 费費費 贸貿貿 资資資 贼賊賊 赛賽賽 赞贊賛 质質質 \
+货貨貨 贷貸貸 贫貧貧 购購購 贯貫貫 责責責 贤賢賢 败敗敗 钱錢銭 银銀銀 铜銅銅 钟鐘鐘 锁鎖鎖 错錯錯 针針針 钢鋼鋼 \
+镜鏡鏡 铅鉛鉛 锐銳鋭 纸紙紙 线線線 红紅紅 绿綠緑 给給給 结結結 练練練 终終終 绍紹紹 组組組 细細細 织織織 约約約 \
+级級級 纪紀紀 纯純純 纳納納 纷紛紛 续續続 绝絕絶 统統統 络絡絡 继繼継 绩績績 维維維 综綜綜 缘緣縁 编編編 缩縮縮 \
+网網網 绳繩縄 乡鄉郷 县縣県 词詞詞 认認認 识識識 计計計 记記記 讲講講 许許許 论論論 设設設 访訪訪 证證証 评評評 \
+试試試 诗詩詩 该該該 详詳詳 误誤誤 请請請 谁誰誰 调調調 谈談談 谢謝謝 议議議 译譯訳 护護護 课課課 谊誼誼 谦謙謙 \
+谜謎謎 诚誠誠 诞誕誕 诸諸諸 谓謂謂 谱譜譜 让讓譲 训訓訓 讨討討 订訂訂 饭飯飯 饮飲飲 馆館館 饿餓餓 饼餅餅 养養養 \
+饰飾飾 驾駕駕 验驗験 骑騎騎 驱驅駆 驻駐駐 骚騷騒 惊驚驚 鸡雞鶏 鸣鳴鳴 鲜鮮鮮 开開開 闭閉閉 闲閑閑 闻聞聞 阅閱閲 \
+阁閣閣 闪閃閃 规規規 视視視 览覽覧 觉覺覚 轻輕軽 转轉転 输輸輸 较較較 载載載 军軍軍 阵陣陣 库庫庫 轮輪輪 软軟軟 \
+辈輩輩 张張張 帐帳帳 场場場 汤湯湯 阳陽陽 伤傷傷 扬揚揚 杨楊楊 肠腸腸 冻凍凍 陈陳陳 为為為 伪偽偽 举舉挙 兴興興 \
+与與与 压壓圧 厅廳庁 历歷歴 严嚴厳 丰豐豊 临臨臨 丽麗麗 两兩両 乱亂乱 争爭争 亚亞亜 产產産 从從従 众眾衆 价價価 \
+传傳伝 伦倫倫 体體体 余餘余 侧側側 侨僑僑 俭儉倹 债債債 倾傾傾 优優優 仪儀儀 伟偉偉 备備備 宝寶宝 宁寧寧 宫宮宮 \
+寿壽寿 导導導 层層層 岁歲歳 岛島島 带帶帯 庆慶慶 废廢廃 异異異 强強強 彻徹徹 径徑径 忆憶憶 怀懷懐 态態態 恶惡悪 \
+悬懸懸 惯慣慣 愤憤憤 忧憂憂 户戶戸 执執執 扩擴拡 担擔担 择擇択 拥擁擁 挂掛掛 换換換 损損損 据據拠 摄攝摂 击擊撃 \
+报報報 数數数 断斷断 晓曉暁 术術術 机機機 杀殺殺 条條条 杰傑傑 极極極 构構構 枪槍槍 标標標 样樣様 树樹樹 桥橋橋 \
+梦夢夢 检檢検 楼樓楼 横橫横 欧歐欧 残殘残 毁毀毀 毕畢畢 沟溝溝 没沒没 泽澤沢 洁潔潔 浅淺浅 测測測 浓濃濃 润潤潤 \
+渐漸漸 温溫温 湿濕湿 满滿満 灭滅滅 灯燈灯 灵靈霊 灾災災 点點点 烟煙煙 热熱熱 爱愛愛 爷爺爺 牺犧犠 状狀状 犹猶猶 \
+独獨独 狭狹狭 猎獵猟 献獻献 环環環 现現現 画畫画 畅暢暢 疗療療 盐鹽塩 监監監 盖蓋蓋 盘盤盤 矿礦鉱 础礎礎 确確確 \
+礼禮礼 祸禍禍 离離離 种種種 称稱称 积積積 稳穩穏 穷窮窮 窃竊窃 竞競競 笔筆筆 笼籠籠 筑築築 简簡簡 节節節 范範範 \
+荣榮栄 获獲獲 营營営 蓝藍藍 艺藝芸 苏蘇蘇 虑慮慮 虫蟲虫 蚕蠶蚕 补補補 装裝装 触觸触 誉譽誉 跃躍躍 践踐践 轨軌軌 \
+辞辭辞 迹跡跡 邮郵郵 邻鄰隣 酱醬醤 医醫医 释釋釈 鉴鑑鑑 钓釣釣 铃鈴鈴 铭銘銘 锅鍋鍋 键鍵鍵 队隊隊 阶階階 际際際 \
+陆陸陸 险險険 随隨随 隐隱隠 难難難 雾霧霧 静靜静 韩韓韓 风風風 饥飢飢 麦麥麦 黄黃黄 齿齒歯 龙龍竜 龟龜亀 区區区 \
+华華華 卫衛衛 双雙双 叶葉葉 号號号 叹嘆嘆 员員員 启啟啓 响響響 围圍囲 圣聖聖 坏壞壊 块塊塊 坚堅堅 坛壇壇 垒壘塁 \
+处處処 复復復 头頭頭 夺奪奪 奋奮奮 奖獎奨 娱娛娯 孙孫孫 宪憲憲 审審審 宽寬寛 宾賓賓 寻尋尋 尔爾爾 尘塵塵 尽盡尽 \
+届屆届 属屬属 币幣幣 庄莊荘 庙廟廟 弃棄棄 弥彌弥 录錄録 恳懇懇 惨慘惨 戏戲戯 拟擬擬 挥揮揮 携攜携 摇搖揺 敌敵敵 \
+无無無 旧舊旧 昼晝昼 暂暫暫 壳殼殻 污汙汚 泪淚涙 浊濁濁 涂塗塗 涌湧湧 渔漁漁 滞滯滞 滨濱浜 潜潛潜 炉爐炉 烦煩煩 \
+烧燒焼 牵牽牽 猪豬猪 猫貓猫 盗盜盗 矫矯矯 碍礙碍 祷禱祷 竖豎竪 粮糧糧 紧緊緊 纠糾糾 纤纖繊 纬緯緯 纲綱綱 纵縱縦 \
+纹紋紋 纺紡紡 绘繪絵 绪緒緒 绵綿綿 缓緩緩 缝縫縫 罗羅羅 罚罰罰 职職職 聪聰聡 肃肅粛 肤膚膚 肿腫腫 胁脅脅 胆膽胆 \
+胜勝勝 脑腦脳 脏臟臓 脚腳脚 舰艦艦 艳艷艶 苍蒼蒼 茎莖茎 荐薦薦 莲蓮蓮 虏虜虜 虚虛虚 蛮蠻蛮 袭襲襲 讯訊訊 询詢詢 \
+谅諒諒 谋謀謀 贞貞貞 贡貢貢 贩販販 贮貯貯 贴貼貼 贺賀賀 赋賦賦 赌賭賭 赐賜賜 赔賠賠 赖賴頼 赠贈贈 趋趨趨 躯軀躯 \
+辉輝輝 辑輯輯 辖轄轄 辩辯弁 遗遺遺 酿釀醸 钝鈍鈍 铺鋪舗 锋鋒鋒 锦錦錦 锻鍛鍛 镇鎮鎮 阴陰陰 隶隸隷 韵韻韻 顽頑頑 \
+顿頓頓 颁頒頒 颂頌頌 饱飽飽 驰馳馳 骂罵罵 鲁魯魯 鲸鯨鯨 鸭鴨鴨 鹤鶴鶴 黑黑黒 龄齡齢 步步歩 每每毎 将將将 净淨浄 \
+德德徳 剑劍剣 栈棧桟 姬姬姫 巢巢巣 粹粹粋 醉醉酔 谨謹謹 兔兔兎 蝉蟬蝉 禅禪禅 恒恆恒 叙敘叙 别別別";
+
+// 一对多或者本身就是另一个日文汉字的字：发（發/髮）、复（復/複）、历（歷/曆）、叶（葉/日文的 叶う）、
+// 机（機/日文的 机）、弁（辯/辨/瓣）……不用于自动转换，只在字形对照中显示
+const AMBIGUOUS: &str = "发复历脏钟范丰杰涂叶余机据弁";
+
+#[derive(Clone, Copy)]
+struct Variants {
+    simplified: char,
+    japanese: char,
+}
+
+// 简体、繁体、日本新字体都可以作为键；all 为 true 时包含 AMBIGUOUS 中的字（字形对照用）
+fn build_table(all: bool) -> HashMap<char, Variants> {
+    let mut table = HashMap::new();
+    for item in CHARACTER_TABLE.split_whitespace() {
+        let chars: Vec<char> = item.chars().collect();
+        let [simplified, traditional, japanese] = chars[..] else {
+            panic!("hanzi table item '{}' must have exactly 3 characters", item);
+        };
+        let variants = Variants { simplified, japanese };
+        for c in [simplified, traditional, japanese] {
+            if all || !AMBIGUOUS.contains(c) {
+                table.entry(c).or_insert(variants);
+            }
+        }
+    }
+    table
+}
+
+// 自动转换用的对照表
+fn table() -> &'static HashMap<char, Variants> {
+    static TABLE: OnceLock<HashMap<char, Variants>> = OnceLock::new();
+    TABLE.get_or_init(|| build_table(false))
+}
+
+// 字形对照用的对照表
+fn display_table() -> &'static HashMap<char, Variants> {
+    static TABLE: OnceLock<HashMap<char, Variants>> = OnceLock::new();
+    TABLE.get_or_init(|| build_table(true))
+}
+
+// 转为日本新字体：学习 → 学習，書 → 書
+pub fn to_japanese(text: &str) -> String {
+    let table = table();
+    text.chars().map(|c| table.get(&c).map(|v| v.japanese).unwrap_or(c)).collect()
+}
+
+// 转为简体中文：學習 / 学習 → 学习
+pub fn to_simplified(text: &str) -> String {
+    let table = table();
+    text.chars().map(|c| table.get(&c).map(|v| v.simplified).unwrap_or(c)).collect()
+}
+
+// 中文和日文写法不同的字：书 → 書、习 → 習
+pub fn comparisons(chinese: &str) -> Vec<(char, char)> {
+    let table = display_table();
+    let mut pairs: Vec<(char, char)> = Vec::new();
+    for c in chinese.chars() {
+        if let Some(variants) = table.get(&c) {
+            let pair = (c, variants.japanese);
+            if c != variants.japanese && !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+        }
+    }
+    pairs
+}
+
+// "书 → 書、习 → 習"，没有字形差异时返回 None
+pub fn comparison_text(chinese: &str) -> Option<String> {
+    let pairs = comparisons(chinese);
+    if pairs.is_empty() {
+        return None;
+    }
+    let items: Vec<String> = pairs.iter().map(|(c, j)| format!("{} → {}", c, j)).collect();
+    Some(items.join("、"))
+}
+
+// 在查询结果框中输出字形对照
+pub fn print_comparison(chinese: &str) {
+    if let Some(text) = comparison_text(chinese) {
+        println!("║ Kanji (字形对照): {}", text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_table_item_is_parsed() {
+        let items = CHARACTER_TABLE.split_whitespace().count();
+        assert_eq!(items, 622);
+        assert!(CHARACTER_TABLE.split_whitespace().all(|item| item.chars().count() == 3));
+    }
+
+    #[test]
+    fn row_boundary_characters_are_mapped() {
+        assert_eq!(to_japanese("时间"), "時間");
+        assert_eq!(to_japanese("对来传报处无价桥选变区华"), "対来伝報処無価橋選変区華");
+        assert_eq!(to_simplified("時間"), "时间");
+        assert_eq!(comparison_text("时间").as_deref(), Some("时 → 時、间 → 間"));
+    }
+
+    #[test]
+    fn ambiguous_characters_are_not_converted() {
+        assert_eq!(to_japanese("叶う"), "叶う");
+        assert_eq!(to_japanese("余る"), "余る");
+        assert_eq!(to_japanese("机"), "机");
+        assert_eq!(to_japanese("头发"), "頭发");
+        assert_eq!(to_simplified("弁当"), "弁当");
+        assert_eq!(to_simplified("葉"), "叶");
+        assert_eq!(to_japanese("發"), "発");
+        assert_eq!(comparison_text("出发").as_deref(), Some("发 → 発"));
+    }
+}
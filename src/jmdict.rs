@@ -0,0 +1,561 @@
+// JMdict 日语词典：把 JMdict XML（JMdict_e）或 jmdict-simplified JSON 导入为数据目录下的 jmdict.jsonl，
+// 每行一个词条，按常用程度排序；同时生成 jmdict.idx（按写法排序的“写法\t行偏移”），
+// 按写法查询时在索引中二分查找再直接定位到词条，不需要扫描或读入整个词典
+use crate::accent;
+use crate::kana::{self, Romanizer};
+use crate::levels::{self, LevelIndex};
+use crate::storage;
+use crate::tokenizer::Pos;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const JMDICT_FILE: &str = "jmdict.jsonl";
+const INDEX_FILE: &str = "jmdict.idx";
+
+// 常用词的优先级标记（JMdict 中带这些标记的词条视为常用词）
+const COMMON_MARKERS: &[&str] = &["news1", "ichi1", "spec1", "spec2", "gai1", "common"];
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct JmSense {
+    // 词性代码：n、v5k、adj-i……
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pos: Vec<String>,
+    #[serde(rename = "g")]
+    pub glosses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct JmEntry {
+    pub id: u32,
+    #[serde(rename = "k", default, skip_serializing_if = "Vec::is_empty")]
+    pub kanji: Vec<String>,
+    #[serde(rename = "r")]
+    pub kana: Vec<String>,
+    // 优先级标记：news1、ichi1、nf12……
+    #[serde(rename = "p", default, skip_serializing_if = "Vec::is_empty")]
+    pub priority: Vec<String>,
+    #[serde(rename = "s")]
+    pub senses: Vec<JmSense>,
+}
+
+impl JmEntry {
+    pub fn is_common(&self) -> bool {
+        self.priority.iter().any(|p| COMMON_MARKERS.contains(&p.as_str()))
+    }
+
+    // 排序用：常用词在前，再按 nfXX（词频分组，越小越常用）
+    fn rank(&self) -> (bool, u32, u32) {
+        let nf = self
+            .priority
+            .iter()
+            .filter_map(|p| p.strip_prefix("nf").and_then(|n| n.parse().ok()))
+            .min()
+            .unwrap_or(99);
+        (!self.is_common(), nf, self.id)
+    }
+
+    // 主要写法：有汉字时取第一个汉字写法，否则取假名
+    pub fn headword(&self) -> &str {
+        self.kanji.first().or(self.kana.first()).map(String::as_str).unwrap_or("")
+    }
+
+    pub fn reading(&self) -> &str {
+        self.kana.first().map(String::as_str).unwrap_or("")
+    }
+
+    // 第一个能识别的词性
+    pub fn main_pos(&self) -> Option<Pos> {
+        self.senses.iter().flat_map(|s| &s.pos).find_map(|code| Pos::from_code(code))
+    }
+
+    // 索引键：所有汉字和假名写法，片假名统一成平假名
+    fn index_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.kanji.iter().chain(&self.kana).map(|w| kana::to_hiragana(w)).collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    fn matches_word(&self, word: &str) -> bool {
+        let hiragana = kana::to_hiragana(word);
+        self.kanji.iter().any(|k| k == word) || self.kana.iter().any(|r| r == word || kana::to_hiragana(r) == hiragana)
+    }
+}
+
+pub struct ImportStats {
+    pub entries: usize,
+    pub common: usize,
+}
+
+// 把 &amp; 这样的实体还原；JMdict 的词性等字段使用 &v5k; 这样的自定义实体，直接取实体名
+fn unescape(text: &str) -> String {
+    let text = text.trim();
+    if let Some(name) = text.strip_prefix('&').and_then(|t| t.strip_suffix(';')) {
+        if !matches!(name, "amp" | "lt" | "gt" | "quot" | "apos") {
+            return name.to_string();
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// 解析 JMdict XML；只保留英文释义。没有词性的义项沿用前一个义项的词性（JMdict 的约定）
+fn parse_xml(content: &str) -> Vec<JmEntry> {
+    let mut entries = Vec::new();
+    let mut entry = JmEntry::default();
+    let mut sense: Option<JmSense> = None;
+    let mut last_pos: Vec<String> = Vec::new();
+
+    // 跳过 DTD
+    let mut rest = content.find("<JMdict>").map(|i| &content[i..]).unwrap_or(content);
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+        let (name, attrs) = tag.split_once(' ').unwrap_or((tag, ""));
+        let text = || unescape(&rest[..rest.find('<').unwrap_or(rest.len())]);
+
+        match name {
+            "entry" => {
+                entry = JmEntry::default();
+                last_pos.clear();
+            }
+            "/entry" if !entry.kana.is_empty() && !entry.senses.is_empty() => {
+                entries.push(std::mem::take(&mut entry));
+            }
+            "sense" => sense = Some(JmSense::default()),
+            "/sense" => {
+                if let Some(mut s) = sense.take() {
+                    if s.pos.is_empty() {
+                        s.pos = last_pos.clone();
+                    } else {
+                        last_pos = s.pos.clone();
+                    }
+                    if !s.glosses.is_empty() {
+                        entry.senses.push(s);
+                    }
+                }
+            }
+            "ent_seq" => entry.id = text().parse().unwrap_or(0),
+            "keb" => entry.kanji.push(text()),
+            "reb" => entry.kana.push(text()),
+            "ke_pri" | "re_pri" => {
+                let marker = text();
+                if !entry.priority.contains(&marker) {
+                    entry.priority.push(marker);
+                }
+            }
+            "pos" => {
+                if let Some(s) = sense.as_mut() {
+                    s.pos.push(text());
+                }
+            }
+            "gloss" => {
+                let english = !attrs.contains("xml:lang") || attrs.contains("\"eng\"");
+                if let (Some(s), true) = (sense.as_mut(), english) {
+                    s.glosses.push(text());
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+// jmdict-simplified 的 JSON 格式
+#[derive(Deserialize)]
+struct SimplifiedDict {
+    words: Vec<SimplifiedWord>,
+}
+
+#[derive(Deserialize)]
+struct SimplifiedWord {
+    id: String,
+    #[serde(default)]
+    kanji: Vec<SimplifiedText>,
+    #[serde(default)]
+    kana: Vec<SimplifiedText>,
+    #[serde(default)]
+    sense: Vec<SimplifiedSense>,
+}
+
+#[derive(Deserialize)]
+struct SimplifiedText {
+    text: String,
+    #[serde(default)]
+    common: bool,
+}
+
+#[derive(Deserialize)]
+struct SimplifiedSense {
+    #[serde(rename = "partOfSpeech", default)]
+    part_of_speech: Vec<String>,
+    #[serde(default)]
+    gloss: Vec<SimplifiedGloss>,
+}
+
+#[derive(Deserialize)]
+struct SimplifiedGloss {
+    #[serde(default)]
+    lang: String,
+    text: String,
+}
+
+fn parse_simplified(content: &str) -> Result<Vec<JmEntry>, serde_json::Error> {
+    let dict: SimplifiedDict = serde_json::from_str(content)?;
+    let entries = dict
+        .words
+        .into_iter()
+        .map(|word| {
+            // simplified 格式只保留了“是否常用”，没有具体的优先级标记
+            let common = word.kanji.iter().chain(&word.kana).any(|t| t.common);
+            JmEntry {
+                id: word.id.parse().unwrap_or(0),
+                kanji: word.kanji.into_iter().map(|t| t.text).collect(),
+                kana: word.kana.into_iter().map(|t| t.text).collect(),
+                priority: if common { vec!["common".to_string()] } else { Vec::new() },
+                senses: word
+                    .sense
+                    .into_iter()
+                    .map(|s| JmSense {
+                        pos: s.part_of_speech,
+                        glosses: s
+                            .gloss
+                            .into_iter()
+                            .filter(|g| g.lang.is_empty() || g.lang == "eng")
+                            .map(|g| g.text)
+                            .collect(),
+                    })
+                    .filter(|s| !s.glosses.is_empty())
+                    .collect(),
+            }
+        })
+        .filter(|entry| !entry.kana.is_empty() && !entry.senses.is_empty())
+        .collect();
+    Ok(entries)
+}
+
+// 导入 JMdict XML 或 jmdict-simplified JSON，替换已导入的词典
+pub fn import(path: &Path) -> Result<ImportStats, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut entries = if content.trim_start().starts_with('{') {
+        parse_simplified(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?
+    } else {
+        parse_xml(&content)
+    };
+    if entries.is_empty() {
+        return Err(format!("no JMdict entries found in {} (expected JMdict XML or jmdict-simplified JSON)", path.display()).into());
+    }
+    entries.sort_by_key(JmEntry::rank);
+
+    let target = jmdict_path();
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = target.with_extension("jsonl.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in &entries {
+        writeln!(writer, "{}", serde_json::to_string(entry)?)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, &target)?;
+    build_index(&target, &index_path())?;
+
+    Ok(ImportStats {
+        entries: entries.len(),
+        common: entries.iter().filter(|e| e.is_common()).count(),
+    })
+}
+
+// 逐行读取词典，先用 prefilter 排除不可能匹配的行再解析
+pub fn scan(prefilter: impl Fn(&str) -> bool, mut visit: impl FnMut(JmEntry) -> bool) {
+    let Ok(file) = File::open(jmdict_path()) else {
+        return;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if !prefilter(&line) {
+            continue;
+        }
+        if let Ok(entry) = serde_json::from_str::<JmEntry>(&line) {
+            if !visit(entry) {
+                break;
+            }
+        }
+    }
+}
+
+pub fn is_imported() -> bool {
+    jmdict_path().exists()
+}
+
+// 根据 jmdict.jsonl 生成写法索引：每行“写法\t行的字节偏移”，按写法排序，同一写法内保持词典的常用程度顺序
+fn build_index(source: &Path, target: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let mut keys: Vec<(String, u64)> = Vec::new();
+    let mut offset = 0u64;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let len = reader.read_line(&mut line)?;
+        if len == 0 {
+            break;
+        }
+        if let Ok(entry) = serde_json::from_str::<JmEntry>(&line) {
+            keys.extend(entry.index_keys().into_iter().map(|key| (key, offset)));
+        }
+        offset += len as u64;
+    }
+    keys.sort();
+
+    let tmp_path = target.with_extension("idx.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for (key, offset) in &keys {
+        writeln!(writer, "{}\t{}", key, offset)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, target)?;
+    Ok(())
+}
+
+// 读取从 pos 开始的第一整行（pos 不在行首时跳过当前行的剩余部分），返回行首位置和去掉换行的内容
+fn line_from(reader: &mut BufReader<File>, pos: u64) -> std::io::Result<(u64, String)> {
+    let mut start = pos;
+    let mut line = String::new();
+    if pos > 0 {
+        reader.seek(SeekFrom::Start(pos - 1))?;
+        let mut skipped = Vec::new();
+        start = pos - 1 + reader.read_until(b'\n', &mut skipped)? as u64;
+    } else {
+        reader.seek(SeekFrom::Start(0))?;
+    }
+    reader.read_line(&mut line)?;
+    Ok((start, line.trim_end_matches('\n').to_string()))
+}
+
+// 在索引中二分查找写法，返回对应词条在 jmdict.jsonl 中的偏移
+fn index_offsets(path: &Path, key: &str) -> std::io::Result<Vec<u64>> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let key_of = |line: &str| line.split('\t').next().unwrap_or("").to_string();
+
+    // 不变量：lo 之前开始的行都小于 key，hi 及之后开始的行都不小于 key
+    let (mut lo, mut hi) = (0u64, size);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let (start, line) = line_from(&mut reader, mid)?;
+        if start >= hi || key_of(&line).as_str() >= key {
+            hi = mid;
+        } else {
+            lo = start + line.len() as u64 + 1;
+        }
+    }
+
+    let mut offsets = Vec::new();
+    let mut pos = lo;
+    while pos < size {
+        let (_, line) = line_from(&mut reader, pos)?;
+        let Some((line_key, offset)) = line.split_once('\t') else {
+            break;
+        };
+        if line_key != key {
+            break;
+        }
+        offsets.extend(offset.parse::<u64>().ok());
+        pos += line.len() as u64 + 1;
+    }
+    Ok(offsets)
+}
+
+// 按汉字或假名写法查询（片假名和平假名视为相同）；旧版本导入的词典没有索引时先补建
+pub fn lookup(word: &str) -> Vec<JmEntry> {
+    let word = word.trim();
+    if word.is_empty() || !is_imported() {
+        return Vec::new();
+    }
+    let index = index_path();
+    if !index.exists() && build_index(&jmdict_path(), &index).is_err() {
+        return Vec::new();
+    }
+    let Ok(offsets) = index_offsets(&index, &kana::to_hiragana(word)) else {
+        return Vec::new();
+    };
+    let Ok(file) = File::open(jmdict_path()) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(file);
+    let mut results = Vec::new();
+    for offset in offsets {
+        let mut line = String::new();
+        if reader.seek(SeekFrom::Start(offset)).is_err() || reader.read_line(&mut line).is_err() {
+            continue;
+        }
+        if let Ok(entry) = serde_json::from_str::<JmEntry>(&line) {
+            if entry.matches_word(word) {
+                results.push(entry);
+            }
+        }
+    }
+    results
+}
+
+// 用于比较的释义：小写，去掉括号中的说明和动词的 "to "
+pub fn normalize_gloss(gloss: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0;
+    for c in gloss.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 => result.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+    let result = result.split_whitespace().collect::<Vec<_>>().join(" ");
+    result.strip_prefix("to ").map(str::to_string).unwrap_or(result)
+}
+
+// 释义中是否包含完整的单词（不区分大小写），返回匹配程度：0 完全相同，1 包含该词
+fn gloss_match(gloss: &str, query: &str) -> Option<u8> {
+    let gloss = gloss.to_lowercase();
+    if gloss == query || gloss.strip_prefix("to ") == Some(query) {
+        return Some(0);
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '-';
+    gloss.match_indices(query).find_map(|(i, _)| {
+        let before = gloss[..i].chars().next_back();
+        let after = gloss[i + query.len()..].chars().next();
+        (!before.map(is_word_char).unwrap_or(false) && !after.map(is_word_char).unwrap_or(false)).then_some(1)
+    })
+}
+
+// 按英文释义查询，完全匹配的排在前面，其余按常用程度
+pub fn search_gloss(query: &str, limit: usize) -> Vec<JmEntry> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut results: Vec<(u8, JmEntry)> = Vec::new();
+    scan(
+        |line| line.to_lowercase().contains(&query),
+        |entry| {
+            let best = entry.senses.iter().flat_map(|s| &s.glosses).filter_map(|g| gloss_match(g, &query)).min();
+            if let Some(score) = best {
+                results.push((score, entry));
+            }
+            true
+        },
+    );
+    results.sort_by_key(|(score, _)| *score);
+    results.into_iter().take(limit).map(|(_, entry)| entry).collect()
+}
+
+// 常用词条，供离线分词使用；词典按常用程度排序，读到第一个非常用词就可以停止
+pub fn common_entries() -> Vec<JmEntry> {
+    let mut results = Vec::new();
+    scan(
+        |_| true,
+        |entry| {
+            if !entry.is_common() {
+                return false;
+            }
+            results.push(entry);
+            true
+        },
+    );
+    results
+}
+
+// 在查询结果框中输出词条
+pub fn print_entries(entries: &[JmEntry], romanizer: &Romanizer, target_label: &str) {
+    let levels = LevelIndex::load();
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            println!("╟───────────────────────────────────────────────");
+        }
+        println!("║ Japanese (日文): {}", entry.headword());
+        if entry.kanji.len() > 1 {
+            println!("║ Other forms (其他写法): {}", entry.kanji[1..].join("、"));
+        }
+        println!("║ Kana (假名): {}", entry.kana.join("、"));
+        println!("║ Romaji (罗马音): {}", romanizer.romanize(entry.reading()));
+        accent::print_accent(entry.headword(), Some(entry.reading()));
+        levels::print_tags(levels.entry_tags(entry));
+        println!("║ {}:", target_label);
+        for (n, sense) in entry.senses.iter().enumerate() {
+            let mut labels: Vec<&str> = sense.pos.iter().filter_map(|code| Pos::from_code(code)).map(Pos::label).collect();
+            labels.dedup();
+            let prefix = if labels.is_empty() { String::new() } else { format!("[{}] ", labels.join("、")) };
+            println!("║   {}. {}{}", n + 1, prefix, sense.glosses.join("; "));
+        }
+        println!("║ 📚 来自 JMdict{}", if entry.is_common() { "（常用词）" } else { "" });
+    }
+}
+
+pub fn jmdict_path() -> PathBuf {
+    storage::data_file(JMDICT_FILE)
+}
+
+fn index_path() -> PathBuf {
+    storage::data_file(INDEX_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, kanji: &[&str], kana: &[&str]) -> JmEntry {
+        JmEntry {
+            id,
+            kanji: kanji.iter().map(|k| k.to_string()).collect(),
+            kana: kana.iter().map(|k| k.to_string()).collect(),
+            priority: Vec::new(),
+            senses: vec![JmSense { pos: Vec::new(), glosses: vec![format!("gloss {}", id)] }],
+        }
+    }
+
+    #[test]
+    fn index_finds_every_form() {
+        let dir = std::env::temp_dir().join(format!("jp-jmdict-index-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join(JMDICT_FILE);
+        let index = dir.join(INDEX_FILE);
+        let entries = [
+            entry(1, &["学生"], &["がくせい"]),
+            entry(2, &["橋"], &["はし"]),
+            entry(3, &["箸"], &["はし"]),
+            entry(4, &[], &["テレビ"]),
+            entry(5, &["書く"], &["かく"]),
+        ];
+        let lines: Vec<String> = entries.iter().map(|e| serde_json::to_string(e).unwrap()).collect();
+        fs::write(&source, lines.join("\n") + "\n").unwrap();
+        build_index(&source, &index).unwrap();
+
+        let ids = |key: &str| -> Vec<u32> {
+            let content = fs::read_to_string(&source).unwrap();
+            index_offsets(&index, key)
+                .unwrap()
+                .into_iter()
+                .map(|offset| serde_json::from_str::<JmEntry>(content[offset as usize..].lines().next().unwrap()).unwrap().id)
+                .collect()
+        };
+        assert_eq!(ids("学生"), [1]);
+        assert_eq!(ids("がくせい"), [1]);
+        assert_eq!(ids("はし"), [2, 3]);
+        assert_eq!(ids("てれび"), [4]);
+        assert_eq!(ids("書く"), [5]);
+        assert!(ids("かか").is_empty());
+        assert!(ids("ん").is_empty());
+        assert!(ids("あ").is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+// 假名工具：平假名/片假名转换和罗马音转换。
+// 罗马音以 init_romaji_map 的五十音表为基础，推导出浊音、半浊音、拗音、促音和长音
+use std::collections::HashMap;
+
+pub fn is_hiragana(c: char) -> bool {
+    ('\u{3041}'..='\u{309F}').contains(&c)
+}
+
+pub fn is_katakana(c: char) -> bool {
+    ('\u{30A1}'..='\u{30FF}').contains(&c) || ('\u{31F0}'..='\u{31FF}').contains(&c)
+}
+
+// 汉字（含々）
+pub fn is_kanji(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c) || c == '々' || c == '〆'
+}
+
+// 片假名转平假名（长音符号 ー 保留）
+pub fn to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+pub struct Romanizer {
+    table: HashMap<char, String>,
+}
+
+// 五十音表的一项（main.rs 的 JapaneseChar）；这样桌面端也能单独引用本模块的转换函数
+pub trait KanaRow {
+    fn hiragana(&self) -> &str;
+    fn romaji(&self) -> &str;
+}
+
+// 小写假名对应的元音，如 ゃ → a
+fn small_vowel(c: char) -> Option<&'static str> {
+    match c {
+        'ぁ' | 'ゃ' | 'ゎ' => Some("a"),
+        'ぃ' => Some("i"),
+        'ぅ' | 'ゅ' => Some("u"),
+        'ぇ' => Some("e"),
+        'ぉ' | 'ょ' => Some("o"),
+        _ => None,
+    }
+}
+
+impl Romanizer {
+    pub fn new<T: KanaRow>(romaji_map: &HashMap<String, T>) -> Self {
+        let mut table = HashMap::new();
+        for jp_char in romaji_map.values() {
+            if let Some(c) = jp_char.hiragana().chars().next() {
+                table.insert(c, jp_char.romaji().to_string());
+            }
+        }
+
+        // 浊音和半浊音在 Unicode 中紧跟清音：か(304B) が(304C)、は(306F) ば(3070) ぱ(3071)
+        let mut voiced = Vec::new();
+        for (&c, romaji) in &table {
+            let dakuten = match romaji.as_str() {
+                "shi" => Some("ji".to_string()),
+                "chi" => Some("ji".to_string()),
+                "tsu" => Some("zu".to_string()),
+                "fu" => Some("bu".to_string()),
+                r if r.starts_with('k') => Some(format!("g{}", &r[1..])),
+                r if r.starts_with('s') => Some(format!("z{}", &r[1..])),
+                r if r.starts_with('t') => Some(format!("d{}", &r[1..])),
+                r if r.starts_with('h') => Some(format!("b{}", &r[1..])),
+                _ => None,
+            };
+            if let Some(dakuten) = dakuten {
+                if let Some(v) = char::from_u32(c as u32 + 1) {
+                    voiced.push((v, dakuten));
+                }
+            }
+            if romaji.starts_with('h') || romaji == "fu" {
+                if let Some(p) = char::from_u32(c as u32 + 2) {
+                    voiced.push((p, format!("p{}", romaji.trim_start_matches(['h', 'f']))));
+                }
+            }
+        }
+        table.extend(voiced);
+        table.insert('ゔ', "vu".to_string());
+
+        Romanizer { table }
+    }
+
+    // 假名转罗马音；非假名字符原样保留
+    pub fn romanize(&self, kana: &str) -> String {
+        let chars: Vec<char> = to_hiragana(kana).chars().collect();
+        let mut result = String::new();
+        let mut geminate = false;
+
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            i += 1;
+
+            if c == 'っ' {
+                geminate = true;
+                continue;
+            }
+            if c == 'ー' {
+                if let Some(vowel) = result.chars().rev().find(|c| "aeiou".contains(*c)) {
+                    result.push(vowel);
+                }
+                continue;
+            }
+
+            let Some(base) = self.table.get(&c) else {
+                geminate = false;
+                result.push(c);
+                continue;
+            };
+            let mut syllable = base.clone();
+
+            // 拗音和外来语的小写假名：き+ゃ → kya，し+ゃ → sha，ふ+ぁ → fa，て+ぃ → ti
+            if let Some(vowel) = chars.get(i).copied().and_then(small_vowel) {
+                let is_youon = matches!(chars[i], 'ゃ' | 'ゅ' | 'ょ');
+                syllable = if is_youon && syllable.ends_with('i') && syllable.len() > 1 {
+                    let stem = &syllable[..syllable.len() - 1];
+                    if stem.ends_with("sh") || stem.ends_with("ch") || stem.ends_with('j') {
+                        format!("{}{}", stem, vowel)
+                    } else {
+                        format!("{}y{}", stem, vowel)
+                    }
+                } else if syllable == "u" {
+                    format!("w{}", vowel)
+                } else {
+                    format!("{}{}", &syllable[..syllable.len() - 1], vowel)
+                };
+                i += 1;
+            }
+
+            if syllable == "n" {
+                // ん 后面是元音或 y 时加撇号区分：kin'en
+                let next = chars.get(i).and_then(|c| self.table.get(c));
+                if next.map(|r| r.starts_with(['a', 'i', 'u', 'e', 'o', 'y'])).unwrap_or(false) {
+                    syllable = "n'".to_string();
+                }
+            }
+
+            if geminate {
+                if syllable.starts_with("ch") {
+                    result.push('t');
+                } else if let Some(first) = syllable.chars().next().filter(|c| !"aeiou".contains(*c)) {
+                    result.push(first);
+                }
+                geminate = false;
+            }
+            result.push_str(&syllable);
+        }
+        result
+    }
+}
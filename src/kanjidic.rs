@@ -0,0 +1,299 @@
+// KANJIDIC2 汉字字典：把 kanjidic2.xml 导入为数据目录下的 kanjidic.jsonl，每行一个汉字，
+// 包含音读、训读、英文释义、笔画数、部首、年级、JLPT 等级和使用频率，用于 jp kanji 的汉字卡片
+use crate::cedict;
+use crate::glyph;
+use crate::hanzi;
+use crate::jmdict;
+use crate::kana::{self, Romanizer};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const KANJIDIC_FILE: &str = "kanjidic.jsonl";
+
+// 康熙部首，按部首编号排列（KANJIDIC2 的 classical 部首编号 1-214）
+const KANGXI_RADICALS: &str = "\
+一丨丶丿乙亅二亠人儿入八冂冖冫几凵刀力勹匕匚匸十卜卩厂厶又口囗土士夂夊夕大女子宀寸小尢尸屮山巛工己巾干幺广廴廾弋弓彐彡彳\
+心戈戶手支攴文斗斤方无日曰月木欠止歹殳毋比毛氏气水火爪父爻爿片牙牛犬玄玉瓜瓦甘生用田疋疒癶白皮皿目矛矢石示禸禾穴立\
+竹米糸缶网羊羽老而耒耳聿肉臣自至臼舌舛舟艮色艸虍虫血行衣襾見角言谷豆豕豸貝赤走足身車辛辰辵邑酉釆里金長門阜隶隹雨青非\
+面革韋韭音頁風飛食首香馬骨高髟鬥鬯鬲鬼魚鳥鹵鹿麥麻黃黍黑黹黽鼎鼓鼠鼻齊齒龍龜龠";
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct KanjiEntry {
+    #[serde(rename = "c")]
+    pub literal: String,
+    #[serde(rename = "sc")]
+    pub strokes: u8,
+    // 康熙部首编号
+    #[serde(rename = "rad")]
+    pub radical: u8,
+    // 1-6 小学年级，8 中学常用汉字，9-10 人名用汉字
+    #[serde(rename = "gr", default, skip_serializing_if = "Option::is_none")]
+    pub grade: Option<u8>,
+    // 旧 JLPT 等级（4 最简单，1 最难）
+    #[serde(rename = "jlpt", default, skip_serializing_if = "Option::is_none")]
+    pub jlpt: Option<u8>,
+    // 报纸中的使用频率排名（1-2500）
+    #[serde(rename = "f", default, skip_serializing_if = "Option::is_none")]
+    pub freq: Option<u32>,
+    #[serde(default)]
+    pub on: Vec<String>,
+    #[serde(default)]
+    pub kun: Vec<String>,
+    // 名字中的读音
+    #[serde(rename = "na", default, skip_serializing_if = "Vec::is_empty")]
+    pub nanori: Vec<String>,
+    #[serde(rename = "m", default)]
+    pub meanings: Vec<String>,
+    // 数字声调的拼音
+    #[serde(rename = "py", default, skip_serializing_if = "Vec::is_empty")]
+    pub pinyin: Vec<String>,
+}
+
+impl KanjiEntry {
+    pub fn radical_char(&self) -> Option<char> {
+        radical_char(self.radical)
+    }
+
+    pub fn grade_label(&self) -> Option<String> {
+        let label = match self.grade? {
+            g @ 1..=6 => format!("小学 {} 年级（教育汉字）", g),
+            8 => "中学（常用汉字）".to_string(),
+            9 | 10 => "人名用汉字".to_string(),
+            g => g.to_string(),
+        };
+        Some(label)
+    }
+
+    // KANJIDIC2 使用 2010 年以前的四级 JLPT，旧 2 级对应现在的 N3 和 N2
+    pub fn jlpt_label(&self) -> Option<String> {
+        let level = self.jlpt?;
+        let current = match level {
+            4 => "N5",
+            3 => "N4",
+            2 => "N3/N2",
+            _ => "N1",
+        };
+        Some(format!("旧 {} 级（≈ {}）", level, current))
+    }
+}
+
+pub fn radical_char(number: u8) -> Option<char> {
+    KANGXI_RADICALS.chars().nth(usize::from(number).checked_sub(1)?)
+}
+
+fn unescape(text: &str) -> String {
+    text.trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// 解析 KANJIDIC2 XML；只保留英文释义，笔画数有多个时取第一个（其余是常见的误数）
+fn parse_xml(content: &str) -> Vec<KanjiEntry> {
+    let mut entries = Vec::new();
+    let mut entry = KanjiEntry::default();
+
+    // 跳过 DTD
+    let mut rest = content.find("<kanjidic2>").map(|i| &content[i..]).unwrap_or(content);
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+        let (name, attrs) = tag.split_once(' ').unwrap_or((tag, ""));
+        let text = || unescape(&rest[..rest.find('<').unwrap_or(rest.len())]);
+
+        match name {
+            "character" => entry = KanjiEntry::default(),
+            "/character" if !entry.literal.is_empty() => entries.push(std::mem::take(&mut entry)),
+            "literal" => entry.literal = text(),
+            "rad_value" if attrs.contains("\"classical\"") => entry.radical = text().parse().unwrap_or(0),
+            "grade" => entry.grade = text().parse().ok(),
+            "stroke_count" if entry.strokes == 0 => entry.strokes = text().parse().unwrap_or(0),
+            "freq" => entry.freq = text().parse().ok(),
+            "jlpt" => entry.jlpt = text().parse().ok(),
+            "reading" if attrs.contains("\"ja_on\"") => entry.on.push(text()),
+            "reading" if attrs.contains("\"ja_kun\"") => entry.kun.push(text()),
+            "reading" if attrs.contains("\"pinyin\"") => entry.pinyin.push(text()),
+            "nanori" => entry.nanori.push(text()),
+            "meaning" if !attrs.contains("m_lang") => entry.meanings.push(text()),
+            _ => {}
+        }
+    }
+    entries
+}
+
+// 导入 KANJIDIC2 XML，替换已导入的字典，返回汉字数
+pub fn import(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let entries = parse_xml(&content);
+    if entries.is_empty() {
+        return Err(format!("no KANJIDIC2 characters found in {}", path.display()).into());
+    }
+
+    let target = kanjidic_path();
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = target.with_extension("jsonl.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in &entries {
+        writeln!(writer, "{}", serde_json::to_string(entry)?)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, &target)?;
+    Ok(entries.len())
+}
+
+pub fn is_imported() -> bool {
+    kanjidic_path().exists()
+}
+
+pub fn lookup(kanji: char) -> Option<KanjiEntry> {
+    let file = File::open(kanjidic_path()).ok()?;
+    let needle = format!("\"c\":{}", serde_json::to_string(&kanji.to_string()).ok()?);
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| line.contains(&needle))
+        .filter_map(|line| serde_json::from_str::<KanjiEntry>(&line).ok())
+        .find(|entry| entry.literal.starts_with(kanji))
+}
+
+// 全部汉字，用于按笔画数排序和按释义查找
+pub fn entries() -> Vec<KanjiEntry> {
+    let Ok(file) = File::open(kanjidic_path()) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<KanjiEntry>(&line).ok())
+        .collect()
+}
+
+pub fn kanjidic_path() -> std::path::PathBuf {
+    storage::data_file(KANJIDIC_FILE)
+}
+
+// JMdict 中包含该汉字的常用词
+fn example_words(kanji: char, limit: usize) -> Vec<jmdict::JmEntry> {
+    let mut words = Vec::new();
+    jmdict::scan(
+        |line| line.contains(kanji),
+        |entry| {
+            if entry.is_common() && entry.headword().contains(kanji) && entry.headword().chars().count() > 1 {
+                words.push(entry);
+            }
+            words.len() < limit
+        },
+    );
+    words
+}
+
+// 训读中的 "." 是送假名的分界，"-" 表示词头或词尾；罗马音中去掉
+fn reading_romaji(reading: &str, romanizer: &Romanizer) -> String {
+    let kana: String = reading.chars().filter(|c| !matches!(c, '.' | '-')).collect();
+    romanizer.romanize(&kana::to_hiragana(&kana))
+}
+
+// 和假名单词卡相同样式的汉字卡片；input 是用户输入的字（简体或繁体时显示字形对照）
+pub fn print_card(entry: &KanjiEntry, input: &str, romanizer: &Romanizer) {
+    const INNER_WIDTH: usize = 85;
+    fn emit(text: &str) {
+        let w = UnicodeWidthStr::width(text);
+        let pad = INNER_WIDTH.saturating_sub(w);
+        println!("║{}{}║", text, " ".repeat(pad));
+    }
+    fn emit_center(text: &str) {
+        let w = UnicodeWidthStr::width(text);
+        let left = INNER_WIDTH.saturating_sub(w) / 2;
+        let right = INNER_WIDTH.saturating_sub(w + left);
+        println!("║{}{}{}║", " ".repeat(left), text, " ".repeat(right));
+    }
+    fn readings(list: &[String], romanizer: &Romanizer) -> String {
+        let items: Vec<String> = list.iter().map(|r| format!("{} ({})", r, reading_romaji(r, romanizer))).collect();
+        if items.is_empty() {
+            "-".to_string()
+        } else {
+            items.join("、")
+        }
+    }
+    const RULE: &str = "╠═════════════════════════════════════════════════════════════════════════════════════╣";
+
+    println!("\n╔═════════════════════════════════════════════════════════════════════════════════════╗");
+    emit_center("JAPANESE KANJI FLASHCARD");
+    println!("{}", RULE);
+    emit("");
+    emit(&format!("   Kanji (汉字): {}        Strokes (笔画): {}", entry.literal, entry.strokes));
+    emit("");
+    emit(&format!("   On'yomi (音读): {}", readings(&entry.on, romanizer)));
+    emit(&format!("   Kun'yomi (训读): {}", readings(&entry.kun, romanizer)));
+    if !entry.nanori.is_empty() {
+        emit(&format!("   Nanori (名字读音): {}", entry.nanori.join("、")));
+    }
+    emit(&format!("   Meanings (意思): {}", entry.meanings.join("; ")));
+    emit("");
+    let radical = match entry.radical_char() {
+        Some(c) => format!("{} ({})", c, entry.radical),
+        None => "-".to_string(),
+    };
+    emit(&format!("   Radical (部首): {}", radical));
+    emit(&format!("   Grade (年级): {}", entry.grade_label().unwrap_or_else(|| "-".to_string())));
+    emit(&format!("   JLPT: {}", entry.jlpt_label().unwrap_or_else(|| "-".to_string())));
+    let freq = entry.freq.map(|f| format!("第 {} 位（报纸用字）", f)).unwrap_or_else(|| "-".to_string());
+    emit(&format!("   Frequency (频率): {}", freq));
+    if !entry.pinyin.is_empty() {
+        let pinyin: Vec<String> = entry.pinyin.iter().map(|p| cedict::pinyin_marks(p)).collect();
+        emit(&format!("   Pinyin (拼音): {}", pinyin.join(" / ")));
+    }
+    if let Some(comparison) = hanzi::comparison_text(input) {
+        emit(&format!("   Kanji (字形对照): {}", comparison));
+    }
+    emit("");
+
+    let literal = entry.literal.chars().next().unwrap_or_default();
+    println!("{}", RULE);
+    emit_center("汉字 (Kanji) ASCII Art");
+    println!("{}", RULE);
+    match glyph::render(literal) {
+        Some(art) => {
+            for line in art {
+                emit(&format!(" {}", line));
+            }
+        }
+        None => {
+            emit("");
+            emit_center(&entry.literal);
+            emit("");
+            emit_center("（没有找到日文字体，可以用 JP_KANJI_FONT 指定字体文件）");
+            emit("");
+        }
+    }
+
+    let words = example_words(literal, 3);
+    if !words.is_empty() {
+        println!("{}", RULE);
+        emit("  Example Words (例词):");
+        for (i, word) in words.iter().enumerate() {
+            let gloss = word.senses.first().and_then(|s| s.glosses.first()).map(String::as_str).unwrap_or("");
+            emit(&format!(
+                "  {}. {}（{}/{}）- {}",
+                i + 1,
+                word.headword(),
+                word.reading(),
+                romanizer.romanize(word.reading()),
+                gloss
+            ));
+        }
+    }
+    println!("╚═════════════════════════════════════════════════════════════════════════════════════╝\n");
+}
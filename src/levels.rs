@@ -0,0 +1,355 @@
+// JLPT 等级和词频：内置常用词的等级，导入开放的 JLPT 词表（jlpt.jsonl）和词频表（frequency.jsonl），
+// 为假名例词、内置中文字典、JMdict 词条和复习卡片标注 "N5 · #123"，并用于 jp quiz / jp review 的范围筛选
+use crate::jmdict::{self, JmEntry};
+use crate::kana;
+use crate::storage;
+use crate::JlptLevel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+const JLPT_FILE: &str = "jlpt.jsonl";
+const FREQUENCY_FILE: &str = "frequency.jsonl";
+
+// 内置的等级：假名卡片例词和内置中文字典中的词，没有导入 JLPT 词表时也能标注和筛选；导入的词表优先
+const BUILTIN_JLPT: &[(JlptLevel, &str)] = &[
+    (
+        JlptLevel::N5,
+        "赤 朝 犬 家 海 歌 絵 駅 円 男 女 川 家族 木 黄色 昨日 靴 口 子供 声 魚 寒い 白 好き 静か 先生 背 空 外 食べる \
+         誕生日 高い 父 小さい 月 机 強い 手 天気 手紙 友達 鳥 時計 名前 夏 長い 日本 肉 猫 飲む 花 春 母 火 人 低い 冬 \
+         古い 部屋 本 欲しい 窓 毎日 水 耳 道 難しい 目 山 安い 雪 夜 四 良い 来月 六 昼 廊下 私 若い 悪い 消しゴム \
+         こんにちは おはよう こんばんは ありがとう すみません さようなら はい いいえ お願いします 日 勉強 学校 学生 \
+         一 二 三 五 七 八 九 十 兄 姉 弟 妹 青 黒 秋 月曜日 火曜日 水曜日 木曜日 金曜日 土曜日 日曜日 \
+         本を読む 水を飲む",
+    ),
+    (
+        JlptLevel::N4,
+        "音 雲 景色 心 世界 祖父 眠い 値段 野菜 星 船 村 森 林 文字 夢 指 理由 料理 例 歴史 簡単 楽 石 塩 緑 寿司 ラーメン",
+    ),
+    (
+        JlptLevel::N3,
+        "愛 馬 鏡 毛 桜 砂 血 虹 布 塗る 温い 減る 街 紫 飯 桃 留守 ルール 冷蔵庫 天 地 蛇 喉 ロボット",
+    ),
+    (JlptLevel::N2, "姪"),
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JlptWord {
+    #[serde(rename = "w")]
+    pub word: String,
+    #[serde(rename = "r", default)]
+    pub reading: String,
+    #[serde(rename = "m", default)]
+    pub meaning: String,
+    #[serde(rename = "l")]
+    pub level: JlptLevel,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FrequencyRank {
+    #[serde(rename = "w")]
+    word: String,
+    #[serde(rename = "n")]
+    rank: u32,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tags {
+    pub jlpt: Option<JlptLevel>,
+    // 词频排名，1 最常用
+    pub rank: Option<u32>,
+}
+
+impl Tags {
+    pub fn is_empty(&self) -> bool {
+        self.jlpt.is_none() && self.rank.is_none()
+    }
+
+    // 是否在筛选范围内：--jlpt N4 包含 N5 和 N4 的词，--max-rank 2000 只要前 2000 个常用词
+    pub fn within(&self, jlpt: Option<JlptLevel>, max_rank: Option<u32>) -> bool {
+        let level_ok = jlpt.is_none_or(|max| self.jlpt.is_some_and(|level| level <= max));
+        let rank_ok = max_rank.is_none_or(|max| self.rank.is_some_and(|rank| rank <= max));
+        level_ok && rank_ok
+    }
+}
+
+impl std::fmt::Display for Tags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(level) = self.jlpt {
+            parts.push(level.to_string());
+        }
+        if let Some(rank) = self.rank {
+            parts.push(format!("#{}", rank));
+        }
+        write!(f, "{}", parts.join(" · "))
+    }
+}
+
+// JMdict 的 nf01-nf48 标记：按报纸语料的词频每 500 个词一组，取组内的第一名作为近似排名
+pub fn jmdict_rank(entry: &JmEntry) -> Option<u32> {
+    entry
+        .priority
+        .iter()
+        .filter_map(|p| p.strip_prefix("nf")?.parse::<u32>().ok())
+        .min()
+        .map(|group| (group - 1) * 500 + 1)
+}
+
+#[derive(Default)]
+pub struct LevelIndex {
+    jlpt: HashMap<String, JlptLevel>,
+    ranks: HashMap<String, u32>,
+    // 词频表中没有的词在 JMdict 中查 nf 标记的近似排名
+    jmdict_ranks: bool,
+}
+
+impl LevelIndex {
+    pub fn load() -> Self {
+        let mut index = LevelIndex::default();
+        for word in jlpt_words() {
+            // 同一个词出现在多个等级时取较简单的等级
+            for key in [word.word.clone(), word.reading.clone()] {
+                if key.is_empty() {
+                    continue;
+                }
+                let level = index.jlpt.entry(key).or_insert(word.level);
+                *level = (*level).min(word.level);
+            }
+        }
+        index.add_builtin();
+        index.jmdict_ranks = jmdict::is_imported();
+        if let Ok(file) = File::open(storage::data_file(FREQUENCY_FILE)) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                if let Ok(entry) = serde_json::from_str::<FrequencyRank>(&line) {
+                    index.ranks.entry(entry.word).or_insert(entry.rank);
+                }
+            }
+        }
+        index
+    }
+
+    // 内置等级只补充导入的词表中没有的词
+    fn add_builtin(&mut self) {
+        for &(level, words) in BUILTIN_JLPT {
+            for word in words.split_whitespace() {
+                self.jlpt.entry(word.to_string()).or_insert(level);
+            }
+        }
+    }
+
+    // 按写法查询；只有假名的词也按读音查询（有汉字时不用读音，避免同音词混淆）；
+    // 词频表中没有时使用 JMdict 的近似排名
+    pub fn tags(&self, word: &str, reading: Option<&str>) -> Tags {
+        let mut tags = self.imported_tags(word, reading);
+        if tags.rank.is_none() {
+            tags.rank = self.jmdict_rank_of(word, reading);
+        }
+        tags
+    }
+
+    fn imported_tags(&self, word: &str, reading: Option<&str>) -> Tags {
+        let kana_reading = reading.filter(|_| word.chars().all(|c| !kana::is_kanji(c)));
+        let mut keys = std::iter::once(word).chain(kana_reading);
+        Tags {
+            jlpt: keys.clone().find_map(|k| self.jlpt.get(k).copied()),
+            rank: keys.find_map(|k| self.ranks.get(k).copied()),
+        }
+    }
+
+    // 写法（和读音）都相同的 JMdict 词条的近似排名
+    fn jmdict_rank_of(&self, word: &str, reading: Option<&str>) -> Option<u32> {
+        if !self.jmdict_ranks {
+            return None;
+        }
+        let reading = reading.map(kana::to_hiragana);
+        jmdict::lookup(word)
+            .iter()
+            .filter(|e| reading.as_ref().is_none_or(|r| e.kana.iter().any(|k| kana::to_hiragana(k) == *r)))
+            .find_map(jmdict_rank)
+    }
+
+    // JMdict 词条：词频表中没有时直接使用该词条 nf 标记的近似排名
+    pub fn entry_tags(&self, entry: &JmEntry) -> Tags {
+        let mut tags = self.imported_tags(entry.headword(), Some(entry.reading()));
+        if tags.rank.is_none() {
+            tags.rank = jmdict_rank(entry);
+        }
+        tags
+    }
+}
+
+// 在查询结果框中输出等级和词频（没有标注时不输出）
+pub fn print_tags(tags: Tags) {
+    if !tags.is_empty() {
+        println!("║ Level (等级/词频): {}", tags);
+    }
+}
+
+// 已导入的 JLPT 词表
+pub fn jlpt_words() -> Vec<JlptWord> {
+    let Ok(file) = File::open(storage::data_file(JLPT_FILE)) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<JlptWord>(&line).ok())
+        .collect()
+}
+
+// 文本中的 JLPT 等级：N5、JLPT_N4、"n3" 等
+fn find_level(text: &str) -> Option<JlptLevel> {
+    let upper = text.to_uppercase();
+    let bytes = upper.as_bytes();
+    (0..bytes.len().saturating_sub(1)).find_map(|i| {
+        let boundary = i == 0 || !bytes[i - 1].is_ascii_alphabetic();
+        let after = bytes.get(i + 2).is_none_or(|b| !b.is_ascii_digit());
+        if bytes[i] != b'N' || !boundary || !after {
+            return None;
+        }
+        match bytes[i + 1] {
+            b'5' => Some(JlptLevel::N5),
+            b'4' => Some(JlptLevel::N4),
+            b'3' => Some(JlptLevel::N3),
+            b'2' => Some(JlptLevel::N2),
+            b'1' => Some(JlptLevel::N1),
+            _ => None,
+        }
+    })
+}
+
+// 导入 JLPT 词表（CSV 或 TSV：写法、读音、释义，等级写在某一列如 "JLPT_N5"，或者在文件名中如 n5.csv），
+// 和已导入的词表合并，返回本次导入的词数
+pub fn import_jlpt(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file_level = path.file_stem().and_then(|s| find_level(&s.to_string_lossy()));
+    let mut imported: Vec<JlptWord> = Vec::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let separator = if line.contains('\t') { '\t' } else { ',' };
+        let fields: Vec<&str> = line.split(separator).map(|f| f.trim().trim_matches('"')).collect();
+        let word = fields[0];
+        // 跳过表头和空行
+        if word.is_empty() || word.is_ascii() {
+            continue;
+        }
+        let Some(level) = fields[1..].iter().find_map(|f| find_level(f)).or(file_level) else {
+            continue;
+        };
+        let reading = fields.get(1).filter(|r| !r.is_ascii()).map(|r| kana::to_hiragana(r)).unwrap_or_default();
+        let meaning = fields.get(2).filter(|m| find_level(m).is_none()).copied().unwrap_or_default();
+        imported.push(JlptWord {
+            word: word.to_string(),
+            reading,
+            meaning: meaning.to_string(),
+            level,
+        });
+    }
+    if imported.is_empty() {
+        return Err(format!(
+            "no JLPT words found in {} (expected word,reading,meaning with a level column or a file name like n5.csv)",
+            path.display()
+        )
+        .into());
+    }
+
+    // 重新导入同一个等级时替换旧的词
+    let levels: Vec<JlptLevel> = imported.iter().map(|w| w.level).collect();
+    let mut words: Vec<JlptWord> = jlpt_words().into_iter().filter(|w| !levels.contains(&w.level)).collect();
+    let count = imported.len();
+    words.extend(imported);
+    words.sort_by_key(|w| w.level);
+    write_jsonl(JLPT_FILE, &words)?;
+    Ok(count)
+}
+
+// 导入词频表：每行 "词<TAB>排名"、"词,排名" 或只有词（按行号排名），替换已导入的词频表
+pub fn import_frequency(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut entries: Vec<FrequencyRank> = Vec::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(['\t', ',']).map(str::trim);
+        let word = fields.next().unwrap_or_default();
+        if word.is_ascii() {
+            continue;
+        }
+        let rank = fields.find_map(|f| f.parse::<u32>().ok()).unwrap_or(entries.len() as u32 + 1);
+        entries.push(FrequencyRank { word: word.to_string(), rank });
+    }
+    if entries.is_empty() {
+        return Err(format!("no words found in {}", path.display()).into());
+    }
+    entries.sort_by_key(|e| e.rank);
+    write_jsonl(FREQUENCY_FILE, &entries)?;
+    Ok(entries.len())
+}
+
+fn write_jsonl<T: Serialize>(name: &str, entries: &[T]) -> Result<(), Box<dyn std::error::Error>> {
+    let target = storage::data_file(name);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = target.with_extension("jsonl.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in entries {
+        writeln!(writer, "{}", serde_json::to_string(entry)?)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, &target)?;
+    Ok(())
+}
+
+pub fn jlpt_path() -> std::path::PathBuf {
+    storage::data_file(JLPT_FILE)
+}
+
+pub fn frequency_path() -> std::path::PathBuf {
+    storage::data_file(FREQUENCY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_words_have_levels() {
+        let words: Vec<&str> = BUILTIN_JLPT.iter().flat_map(|(_, words)| words.split_whitespace()).collect();
+        let unique: std::collections::HashSet<&&str> = words.iter().collect();
+        assert_eq!(unique.len(), words.len(), "a word is listed under two levels");
+
+        let mut index = LevelIndex::default();
+        index.add_builtin();
+        assert_eq!(index.tags("水", None).jlpt, Some(JlptLevel::N5));
+        assert_eq!(index.tags("こんにちは", None).jlpt, Some(JlptLevel::N5));
+        assert_eq!(index.tags("歴史", None).jlpt, Some(JlptLevel::N4));
+        assert!(index.tags("水", None).within(Some(JlptLevel::N5), None));
+        assert!(!index.tags("桜", None).within(Some(JlptLevel::N4), None));
+    }
+
+    #[test]
+    fn kana_card_examples_have_levels() {
+        let mut index = LevelIndex::default();
+        index.add_builtin();
+        let mut missing: Vec<&str> = Vec::new();
+        let romaji_map = crate::init_romaji_map();
+        for jp_char in romaji_map.values() {
+            for example in &jp_char.examples {
+                let Some((left, _)) = example.split_once(" - ") else {
+                    continue;
+                };
+                let (word, kana) = crate::split_japanese_entry(left);
+                if index.tags(word, kana.first().copied()).jlpt.is_none() {
+                    missing.push(word);
+                }
+            }
+        }
+        assert!(missing.is_empty(), "kana card examples without a level: {:?}", missing);
+    }
+}
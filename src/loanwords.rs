@@ -0,0 +1,484 @@
+// 外来语：英文单词的标准片假名写法（内置外来语词典 → 导入的 JMdict），
+// 查不到时按拼写规则和外来语用的扩展片假名（ティ、ファ、ヴァ 等）生成近似音译
+use crate::jmdict::{self, JmEntry};
+use crate::kana::{self, Romanizer};
+
+// 常用外来语：英文、片假名、中文
+const LOANWORDS: &str = "\
+computer	コンピューター	电脑
+internet	インターネット	互联网
+smartphone	スマートフォン	智能手机
+television	テレビ	电视
+camera	カメラ	相机
+email	メール	电子邮件
+mail	メール	邮件
+password	パスワード	密码
+keyboard	キーボード	键盘
+mouse	マウス	鼠标
+software	ソフトウェア	软件
+printer	プリンター	打印机
+file	ファイル	文件
+data	データ	数据
+download	ダウンロード	下载
+game	ゲーム	游戏
+music	ミュージック	音乐
+radio	ラジオ	收音机
+video	ビデオ	视频
+news	ニュース	新闻
+coffee	コーヒー	咖啡
+tea	ティー	茶
+milk	ミルク	牛奶
+juice	ジュース	果汁
+beer	ビール	啤酒
+wine	ワイン	葡萄酒
+water	ウォーター	水
+bread	パン	面包
+butter	バター	黄油
+cheese	チーズ	奶酪
+cake	ケーキ	蛋糕
+chocolate	チョコレート	巧克力
+ice cream	アイスクリーム	冰淇淋
+hamburger	ハンバーガー	汉堡
+pizza	ピザ	比萨
+salad	サラダ	沙拉
+sandwich	サンドイッチ	三明治
+tomato	トマト	西红柿
+banana	バナナ	香蕉
+lemon	レモン	柠檬
+orange	オレンジ	橙子
+menu	メニュー	菜单
+restaurant	レストラン	餐厅
+hotel	ホテル	酒店
+taxi	タクシー	出租车
+bus	バス	公交车
+train	トレイン	火车
+truck	トラック	卡车
+bicycle	バイシクル	自行车
+elevator	エレベーター	电梯
+escalator	エスカレーター	自动扶梯
+toilet	トイレ	厕所
+bed	ベッド	床
+table	テーブル	桌子
+door	ドア	门
+window	ウィンドウ	窗户
+bag	バッグ	包
+shirt	シャツ	衬衫
+skirt	スカート	裙子
+coat	コート	外套
+shoes	シューズ	鞋
+pen	ペン	笔
+notebook	ノート	笔记本
+note	ノート	笔记
+test	テスト	考试
+class	クラス	班级
+school	スクール	学校
+party	パーティー	聚会
+birthday	バースデー	生日
+present	プレゼント	礼物
+card	カード	卡片
+ticket	チケット	票
+money	マネー	钱
+shopping	ショッピング	购物
+department store	デパート	百货商店
+supermarket	スーパー	超市
+convenience store	コンビニ	便利店
+sale	セール	促销
+size	サイズ	尺寸
+service	サービス	服务
+team	チーム	队
+sport	スポーツ	运动
+soccer	サッカー	足球
+football	フットボール	足球
+tennis	テニス	网球
+golf	ゴルフ	高尔夫
+guitar	ギター	吉他
+piano	ピアノ	钢琴
+concert	コンサート	音乐会
+movie	ムービー	电影
+drama	ドラマ	电视剧
+animation	アニメーション	动画
+anime	アニメ	动画
+design	デザイン	设计
+image	イメージ	印象
+idea	アイデア	主意
+plan	プラン	计划
+schedule	スケジュール	日程
+meeting	ミーティング	会议
+project	プロジェクト	项目
+manager	マネージャー	经理
+office	オフィス	办公室
+business	ビジネス	商务
+energy	エネルギー	能量
+health	ヘルス	健康
+diet	ダイエット	减肥
+cup	カップ	杯子
+glass	グラス	玻璃杯
+knife	ナイフ	刀
+fork	フォーク	叉子
+spoon	スプーン	勺子
+apartment	アパート	公寓
+building	ビル	大楼
+park	パーク	公园
+stress	ストレス	压力
+simple	シンプル	简单
+cute	キュート	可爱
+";
+
+pub struct Loanword {
+    pub katakana: String,
+    pub meaning: String,
+}
+
+// 内置外来语词典
+pub fn builtin(word: &str) -> Option<Loanword> {
+    let word = word.trim().to_lowercase();
+    LOANWORDS.lines().find_map(|line| {
+        let mut fields = line.split('\t');
+        (fields.next()? == word).then(|| Loanword {
+            katakana: fields.next().unwrap_or_default().to_string(),
+            meaning: fields.next().unwrap_or_default().to_string(),
+        })
+    })
+}
+
+fn is_katakana_word(text: &str) -> bool {
+    text.chars().all(|c| kana::is_katakana(c) || c == 'ー' || c == '・')
+}
+
+// JMdict 中以该英文为释义、写法是片假名的词条（外来语）
+pub fn from_jmdict(word: &str, limit: usize) -> Vec<JmEntry> {
+    if !jmdict::is_imported() {
+        return Vec::new();
+    }
+    jmdict::search_gloss(word, 50)
+        .into_iter()
+        .filter(|entry| entry.kanji.is_empty() && entry.kana.first().is_some_and(|k| is_katakana_word(k)))
+        .take(limit)
+        .collect()
+}
+
+// 拼写分析得到的音
+#[derive(Clone, PartialEq)]
+enum Sound {
+    Consonant(&'static str),
+    // 元音和是否是长音
+    Vowel(&'static str, bool),
+    // ン
+    N,
+    // ッ
+    Sokuon,
+}
+
+fn is_vowel_letter(c: char) -> bool {
+    "aeiou".contains(c)
+}
+
+// 英文拼写 → 音：处理常见的字母组合、不发音的 e、开音节的长元音和词尾的促音
+fn sounds(word: &str) -> Vec<Sound> {
+    use Sound::*;
+    let letters: Vec<char> = word.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let at = |i: usize| letters.get(i).copied().unwrap_or(' ');
+    let rest = |i: usize| letters[i.min(letters.len())..].iter().collect::<String>();
+    let vowel_at = |i: usize| is_vowel_letter(at(i));
+    // 词尾不发音的 e：前面是辅音，并且前面还有其他元音
+    let silent_e = |i: usize| {
+        let at_end = i + 1 == letters.len() || (at(i + 1) == 's' && i + 2 == letters.len());
+        i > 0 && at(i) == 'e' && at_end && !vowel_at(i - 1) && letters[..i - 1].iter().any(|&c| is_vowel_letter(c))
+    };
+    // 元音 + 单个辅音 + 不发音的 e（game、time、home、cute）
+    let magic_e = |i: usize| i + 2 < letters.len() && !vowel_at(i + 1) && at(i + 1) != 'r' && silent_e(i + 2);
+
+    let mut result: Vec<Sound> = Vec::new();
+    let mut i = 0;
+    while i < letters.len() {
+        let tail = rest(i);
+        let c = at(i);
+        let next = at(i + 1);
+        let (mut sound, len): (Vec<Sound>, usize) = if tail.starts_with("ation") {
+            (vec![Vowel("e", true), Consonant("sh"), Vowel("o", false), N], 5)
+        } else if tail.starts_with("tion") {
+            (vec![Consonant("sh"), Vowel("o", false), N], 4)
+        } else if tail.starts_with("sion") {
+            (vec![Consonant("j"), Vowel("o", false), N], 4)
+        } else if tail.starts_with("ture") {
+            (vec![Consonant("ch"), Vowel("a", true)], 4)
+        } else if tail.starts_with("igh") {
+            (vec![Vowel("a", false), Vowel("i", false)], 3)
+        } else if tail.starts_with("tch") {
+            (vec![Sokuon, Consonant("ch")], 3)
+        } else if tail.starts_with("ck") {
+            (vec![Sokuon, Consonant("k")], 2)
+        } else if tail.starts_with("ph") {
+            (vec![Consonant("f")], 2)
+        } else if tail.starts_with("th") {
+            (vec![Consonant("s")], 2)
+        } else if tail.starts_with("sh") {
+            (vec![Consonant("sh")], 2)
+        } else if tail.starts_with("ch") {
+            (vec![Consonant("ch")], 2)
+        } else if tail.starts_with("wh") {
+            (vec![Consonant("w")], 2)
+        } else if tail.starts_with("qu") {
+            (vec![Consonant("kw")], 2)
+        } else if tail.starts_with("ng") && !vowel_at(i + 2) {
+            (vec![N, Consonant("g")], 2)
+        } else if ["ee", "ea", "ie"].iter().any(|p| tail.starts_with(p)) {
+            (vec![Vowel("i", true)], 2)
+        } else if tail.starts_with("oo") {
+            (vec![Vowel("u", true)], 2)
+        } else if tail.starts_with("ou") || (tail.starts_with("ow") && i + 2 == letters.len()) {
+            (vec![Vowel("a", false), Vowel("u", false)], 2)
+        } else if ["ai", "ay", "ei"].iter().any(|p| tail.starts_with(p)) {
+            (vec![Vowel("e", false), Vowel("i", false)], 2)
+        } else if ["oa", "au", "aw"].iter().any(|p| tail.starts_with(p)) {
+            (vec![Vowel("o", true)], 2)
+        } else if tail.starts_with("oi") || tail.starts_with("oy") {
+            (vec![Vowel("o", false), Vowel("i", false)], 2)
+        } else if is_vowel_letter(c) && next == 'r' && !vowel_at(i + 2) {
+            // er、ir、ur、ar → アー，or → オー
+            let vowel = if c == 'o' { "o" } else { "a" };
+            (vec![Vowel(vowel, true)], 2)
+        } else if is_vowel_letter(c) {
+            if silent_e(i) {
+                (Vec::new(), 1)
+            } else if magic_e(i) {
+                let sound = match c {
+                    'a' => vec![Vowel("e", true)],
+                    'i' => vec![Vowel("a", false), Vowel("i", false)],
+                    'o' => vec![Vowel("o", true)],
+                    'u' => vec![Vowel("yu", true)],
+                    _ => vec![Vowel("i", true)],
+                };
+                (sound, 1)
+            } else if c == 'u' && !vowel_at(i + 1) && vowel_at(i + 2) && i > 0 {
+                // 开音节的 u：computer → ピュー
+                (vec![Vowel("yu", true)], 1)
+            } else {
+                let last = i + 1 == letters.len();
+                let vowel = match c {
+                    'a' => "a",
+                    'e' => "e",
+                    'i' => "i",
+                    'o' => "o",
+                    _ if last => "u",
+                    _ => "a",
+                };
+                // 词尾的 i 读长音（taxi → タクシー）
+                (vec![Vowel(vowel, last && c == 'i')], 1)
+            }
+        } else if c == 'y' {
+            if i == 0 || vowel_at(i + 1) {
+                (vec![Consonant("y")], 1)
+            } else if i + 1 == letters.len() && letters.len() <= 3 {
+                (vec![Vowel("a", false), Vowel("i", false)], 1)
+            } else {
+                (vec![Vowel("i", i + 1 == letters.len())], 1)
+            }
+        } else if c == next && matches!(c, 't' | 'p' | 'k' | 'c') {
+            // tt、pp、kk、cc → ッ
+            (vec![Sokuon], 1)
+        } else if c == next {
+            (Vec::new(), 1)
+        } else {
+            let consonant = match c {
+                'b' => "b",
+                'c' if matches!(next, 'e' | 'i' | 'y') => "s",
+                'c' | 'k' | 'q' => "k",
+                'd' => "d",
+                'f' => "f",
+                'g' if matches!(next, 'e' | 'i' | 'y') && i > 0 && at(i - 1) != 'g' => "j",
+                'g' => "g",
+                'h' => "h",
+                'j' => "j",
+                'l' | 'r' => "r",
+                // m 在 b、p 前读作 ン，n 在辅音前和词尾读作 ン
+                'm' if matches!(next, 'b' | 'p') => "",
+                'm' => "m",
+                'n' if (!vowel_at(i + 1) && next != 'y') || silent_e(i + 1) => "",
+                'n' => "n",
+                'p' => "p",
+                's' => "s",
+                't' => "t",
+                'v' => "v",
+                'w' => "w",
+                'x' => "x",
+                _ => "z",
+            };
+            let sound = match consonant {
+                "" => vec![N],
+                "x" => vec![Consonant("k"), Consonant("s")],
+                consonant => vec![Consonant(consonant)],
+            };
+            (sound, 1)
+        };
+
+        // 词尾的短元音 + 塞音（bag、net、cup）加促音
+        let last_consonant = i + len == letters.len() || (i + len + 1 == letters.len() && silent_e(i + len));
+        if last_consonant
+            && matches!(sound.as_slice(), [Consonant("k" | "p" | "t" | "g" | "d" | "ch")])
+            && matches!(result.last(), Some(Vowel(_, false)))
+            && !matches!(result.get(result.len().wrapping_sub(2)), Some(Vowel(_, _)))
+        {
+            sound.insert(0, Sokuon);
+        }
+        result.extend(sound);
+        i += len;
+    }
+    result
+}
+
+// 辅音 + 元音 → 片假名（包括外来语用的 ティ、ファ、ヴァ、ウィ、シェ 等）
+fn syllable(consonant: &str, vowel: &str) -> String {
+    let index = match vowel {
+        "a" => 0,
+        "i" => 1,
+        "u" => 2,
+        "e" => 3,
+        _ => 4,
+    };
+    if vowel == "yu" {
+        let yu = match consonant {
+            "" | "y" => "ユ",
+            "k" => "キュ",
+            "g" => "ギュ",
+            "s" | "sh" => "シュ",
+            "z" | "j" => "ジュ",
+            "t" => "テュ",
+            "d" => "デュ",
+            "ch" => "チュ",
+            "n" => "ニュ",
+            "h" => "ヒュ",
+            "f" => "フュ",
+            "b" => "ビュ",
+            "p" => "ピュ",
+            "v" => "ヴュ",
+            "m" => "ミュ",
+            "r" => "リュ",
+            _ => "ユ",
+        };
+        return yu.to_string();
+    }
+    let row: [&str; 5] = match consonant {
+        "k" => ["カ", "キ", "ク", "ケ", "コ"],
+        "g" => ["ガ", "ギ", "グ", "ゲ", "ゴ"],
+        "s" => ["サ", "シ", "ス", "セ", "ソ"],
+        "z" => ["ザ", "ジ", "ズ", "ゼ", "ゾ"],
+        "t" => ["タ", "ティ", "トゥ", "テ", "ト"],
+        "d" => ["ダ", "ディ", "ドゥ", "デ", "ド"],
+        "n" => ["ナ", "ニ", "ヌ", "ネ", "ノ"],
+        "h" => ["ハ", "ヒ", "フ", "ヘ", "ホ"],
+        "f" => ["ファ", "フィ", "フ", "フェ", "フォ"],
+        "b" => ["バ", "ビ", "ブ", "ベ", "ボ"],
+        "p" => ["パ", "ピ", "プ", "ペ", "ポ"],
+        "v" => ["ヴァ", "ヴィ", "ヴ", "ヴェ", "ヴォ"],
+        "m" => ["マ", "ミ", "ム", "メ", "モ"],
+        "y" => ["ヤ", "イ", "ユ", "イェ", "ヨ"],
+        "r" => ["ラ", "リ", "ル", "レ", "ロ"],
+        "w" => ["ワ", "ウィ", "ウ", "ウェ", "ウォ"],
+        "sh" => ["シャ", "シ", "シュ", "シェ", "ショ"],
+        "ch" => ["チャ", "チ", "チュ", "チェ", "チョ"],
+        "j" => ["ジャ", "ジ", "ジュ", "ジェ", "ジョ"],
+        "kw" => ["クア", "クイ", "ク", "クエ", "クオ"],
+        _ => ["ア", "イ", "ウ", "エ", "オ"],
+    };
+    row[index].to_string()
+}
+
+// 后面没有元音的辅音补上的元音：t、d 补 o（ト、ド），ch、j 补 i（チ、ジ），其他补 u
+fn default_vowel(consonant: &str) -> &'static str {
+    match consonant {
+        "t" | "d" => "o",
+        "ch" | "j" => "i",
+        _ => "u",
+    }
+}
+
+// 按拼写规则生成的近似片假名（多个单词用 ・ 连接）
+pub fn transliterate(text: &str) -> String {
+    text.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let sounds = sounds(&word.to_lowercase());
+            let mut katakana = String::new();
+            let mut i = 0;
+            while i < sounds.len() {
+                match &sounds[i] {
+                    Sound::Consonant(consonant) => match sounds.get(i + 1) {
+                        Some(Sound::Vowel(vowel, long)) => {
+                            katakana.push_str(&syllable(consonant, vowel));
+                            if *long {
+                                katakana.push('ー');
+                            }
+                            i += 1;
+                        }
+                        _ => katakana.push_str(&syllable(consonant, default_vowel(consonant))),
+                    },
+                    Sound::Vowel(vowel, long) => {
+                        katakana.push_str(&syllable("", vowel));
+                        if *long {
+                            katakana.push('ー');
+                        }
+                    }
+                    Sound::N => katakana.push('ン'),
+                    Sound::Sokuon => {
+                        if matches!(sounds.get(i + 1), Some(Sound::Consonant(_))) && !katakana.is_empty() {
+                            katakana.push('ッ');
+                        }
+                    }
+                }
+                i += 1;
+            }
+            katakana
+        })
+        .collect::<Vec<_>>()
+        .join("・")
+}
+
+// 查到的片假名：词典里的标准写法，或者按规则生成的近似音译
+pub fn print_result(english: &str, katakana: &str, meaning: &str, source: &str, approximate: bool, romanizer: &Romanizer) {
+    let mark = if approximate { "≈ " } else { "" };
+    println!("╔═══════════════════════════════════════════════");
+    println!("║ English (英文): {}", english);
+    println!("║ Katakana (片假名): {}{}", mark, katakana);
+    println!("║ Romaji (罗马音): {}", romanizer.romanize(katakana));
+    if !meaning.is_empty() {
+        println!("║ Meaning (中文): {}", meaning);
+    }
+    println!("╟───────────────────────────────────────────────");
+    if approximate {
+        println!("║ ⚠️  词典中没有这个词，以上是按拼写规则生成的近似音译，仅供参考");
+    } else {
+        println!("║ {}", source);
+    }
+    println!("╚═══════════════════════════════════════════════");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_words() {
+        let computer = builtin("Computer").unwrap();
+        assert_eq!(computer.katakana, "コンピューター");
+        assert_eq!(computer.meaning, "电脑");
+        assert_eq!(builtin(" coffee ").unwrap().katakana, "コーヒー");
+        assert!(builtin("xylophonist").is_none());
+    }
+
+    #[test]
+    fn transliterations() {
+        for (english, katakana) in [
+            ("game", "ゲーム"),
+            ("station", "ステーション"),
+            ("jogging", "ジョギング"),
+            ("bed", "ベッド"),
+            ("test", "テスト"),
+            ("milk", "ミルク"),
+            ("cup", "カップ"),
+            ("ice cream", "アイス・クリーム"),
+        ] {
+            assert_eq!(transliterate(english), katakana, "{}", english);
+        }
+    }
+}
@@ -3,9 +3,43 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use unicode_width::UnicodeWidthStr;
-use axum::Router;
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
 use tower_http::services::ServeDir;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+
+mod accent;
+mod cache;
+mod cedict;
+mod conjugate;
+mod counters;
+mod examples;
+mod explain;
+mod glyph;
+mod hanzi;
+mod jmdict;
+mod kana;
+mod kanjidic;
+mod levels;
+mod loanwords;
+mod numbers;
+mod mock_llm;
+mod prompts;
+mod quiz;
+mod radicals;
+mod reader;
+mod review;
+mod storage;
+mod stream;
+mod tokenizer;
+mod usage;
+mod user_dict;
+
+use cache::TranslationCache;
+use user_dict::UserDictionary;
 
 #[derive(Parser)]
 #[command(name = "jp")]
@@ -14,9 +48,21 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Input text (romaji or Chinese), used when no subcommand is provided
+    /// Input text (romaji, Chinese or Japanese), used when no subcommand is provided
     #[arg(value_name = "TEXT")]
     text: Option<String>,
+
+    /// Offline mode: only use the local dictionary and the translation cache
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Save LLM translations to the user dictionary without asking
+    #[arg(long, global = true)]
+    save: bool,
+
+    /// Target language for Japanese input (kanji-only input is treated as Japanese when set)
+    #[arg(long, global = true, value_enum)]
+    to: Option<TargetLang>,
 }
 
 #[derive(Subcommand)]
@@ -31,11 +77,415 @@ enum Commands {
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
     },
-    /// Lookup romaji or translate Chinese (default command)
+    /// Lookup romaji or translate Chinese/Japanese (default command)
     Lookup {
-        /// Input text (romaji or Chinese)
+        /// Input text (romaji, Chinese or Japanese)
+        text: String,
+    },
+    /// Manage the LLM translation cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Manage the user dictionary (translations saved from the LLM)
+    Dict {
+        #[command(subcommand)]
+        action: DictAction,
+    },
+    /// Explain the grammar of a Japanese sentence with the LLM
+    Explain {
+        /// Japanese sentence
+        sentence: String,
+        /// Print the breakdown as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a Japanese sentence or text file with furigana, romaji and dictionary forms (offline)
+    Read {
+        /// Japanese sentence
+        #[arg(required_unless_present = "file")]
+        text: Option<String>,
+        /// Read a whole text file and list its new vocabulary
+        #[arg(long, conflicts_with = "text")]
+        file: Option<PathBuf>,
+        /// Output annotated HTML or Markdown with <ruby> furigana
+        #[arg(long, value_enum, conflicts_with = "json")]
+        format: Option<ReadFormat>,
+        /// Write the HTML, Markdown or JSON output to a file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Add the new words to the review deck
+        #[arg(long)]
+        add: bool,
+        /// Print the tokens as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show kanji flashcards with readings, meanings, radical and JLPT level (needs KANJIDIC2)
+    Kanji {
+        /// One or more kanji (simplified and traditional Chinese forms are converted)
         text: String,
     },
+    /// Show the pitch accent of a word as a high/low diagram (needs imported accent data)
+    Accent {
+        /// Japanese word (kanji or kana)
+        word: String,
+        /// Print the diagram as SVG
+        #[arg(long)]
+        svg: bool,
+        /// Write the SVG to a file
+        #[arg(short, long, requires = "svg")]
+        output: Option<PathBuf>,
+    },
+    /// Find kanji by their components, e.g. 氵 每 (interactive when no components are given)
+    Radicals {
+        /// Components: radicals such as 氵 亻 扌, whole kanji or English meanings
+        parts: Vec<String>,
+        /// List all known components by stroke count
+        #[arg(long, conflicts_with = "parts")]
+        list: bool,
+        /// Maximum number of kanji to show
+        #[arg(short = 'n', long, default_value = "60")]
+        limit: usize,
+        /// Print the matches as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show all conjugated forms of a verb or adjective, or drill random forms
+    Conjugate {
+        /// Verb or adjective in dictionary form, e.g. 書く, 勉強する, 高い, 静か (drills use common words when omitted)
+        #[arg(required_unless_present = "drill")]
+        word: Option<String>,
+        /// Conjugate an い- or な-adjective instead of a verb
+        #[arg(long)]
+        adj: bool,
+        /// Ask for random forms and check the answers (kana or romaji)
+        #[arg(long)]
+        drill: bool,
+        /// Number of drill questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+        /// Print the forms as JSON
+        #[arg(long, conflicts_with = "drill")]
+        json: bool,
+    },
+    /// Read a number or a price in Japanese, e.g. 1234 or 1,234円
+    Number {
+        /// Whole number, optionally with 円
+        #[arg(required_unless_present = "quiz")]
+        value: Option<String>,
+        /// Quiz: read random numbers and prices
+        #[arg(long)]
+        quiz: bool,
+        /// Number of quiz questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+    /// Read a date in Japanese, e.g. 2026-03-14 or 3月14日
+    Date {
+        /// Date as YYYY-MM-DD, YYYY/M/D, M/D or M月D日
+        #[arg(required_unless_present = "quiz")]
+        value: Option<String>,
+        /// Quiz: read random dates
+        #[arg(long)]
+        quiz: bool,
+        /// Number of quiz questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+    /// Read a time of day in Japanese, e.g. 7:30
+    Time {
+        /// Time as H:MM (24-hour) or H時M分
+        #[arg(required_unless_present = "quiz")]
+        value: Option<String>,
+        /// Quiz: read random times
+        #[arg(long)]
+        quiz: bool,
+        /// Number of quiz questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+    /// Read a number with a counter word, e.g. 本 3 → さんぼん (lists the counters without arguments)
+    Counter {
+        /// Counter (本, 枚, 匹, 人, つ …) or an object to find its counter (鉛筆, 猫)
+        counter: Option<String>,
+        /// How many (shows 1-10 when omitted)
+        number: Option<String>,
+        /// Drill: pick the right counter for an object and read the amount
+        #[arg(long, conflicts_with = "number")]
+        drill: bool,
+        /// Number of drill questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+    /// Katakana form of an English loanword, e.g. computer → コンピューター (approximate for unknown words)
+    Katakana {
+        /// English word or phrase
+        #[arg(required = true, num_args = 1..)]
+        word: Vec<String>,
+        /// Also show the rule-based approximation for words found in the dictionary
+        #[arg(long)]
+        approx: bool,
+    },
+    /// Vocabulary quiz: type the reading (kana or romaji) of words from the built-in and imported word lists
+    Quiz {
+        /// Only words up to this JLPT level, e.g. n4 includes N5 and N4 (built-in levels plus any imported JLPT list)
+        #[arg(long, value_enum, ignore_case = true)]
+        jlpt: Option<JlptLevel>,
+        /// Only words within this corpus frequency rank (needs an imported frequency list)
+        #[arg(long)]
+        max_rank: Option<u32>,
+        /// Number of questions
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+    /// Review saved words (review deck and user dictionary) and grade yourself
+    Review {
+        /// Only words up to this JLPT level
+        #[arg(long, value_enum, ignore_case = true)]
+        jlpt: Option<JlptLevel>,
+        /// Only words within this corpus frequency rank
+        #[arg(long)]
+        max_rank: Option<u32>,
+        /// Number of cards
+        #[arg(short = 'n', long, default_value = "20")]
+        count: usize,
+        /// List the cards with their level and rank instead of reviewing
+        #[arg(long)]
+        list: bool,
+    },
+    /// Generate graded example sentences for a word with the LLM
+    Examples {
+        /// Japanese word
+        word: String,
+        /// Number of sentences (1-10)
+        #[arg(short = 'n', long, default_value = "3", value_parser = clap::value_parser!(u8).range(1..=10))]
+        count: u8,
+        /// JLPT level of grammar and vocabulary
+        #[arg(long, value_enum, ignore_case = true, default_value = "n5")]
+        level: JlptLevel,
+        /// Add the sentences to a kana flashcard's example list
+        #[arg(long)]
+        attach: bool,
+        /// Romaji of the flashcard to attach to (defaults to the word's first kana)
+        #[arg(long, requires = "attach")]
+        card: Option<String>,
+        /// Ignore cached sentences and ask the LLM again
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// List, show or edit the LLM prompt templates
+    Prompts {
+        #[command(subcommand)]
+        action: PromptAction,
+    },
+    /// Show LLM token usage, cost and the monthly budget
+    Usage {
+        /// Only include calls from this period, e.g. 7d, 24h, 2w (default: this month)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Import an open dictionary dataset into the data directory
+    Import {
+        /// Dataset type
+        #[arg(value_enum)]
+        kind: ImportKind,
+        /// Path to the downloaded (uncompressed) file
+        path: PathBuf,
+    },
+    /// Start a local OpenAI-compatible server with scripted or dictionary-backed replies
+    MockLlm {
+        /// Port to listen on
+        #[arg(short, long, default_value = "8089")]
+        port: u16,
+
+        /// Host address to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// JSON file with [{"match": "...", "reply": ..., "status": 200}] rules
+        #[arg(long)]
+        script: Option<PathBuf>,
+    },
+}
+
+// JLPT 等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, clap::ValueEnum)]
+enum JlptLevel {
+    N5,
+    N4,
+    N3,
+    N2,
+    N1,
+}
+
+impl std::fmt::Display for JlptLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JlptLevel::N5 => "N5",
+            JlptLevel::N4 => "N4",
+            JlptLevel::N3 => "N3",
+            JlptLevel::N2 => "N2",
+            JlptLevel::N1 => "N1",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Subcommand)]
+enum DictAction {
+    /// List saved entries
+    List {
+        /// Only show entries that still need review
+        #[arg(long)]
+        review: bool,
+    },
+    /// Mark an entry as reviewed
+    Approve {
+        /// Chinese word of the entry
+        chinese: String,
+    },
+    /// Remove an entry
+    Remove {
+        /// Chinese word of the entry
+        chinese: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PromptAction {
+    /// List templates with their source and version
+    List,
+    /// Print the effective template
+    Show {
+        /// Template name
+        name: String,
+    },
+    /// Open the template in $EDITOR (saved to the current profile if JP_PROFILE is set)
+    Edit {
+        /// Template name
+        name: String,
+        /// Edit the global override even if JP_PROFILE is set
+        #[arg(long)]
+        global: bool,
+    },
+    /// Remove an override and go back to the previous template
+    Reset {
+        /// Template name
+        name: String,
+        /// Remove the global override even if JP_PROFILE is set
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show the number of cached translations and their age
+    Stats,
+    /// Remove cached translations
+    Clear {
+        /// Only remove entries older than JP_CACHE_TTL_DAYS
+        #[arg(long)]
+        expired: bool,
+    },
+    /// Export cached translations as JSON
+    Export {
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+// jp import 支持的数据集
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ImportKind {
+    /// JMdict XML (JMdict_e) or jmdict-simplified JSON
+    Jmdict,
+    /// CC-CEDICT (cedict_ts.u8)
+    Cedict,
+    /// KANJIDIC2 (kanjidic2.xml)
+    Kanjidic,
+    /// Pitch accent list: word<TAB>reading<TAB>accent, e.g. Kanjium accents.txt
+    Accent,
+    /// KRADFILE: components of each kanji (EUC-JP or UTF-8)
+    Kradfile,
+    /// RADKFILE: kanji for each component, with stroke counts (EUC-JP or UTF-8)
+    Radkfile,
+    /// JLPT word list: CSV/TSV word,reading,meaning with a level column or a file name like n5.csv
+    Jlpt,
+    /// Frequency list: word<TAB>rank, or one word per line in frequency order
+    Frequency,
+}
+
+// jp read 的输出格式
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ReadFormat {
+    Html,
+    #[value(alias = "md")]
+    Markdown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum TargetLang {
+    /// Chinese (中文)
+    Zh,
+    /// English (英文)
+    En,
+}
+
+// 翻译方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Direction {
+    #[default]
+    ZhJa,
+    JaZh,
+    JaEn,
+}
+
+impl Direction {
+    // 根据输入内容判断翻译方向：含假名视为日语；只有汉字时默认视为中文，指定 --to 时视为日语
+    fn detect(text: &str, to: Option<TargetLang>) -> Option<Direction> {
+        let has_kana = text.chars().any(is_kana);
+        let has_cjk = text.chars().any(|c| {
+            ('\u{4E00}'..='\u{9FFF}').contains(&c) || // CJK统一汉字
+            ('\u{3400}'..='\u{4DBF}').contains(&c)    // CJK扩展A
+        });
+
+        match (has_kana, has_cjk, to) {
+            (true, _, Some(TargetLang::En)) | (false, true, Some(TargetLang::En)) => Some(Direction::JaEn),
+            (true, _, _) | (false, true, Some(TargetLang::Zh)) => Some(Direction::JaZh),
+            (false, true, None) => Some(Direction::ZhJa),
+            (false, false, _) => None,
+        }
+    }
+
+    // 对应的提示词模板
+    fn template_name(self) -> &'static str {
+        match self {
+            Direction::ZhJa => "translate-zh-ja",
+            Direction::JaZh => "translate-ja-zh",
+            Direction::JaEn => "translate-ja-en",
+        }
+    }
+
+    // 目标语言的标签，用于输出
+    fn target_label(self) -> &'static str {
+        match self {
+            Direction::ZhJa => "Japanese (日文)",
+            Direction::JaZh => "Chinese (中文)",
+            Direction::JaEn => "English (英文)",
+        }
+    }
+}
+
+// 查询时的运行选项
+#[derive(Default)]
+struct LookupOptions {
+    offline: bool,
+    save: bool,
+    to: Option<TargetLang>,
 }
 
 struct JapaneseChar {
@@ -45,6 +495,16 @@ struct JapaneseChar {
     examples: Vec<String>,
 }
 
+impl kana::KanaRow for JapaneseChar {
+    fn hiragana(&self) -> &str {
+        &self.hiragana
+    }
+
+    fn romaji(&self) -> &str {
+        &self.romaji
+    }
+}
+
 // 为假名创建 ASCII art 大字效果
 fn get_ascii_art(character: &str) -> Vec<String> {
     match character {
@@ -1657,6 +2117,16 @@ struct ChatRequest {
     model: String,
     messages: Vec<ChatMessage>,
     temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    // 流式输出时请求服务端在最后返回 usage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
+    // 调用来源，用于用量统计，不发送给 API
+    #[serde(skip)]
+    feature: &'static str,
 }
 
 #[derive(Deserialize)]
@@ -1667,46 +2137,523 @@ struct ChatChoice {
 #[derive(Deserialize)]
 struct ChatResponse {
     choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<usage::TokenUsage>,
 }
 
-// 调用 OpenAI compatible API 进行翻译
-async fn translate_with_llm(chinese_text: &str) -> Result<String, Box<dyn std::error::Error>> {
+// LLM 返回的结构化翻译结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Translation {
+    japanese: String,
+    #[serde(default)]
+    kana: String,
+    #[serde(default)]
+    romaji: String,
+    // 日译中 / 日译英时的译文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    meaning: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    alternatives: Vec<String>,
+}
+
+impl Translation {
+    // 与本地字典一致的显示格式：日文 (平假名/罗马音)
+    fn display(&self) -> String {
+        match (self.kana.is_empty(), self.romaji.is_empty()) {
+            (true, true) => self.japanese.clone(),
+            (false, true) => format!("{} ({})", self.japanese, self.kana),
+            (true, false) => format!("{} ({})", self.japanese, self.romaji),
+            (false, false) if self.kana == self.japanese => format!("{} ({})", self.japanese, self.romaji),
+            (false, false) => format!("{} ({}/{})", self.japanese, self.kana, self.romaji),
+        }
+    }
+
+    // 校验字段内容：日文不能为空，读音只能是假名，罗马音只能是 ASCII
+    // 流式输出时显示的字段（键名与 JSON 一致）
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("japanese", self.japanese.clone()),
+            ("kana", self.kana.clone()),
+            ("romaji", self.romaji.clone()),
+            ("meaning", self.meaning.clone().unwrap_or_default()),
+            ("notes", self.notes.clone().unwrap_or_default()),
+            ("alternatives", self.alternatives.join("、")),
+        ]
+    }
+
+    fn validate(mut self) -> Result<Self, String> {
+        self.japanese = self.japanese.trim().to_string();
+        self.kana = self.kana.trim().to_string();
+        self.romaji = expand_long_vowels(self.romaji.trim(), &self.kana);
+        self.meaning = self.meaning.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+        self.notes = self.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        self.alternatives = self.alternatives.into_iter()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+
+        if self.japanese.is_empty() {
+            return Err("missing field 'japanese'".to_string());
+        }
+        if !self.kana.chars().all(|c| is_kana(c) || c.is_whitespace() || is_japanese_punctuation(c)) {
+            return Err(format!("field 'kana' contains non-kana characters: {}", self.kana));
+        }
+        if !self.romaji.is_ascii() {
+            return Err(format!("field 'romaji' is not ASCII: {}", self.romaji));
+        }
+        if self.kana.is_empty() && self.romaji.is_empty() {
+            return Err("missing both 'kana' and 'romaji'".to_string());
+        }
+        Ok(self)
+    }
+}
+
+fn is_kana(c: char) -> bool {
+    ('\u{3040}'..='\u{309F}').contains(&c) || // 平假名
+    ('\u{30A0}'..='\u{30FF}').contains(&c) || // 片假名（含长音符号 ー）
+    ('\u{31F0}'..='\u{31FF}').contains(&c)    // 片假名音标扩展
+}
+
+fn is_japanese_punctuation(c: char) -> bool {
+    ('\u{3000}'..='\u{303F}').contains(&c) || "！？，．、。・".contains(c)
+}
+
+// 假名的元音（拗音的小写假名按所在的段）
+fn kana_vowel(c: char) -> Option<char> {
+    const ROWS: [(char, &str); 5] = [
+        ('a', "あかさたなはまやらわがざだばぱぁゃゎ"),
+        ('i', "いきしちにひみりぎじぢびぴぃ"),
+        ('u', "うくすつぬふむゆるぐずづぶぷぅゅ"),
+        ('e', "えけせてねへめれげぜでべぺぇ"),
+        ('o', "おこそとのほもよろをごぞどぼぽぉょ"),
+    ];
+    ROWS.iter().find(|(_, row)| row.contains(c)).map(|(vowel, _)| *vowel)
+}
+
+// 假名中的长音依次是哪两个字母：とうきょう → [ou, ou]，おおきい → [oo, ii]，せんせい → [ei]，ラーメン → [aa]
+fn long_vowels(kana: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = kana::to_hiragana(kana).chars().collect();
+    chars
+        .windows(2)
+        .filter_map(|pair| {
+            let vowel = kana_vowel(pair[0])?;
+            let second = match (vowel, pair[1]) {
+                (_, 'ー') => vowel,
+                ('o' | 'u', 'う') => 'u',
+                ('o', 'お') => 'o',
+                ('e' | 'i', 'い') => 'i',
+                ('e', 'え') => 'e',
+                ('a', 'あ') => 'a',
+                _ => return None,
+            };
+            Some((vowel, second))
+        })
+        .collect()
+}
+
+// LLM 常用长音符号写罗马音（Tōkyō、ōkii、sensē），按假名里的实际写法改写成 ASCII（Toukyou、ookii、sensei）；
+// 假名中找不到对应的长音时按 ā → aa、ō → ou 处理
+fn expand_long_vowels(romaji: &str, kana: &str) -> String {
+    let mut pending = long_vowels(kana).into_iter();
+    let mut result = String::with_capacity(romaji.len());
+    for c in romaji.chars() {
+        let vowel = match c {
+            'ā' | 'â' | 'Ā' | 'Â' => 'a',
+            'ī' | 'î' | 'Ī' | 'Î' => 'i',
+            'ū' | 'û' | 'Ū' | 'Û' => 'u',
+            'ē' | 'ê' | 'Ē' | 'Ê' => 'e',
+            'ō' | 'ô' | 'Ō' | 'Ô' => 'o',
+            _ => {
+                result.push(c);
+                continue;
+            }
+        };
+        let default = if vowel == 'o' { 'u' } else { vowel };
+        let second = pending.by_ref().find(|(v, _)| *v == vowel).map_or(default, |(_, second)| second);
+        result.push(if c.is_uppercase() { vowel.to_ascii_uppercase() } else { vowel });
+        result.push(second);
+    }
+    result
+}
+
+// 结构化输出模式，可通过 OPENAI_RESPONSE_FORMAT 设置为 json_schema / json_object / off
+fn json_response_format(name: &str, schema: serde_json::Value) -> Option<serde_json::Value> {
+    let mode = env::var("OPENAI_RESPONSE_FORMAT").unwrap_or_else(|_| "json_object".to_string());
+    match mode.trim().to_lowercase().as_str() {
+        "off" | "none" | "text" => None,
+        "json_schema" => Some(serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": name,
+                "strict": true,
+                "schema": schema
+            }
+        })),
+        _ => Some(serde_json::json!({ "type": "json_object" })),
+    }
+}
+
+fn translation_response_format() -> Option<serde_json::Value> {
+    json_response_format("translation", serde_json::json!({
+        "type": "object",
+        "properties": {
+            "japanese": { "type": "string" },
+            "kana": { "type": "string" },
+            "romaji": { "type": "string" },
+            "meaning": { "type": ["string", "null"] },
+            "notes": { "type": ["string", "null"] },
+            "alternatives": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["japanese", "kana", "romaji", "meaning", "notes", "alternatives"],
+        "additionalProperties": false
+    }))
+}
+
+// 从回复中截取 JSON 对象（去掉 ```json 代码块等多余内容）
+fn extract_json_object(content: &str) -> Option<&str> {
+    match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => Some(&content[start..=end]),
+        _ => None,
+    }
+}
+
+// 解析 LLM 回复：优先按 JSON 解析，失败时回退到旧的 "日文汉字 (平假名/罗马音)" 文本格式
+fn parse_translation(content: &str) -> Result<Translation, String> {
+    let content = content.trim();
+
+    let json_error = match extract_json_object(content) {
+        Some(json) => match serde_json::from_str::<Translation>(json) {
+            Ok(translation) => match translation.validate() {
+                Ok(translation) => return Ok(translation),
+                Err(e) => e,
+            },
+            Err(e) => e.to_string(),
+        },
+        None => "no JSON object in response".to_string(),
+    };
+
+    parse_legacy_translation(content)
+        .ok_or_else(|| format!("Could not parse LLM response ({}): {}", json_error, content))
+}
+
+// 兼容忽略 JSON 要求的模型："日文 (かな/romaji)"、"日文 (かな)" 或纯假名
+fn parse_legacy_translation(content: &str) -> Option<Translation> {
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_start_matches(|c: char| c == '-' || c == '*' || c.is_whitespace());
+
+    let (japanese, reading) = match line.find(['(', '（']) {
+        Some(open) => {
+            let close = line.rfind([')', '）']).filter(|&close| close > open)?;
+            let open_len = line[open..].chars().next()?.len_utf8();
+            (line[..open].trim(), Some(line[open + open_len..close].trim()))
+        }
+        None => (line, None),
+    };
+
+    let mut translation = Translation {
+        japanese: japanese.to_string(),
+        kana: String::new(),
+        romaji: String::new(),
+        meaning: None,
+        notes: None,
+        alternatives: Vec::new(),
+    };
+
+    match reading {
+        Some(reading) => {
+            for part in reading.split(['/', '／']) {
+                let part = part.trim();
+                if part.is_ascii() {
+                    translation.romaji = part.to_string();
+                } else {
+                    translation.kana = part.to_string();
+                }
+            }
+        }
+        // 纯假名回复：读音就是本身
+        None if japanese.chars().all(|c| is_kana(c) || c.is_whitespace()) => {
+            translation.kana = japanese.to_string();
+        }
+        None => return None,
+    }
+
+    translation.validate().ok()
+}
+
+fn llm_model() -> String {
+    env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-3.5-turbo".to_string())
+}
+
+// LLM 调用的错误类型，CLI 和 Web 可以据此给出具体的提示
+#[derive(Debug)]
+enum LlmError {
+    // 缺少 API key 等配置问题
+    Config(String),
+    // 401 / 403：API key 无效或没有权限
+    Auth(String),
+    // 429：请求过于频繁或额度用完
+    Quota(String),
+    // 连接失败、超时等网络问题
+    Network(String),
+    // 服务端错误或无法解析的回复
+    BadResponse(String),
+    // 本月费用已达到 JP_MONTHLY_BUDGET
+    Budget(String),
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmError::Config(msg) => write!(f, "configuration error: {}", msg),
+            LlmError::Auth(msg) => write!(f, "authentication failed: {}", msg),
+            LlmError::Quota(msg) => write!(f, "rate limit or quota exceeded: {}", msg),
+            LlmError::Network(msg) => write!(f, "network error: {}", msg),
+            LlmError::BadResponse(msg) => write!(f, "bad response: {}", msg),
+            LlmError::Budget(msg) => write!(f, "monthly budget: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+impl From<reqwest::Error> for LlmError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            LlmError::BadResponse(e.to_string())
+        } else if e.is_builder() {
+            LlmError::Config(e.to_string())
+        } else {
+            LlmError::Network(e.to_string())
+        }
+    }
+}
+
+impl LlmError {
+    // 错误类别，用于 Web API 的 JSON 响应
+    fn kind(&self) -> &'static str {
+        match self {
+            LlmError::Config(_) => "config",
+            LlmError::Auth(_) => "auth",
+            LlmError::Quota(_) => "quota",
+            LlmError::Network(_) => "network",
+            LlmError::BadResponse(_) => "bad_response",
+            LlmError::Budget(_) => "budget",
+        }
+    }
+
+    // 给用户的处理建议
+    fn hints(&self) -> Vec<&'static str> {
+        match self {
+            LlmError::Config(_) | LlmError::Auth(_) => vec![
+                "请确保已设置以下环境变量：",
+                "- OPENAI_API_KEY: 你的 API key",
+                "- OPENAI_API_BASE: API 地址 (可选)",
+                "- OPENAI_MODEL: 模型名称 (可选)",
+            ],
+            LlmError::Quota(_) => vec![
+                "请求过于频繁或账户额度已用完，请稍后再试或检查账户余额",
+            ],
+            LlmError::Network(_) => vec![
+                "请检查网络连接和 API 地址，必要时设置：",
+                "- OPENAI_PROXY: 代理地址 (如 http://127.0.0.1:7890)",
+                "- OPENAI_CONNECT_TIMEOUT / OPENAI_TIMEOUT: 超时秒数",
+            ],
+            LlmError::BadResponse(_) => vec![
+                "服务端出错或模型返回了无法处理的内容，可以重试、更换模型，",
+                "或设置 OPENAI_RESPONSE_FORMAT=off 关闭 JSON 模式",
+            ],
+            LlmError::Budget(_) => vec![
+                "本月的 LLM 费用已达到预算或无法计算费用，可以用 jp usage 查看用量，",
+                "提高 JP_MONTHLY_BUDGET，或使用 --offline 只查本地字典和缓存；",
+                "模型价格未知时请设置 OPENAI_PRICE_INPUT / OPENAI_PRICE_OUTPUT（每百万 token 的美元价格）",
+            ],
+        }
+    }
+}
+
+fn env_secs(name: &str, default: u64) -> std::time::Duration {
+    let secs = env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(default);
+    std::time::Duration::from_secs(secs)
+}
+
+// 构建 HTTP 客户端：连接超时 OPENAI_CONNECT_TIMEOUT（默认 10 秒），
+// 读取超时 OPENAI_TIMEOUT（默认 60 秒），代理 OPENAI_PROXY（默认使用系统代理）
+fn llm_http_client() -> Result<reqwest::Client, LlmError> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(env_secs("OPENAI_CONNECT_TIMEOUT", 10))
+        .read_timeout(env_secs("OPENAI_TIMEOUT", 60));
+
+    if let Ok(proxy) = env::var("OPENAI_PROXY") {
+        if !proxy.trim().is_empty() {
+            let proxy = reqwest::Proxy::all(proxy.trim())
+                .map_err(|e| LlmError::Config(format!("invalid OPENAI_PROXY: {}", e)))?;
+            builder = builder.proxy(proxy);
+        }
+    }
+
+    builder.build().map_err(|e| LlmError::Config(e.to_string()))
+}
+
+// 失败的请求：错误、是否可以重试、服务端要求的等待时间
+struct FailedAttempt {
+    error: LlmError,
+    retryable: bool,
+    retry_after: Option<std::time::Duration>,
+}
+
+// 按状态码把失败的响应转换成错误，并读取 Retry-After（秒）
+async fn response_error(response: reqwest::Response) -> FailedAttempt {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(std::time::Duration::from_secs);
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("status {}: {}", status, body.trim());
+
+    let (error, retryable) = match status.as_u16() {
+        401 | 403 => (LlmError::Auth(message), false),
+        // 额度用完时重试也没有用
+        429 if body.contains("insufficient_quota") => (LlmError::Quota(message), false),
+        429 => (LlmError::Quota(message), true),
+        500..=599 => (LlmError::BadResponse(message), true),
+        _ => (LlmError::BadResponse(message), false),
+    };
+    FailedAttempt { error, retryable, retry_after }
+}
+
+// 发送 chat completions 请求，返回回复内容。
+// 传入 on_token 时使用 SSE 流式输出，每收到一段内容就回调一次；
+// 服务端不支持流式（返回普通 JSON）时自动按非流式响应处理。
+// 网络错误、429 和 5xx 会按指数退避重试 OPENAI_MAX_RETRIES 次（默认 3），优先使用 Retry-After
+async fn chat_completion(
+    mut request: ChatRequest,
+    on_token: Option<&mut (dyn FnMut(&str) + Send)>,
+) -> Result<String, LlmError> {
     // 从环境变量获取 API 配置
     let api_key = env::var("OPENAI_API_KEY")
-        .map_err(|_| "OPENAI_API_KEY environment variable not set")?;
+        .map_err(|_| LlmError::Config("OPENAI_API_KEY environment variable not set".to_string()))?;
 
     // Validate API key is not empty
     if api_key.trim().is_empty() {
-        return Err("OPENAI_API_KEY is set but empty".into());
+        return Err(LlmError::Config("OPENAI_API_KEY is set but empty".to_string()));
     }
 
     let api_base = env::var("OPENAI_API_BASE")
         .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
 
-    let model = env::var("OPENAI_MODEL")
-        .unwrap_or_else(|_| "gpt-3.5-turbo".to_string());
-
     let url = format!("{}/chat/completions", api_base);
 
-    let prompt = format!(
-        "请将以下中文翻译成日语，并提供以下信息：\n\
-        1. 日文汉字（如果有）\n\
-        2. 平假名读音\n\
-        3. 罗马音\n\
-        \n\
-        中文：{}\n\
-        \n\
-        请按照这个格式回复：日文汉字 (平假名/罗马音)\n\
-        如果没有汉字，直接用平假名表示。",
-        chinese_text
+    let max_retries = env::var("OPENAI_MAX_RETRIES")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(3);
+
+    usage::check_budget(&request.model)?;
+
+    request.stream = on_token.is_some();
+    request.stream_options = request.stream.then(|| serde_json::json!({ "include_usage": true }));
+    let prompt_chars: usize = request.messages.iter().map(|m| m.content.chars().count()).sum();
+    let started = std::time::Instant::now();
+
+    let client = llm_http_client()?;
+    let mut attempt = 0;
+    let response = loop {
+        let result = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await;
+
+        let failed = match result {
+            Ok(response) if response.status().is_success() => break response,
+            Ok(response) => response_error(response).await,
+            Err(e) => {
+                let error = LlmError::from(e);
+                let retryable = matches!(error, LlmError::Network(_));
+                FailedAttempt { error, retryable, retry_after: None }
+            }
+        };
+        let FailedAttempt { error, retryable, retry_after } = failed;
+
+        if !retryable || attempt >= max_retries {
+            return Err(error);
+        }
+
+        // 1s, 2s, 4s ... 最长 30 秒；Retry-After 最长等待 60 秒
+        let backoff = std::time::Duration::from_secs(1 << attempt.min(5)).min(std::time::Duration::from_secs(30));
+        let delay = retry_after
+            .map(|d| d.min(std::time::Duration::from_secs(60)))
+            .unwrap_or(backoff);
+        eprintln!("⏳ {}，{} 秒后重试 ({}/{})", error, delay.as_secs(), attempt + 1, max_retries);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    };
+
+    let is_event_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("text/event-stream"))
+        .unwrap_or(false);
+
+    let (content, token_usage) = match (on_token, is_event_stream) {
+        (Some(on_token), true) => stream::read_sse(response, on_token).await?,
+        _ => {
+            let chat_response: ChatResponse = response.json().await?;
+            match chat_response.choices.into_iter().next() {
+                Some(choice) => (choice.message.content, chat_response.usage),
+                None => return Err(LlmError::BadResponse("no choices in response".to_string())),
+            }
+        }
+    };
+
+    usage::record(
+        request.feature,
+        &request.model,
+        token_usage,
+        prompt_chars,
+        content.chars().count(),
+        started.elapsed().as_millis() as u64,
     );
+    Ok(content)
+}
+
+// 是否使用流式输出，可通过 OPENAI_STREAM=0 关闭
+fn streaming_enabled() -> bool {
+    use std::io::IsTerminal;
+
+    let disabled = env::var("OPENAI_STREAM")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "off"))
+        .unwrap_or(false);
+    !disabled && std::io::stdout().is_terminal()
+}
+
+// 调用 OpenAI compatible API 进行翻译
+async fn translate_with_llm(
+    text: &str,
+    direction: Direction,
+    on_token: Option<&mut (dyn FnMut(&str) + Send)>,
+) -> Result<Translation, LlmError> {
+    let (system_prompt, prompt) = prompts::load(direction.template_name())
+        .and_then(|template| template.render(&[("text", text)]))
+        .map_err(LlmError::Config)?;
 
     let request = ChatRequest {
-        model,
+        model: llm_model(),
         messages: vec![
             ChatMessage {
                 role: "system".to_string(),
-                content: "你是一个专业的中日翻译助手。".to_string(),
+                content: system_prompt,
             },
             ChatMessage {
                 role: "user".to_string(),
@@ -1714,30 +2661,18 @@ async fn translate_with_llm(chinese_text: &str) -> Result<String, Box<dyn std::e
             },
         ],
         temperature: 0.3,
+        response_format: translation_response_format(),
+        stream: false,
+        stream_options: None,
+        feature: "translate",
     };
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await?;
-        return Err(format!("API request failed with status {}: {}", status, error_text).into());
-    }
-
-    let chat_response: ChatResponse = response.json().await?;
-
-    if let Some(choice) = chat_response.choices.first() {
-        Ok(choice.message.content.trim().to_string())
-    } else {
-        Err("No response from API".into())
+    let content = chat_completion(request, on_token).await?;
+    let translation = parse_translation(&content).map_err(LlmError::BadResponse)?;
+    if direction != Direction::ZhJa && translation.meaning.is_none() {
+        return Err(LlmError::BadResponse(format!("no 'meaning' field: {}", content)));
     }
+    Ok(translation)
 }
 
 fn init_romaji_map() -> HashMap<String, JapaneseChar> {
@@ -2123,6 +3058,128 @@ fn init_chinese_map() -> HashMap<String, String> {
     map
 }
 
+// 拆分 "日文 (かな/romaji)" 格式，返回日文和所有假名读音（"し・よん" 会拆成两个）
+fn split_japanese_entry(entry: &str) -> (&str, Vec<&str>) {
+    let (word, reading) = match entry.find('(') {
+        Some(open) => (entry[..open].trim(), entry[open + 1..].trim_end_matches(')')),
+        None => (entry.trim(), ""),
+    };
+    let kana = reading
+        .split('/')
+        .filter(|part| !part.is_ascii())
+        .flat_map(|part| part.split('・'))
+        .map(str::trim)
+        .collect();
+    (word, kana)
+}
+
+// 假名例词（"愛(あい/ai) - love"）的等级和词频
+fn example_tags(levels: &levels::LevelIndex, example: &str) -> levels::Tags {
+    let left = example.split_once(" - ").map_or(example, |(left, _)| left);
+    let (word, kana) = split_japanese_entry(left);
+    levels.tags(word, kana.first().copied())
+}
+
+// 日译中：在内置中文字典中反查，返回匹配的中文词
+fn reverse_lookup_chinese(chinese_map: &HashMap<String, String>, japanese: &str) -> Vec<String> {
+    let mut matches: Vec<String> = chinese_map
+        .iter()
+        .filter(|(_, entry)| {
+            let (word, kana) = split_japanese_entry(entry);
+            word == japanese || kana.contains(&japanese)
+        })
+        .map(|(chinese, _)| chinese.clone())
+        .collect();
+    matches.sort();
+    matches
+}
+
+// 日译英：在假名例词（"愛(あい/ai) - love"）中反查英文释义
+fn reverse_lookup_english(romaji_map: &HashMap<String, JapaneseChar>, japanese: &str) -> Vec<String> {
+    let mut matches: Vec<String> = Vec::new();
+    for jp_char in romaji_map.values() {
+        for example in &jp_char.examples {
+            let Some((left, english)) = example.split_once(" - ") else {
+                continue;
+            };
+            let (word, kana) = split_japanese_entry(left);
+            if (word == japanese || kana.contains(&japanese)) && !matches.iter().any(|m| m == english) {
+                matches.push(english.to_string());
+            }
+        }
+    }
+    matches.sort();
+    matches
+}
+
+// API 错误响应：{ "error": "...", "kind": "..." }
+fn api_error(status: StatusCode, kind: &str, message: String) -> (StatusCode, Json<serde_json::Value>) {
+    (status, Json(serde_json::json!({ "error": message, "kind": kind })))
+}
+
+// GET /api/explain?text=...
+async fn api_explain(Query(params): Query<HashMap<String, String>>) -> (StatusCode, Json<serde_json::Value>) {
+    let Some(text) = params.get("text").map(|t| t.trim()).filter(|t| !t.is_empty()) else {
+        return api_error(StatusCode::BAD_REQUEST, "bad_request", "missing 'text' parameter".to_string());
+    };
+
+    match explain::explain_sentence(text).await {
+        Ok(explanation) => (StatusCode::OK, Json(serde_json::json!(explanation))),
+        Err(e) => api_error(StatusCode::BAD_GATEWAY, e.kind(), e.to_string()),
+    }
+}
+
+// GET /api/accent?word=日本 ，format=svg 时直接返回 SVG 图片（第一个音调）
+async fn api_accent(Query(params): Query<HashMap<String, String>>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let Some(word) = params.get("word").map(|t| t.trim()).filter(|t| !t.is_empty()) else {
+        return api_error(StatusCode::BAD_REQUEST, "bad_request", "missing 'word' parameter".to_string()).into_response();
+    };
+    let entries = accent::lookup(word);
+    if entries.is_empty() {
+        return api_error(StatusCode::NOT_FOUND, "not_found", format!("no pitch accent for '{}'", word)).into_response();
+    }
+
+    if params.get("format").map(String::as_str) == Some("svg") {
+        let entry = &entries[0];
+        let svg = accent::svg(&entry.reading, entry.patterns[0]);
+        return ([(axum::http::header::CONTENT_TYPE, "image/svg+xml")], svg).into_response();
+    }
+    let results: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|e| {
+            let mora_count = accent::morae(&e.reading).len();
+            let patterns: Vec<serde_json::Value> = e
+                .patterns
+                .iter()
+                .map(|&p| {
+                    serde_json::json!({
+                        "pattern": p,
+                        "name": accent::pattern_name(p, mora_count),
+                        "levels": accent::pitch_levels(mora_count, p),
+                        "svg": accent::svg(&e.reading, p),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "word": e.word, "reading": e.reading, "morae": accent::morae(&e.reading), "patterns": patterns })
+        })
+        .collect();
+    (StatusCode::OK, Json(serde_json::json!(results))).into_response()
+}
+
+// GET /api/radicals?parts=氵+每
+async fn api_radicals(Query(params): Query<HashMap<String, String>>) -> (StatusCode, Json<serde_json::Value>) {
+    let Some(parts) = params.get("parts").map(|t| t.trim()).filter(|t| !t.is_empty()) else {
+        return api_error(StatusCode::BAD_REQUEST, "bad_request", "missing 'parts' parameter".to_string());
+    };
+    let index = radicals::RadicalIndex::load();
+    if index.is_empty() {
+        return api_error(StatusCode::NOT_FOUND, "not_imported", "no radical data imported (jp import kradfile)".to_string());
+    }
+    (StatusCode::OK, Json(serde_json::json!(index.search(parts))))
+}
+
 // Web server function
 async fn start_web_server(host: String, port: u16) -> Result<(), Box<dyn std::error::Error>> {
     // Get the executable directory
@@ -2153,7 +3210,10 @@ async fn start_web_server(host: String, port: u16) -> Result<(), Box<dyn std::er
     
     // Build the router
     let app = Router::new()
-        .nest_service("/", ServeDir::new(&web_dir));
+        .route("/api/explain", get(api_explain))
+        .route("/api/radicals", get(api_radicals))
+        .route("/api/accent", get(api_accent))
+        .fallback_service(ServeDir::new(&web_dir));
     
     let addr = format!("{}:{}", host, port).parse::<SocketAddr>()?;
 
@@ -2163,10 +3223,10 @@ async fn start_web_server(host: String, port: u16) -> Result<(), Box<dyn std::er
         println!("═══════════════════════════════════════════════════════════");
         println!("You are binding to '{}', which may expose this server", host);
         println!("to your entire network or the internet!");
-        println!("");
+        println!();
         println!("This server has NO authentication or security features.");
         println!("Anyone who can reach this address can access your files.");
-        println!("");
+        println!();
         println!("💡 For local testing, use: --host 127.0.0.1 (default)");
         println!("═══════════════════════════════════════════════════════════\n");
     }
@@ -2181,30 +3241,926 @@ async fn start_web_server(host: String, port: u16) -> Result<(), Box<dyn std::er
     Ok(())
 }
 
-// Handle the lookup functionality (original main logic)
-async fn handle_lookup(text: String) {
-    let romaji_map = init_romaji_map();
-    let chinese_map = init_chinese_map();
-
-    // Helpers for width-aware padding and printing
-    const INNER_WIDTH: usize = 85; // width between the vertical borders
-    fn pad_right(text: &str, width: usize) -> String {
-        let w = UnicodeWidthStr::width(text);
-        if w >= width { text.to_string() } else { format!("{}{}", text, " ".repeat(width - w)) }
-    }
-    fn center_text(text: &str, width: usize) -> String {
-        let w = UnicodeWidthStr::width(text);
-        if w >= width { text.to_string() } else {
-            let left = (width - w) / 2;
-            let right = width - w - left;
-            format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+// 输出 LLM 翻译结果（不含边框的首尾行）
+fn print_translation(text: &str, translation: &Translation, direction: Direction) {
+    match direction {
+        Direction::ZhJa => {
+            println!("║ Chinese (中文): {}", text);
+            println!("║ Japanese (日文): {}", translation.display());
+            hanzi::print_comparison(text);
+        }
+        Direction::JaZh | Direction::JaEn => {
+            println!("║ Japanese (日文): {}", translation.display());
+            println!("║ {}: {}", direction.target_label(), translation.meaning.as_deref().unwrap_or(""));
         }
     }
-    fn emit(text: &str) {
-        println!("║{}║", pad_right(text, INNER_WIDTH));
+    let kana = Some(translation.kana.as_str()).filter(|k| !k.is_empty());
+    accent::print_accent(&translation.japanese, kana);
+    levels::print_tags(levels::LevelIndex::load().tags(&translation.japanese, kana));
+    if let Some(notes) = &translation.notes {
+        println!("║ Notes (说明): {}", notes);
     }
-    fn emit_center(text: &str) {
-        println!("║{}║", center_text(text, INNER_WIDTH));
+    if !translation.alternatives.is_empty() {
+        println!("║ Alternatives (其他说法): {}", translation.alternatives.join("、"));
+    }
+}
+
+// 询问是否把 LLM 翻译收录到用户词典；--save 时直接保存，非交互终端下不询问
+fn offer_to_save(user_dict: &mut UserDictionary, text: &str, translation: Translation, options: &LookupOptions) {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let save = if options.save {
+        true
+    } else if std::io::stdin().is_terminal() {
+        print!("💾 保存到用户词典，以后作为单词卡复习？[y/N] ");
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().lock().read_line(&mut answer);
+        matches!(answer.trim().to_lowercase().as_str(), "y" | "yes" | "是")
+    } else {
+        false
+    };
+
+    if !save {
+        return;
+    }
+
+    user_dict.add_llm_translation(text, translation);
+    match user_dict.save() {
+        Ok(()) => println!("✅ 已保存到用户词典（标记为待复核）: {}", user_dict::user_dict_path().display()),
+        Err(e) => eprintln!("⚠️  Failed to save user dictionary: {}", e),
+    }
+}
+
+// Handle the dict subcommands
+fn handle_dict(action: DictAction) -> Result<(), Box<dyn std::error::Error>> {
+    let mut user_dict = UserDictionary::load();
+
+    match action {
+        DictAction::List { review } => {
+            let entries: Vec<_> = user_dict.entries()
+                .iter()
+                .filter(|e| !review || e.review_needed)
+                .collect();
+            if entries.is_empty() {
+                println!("📒 No entries{}", if review { " need review" } else { " in the user dictionary" });
+                return Ok(());
+            }
+            println!("📒 User dictionary: {}", user_dict::user_dict_path().display());
+            for entry in entries {
+                let flag = if entry.review_needed { " [待复核]" } else { "" };
+                println!("   {} → {}{}", entry.chinese, entry.japanese(), flag);
+            }
+        }
+        DictAction::Approve { chinese } => {
+            if !user_dict.approve(&chinese) {
+                return Err(format!("'{}' is not in the user dictionary", chinese).into());
+            }
+            user_dict.save()?;
+            println!("✅ Marked '{}' as reviewed", chinese);
+        }
+        DictAction::Remove { chinese } => {
+            if !user_dict.remove(&chinese) {
+                return Err(format!("'{}' is not in the user dictionary", chinese).into());
+            }
+            user_dict.save()?;
+            println!("🗑️  Removed '{}'", chinese);
+        }
+    }
+
+    Ok(())
+}
+
+// Handle the import subcommand
+fn handle_import(kind: ImportKind, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    match kind {
+        ImportKind::Jmdict => {
+            println!("📥 正在导入 JMdict: {}", path.display());
+            let stats = jmdict::import(path)?;
+            println!("✅ 已导入 {} 个词条（常用词 {} 个）: {}", stats.entries, stats.common, jmdict::jmdict_path().display());
+        }
+        ImportKind::Cedict => {
+            println!("📥 正在导入 CC-CEDICT: {}", path.display());
+            let count = cedict::import(path)?;
+            println!("✅ 已导入 {} 个词条: {}", count, cedict::cedict_path().display());
+            if !jmdict::is_imported() {
+                println!("💡 中文到日语的离线查询还需要导入 JMdict: jp import jmdict JMdict_e.xml");
+            }
+        }
+        ImportKind::Kanjidic => {
+            println!("📥 正在导入 KANJIDIC2: {}", path.display());
+            let count = kanjidic::import(path)?;
+            println!("✅ 已导入 {} 个汉字: {}", count, kanjidic::kanjidic_path().display());
+        }
+        ImportKind::Accent => {
+            println!("📥 正在导入音调数据: {}", path.display());
+            let count = accent::import(path)?;
+            println!("✅ 已导入 {} 个词条的音调: {}", count, accent::accent_path().display());
+        }
+        ImportKind::Kradfile => {
+            println!("📥 正在导入 KRADFILE: {}", path.display());
+            let count = radicals::import_kradfile(path)?;
+            println!("✅ 已导入 {} 个汉字的部件: {}", count, radicals::radicals_path().display());
+        }
+        ImportKind::Radkfile => {
+            println!("📥 正在导入 RADKFILE: {}", path.display());
+            let count = radicals::import_radkfile(path)?;
+            println!("✅ 已导入 {} 个部件: {}", count, radicals::radicals_path().display());
+        }
+        ImportKind::Jlpt => {
+            println!("📥 正在导入 JLPT 词表: {}", path.display());
+            let count = levels::import_jlpt(path)?;
+            println!("✅ 已导入 {} 个词: {}", count, levels::jlpt_path().display());
+        }
+        ImportKind::Frequency => {
+            println!("📥 正在导入词频表: {}", path.display());
+            let count = levels::import_frequency(path)?;
+            println!("✅ 已导入 {} 个词的词频排名: {}", count, levels::frequency_path().display());
+        }
+    }
+    Ok(())
+}
+
+// Handle the conjugate subcommand：列出活用形，或者出题练习
+fn handle_conjugate(word: Option<String>, adj: bool, drill: bool, count: usize, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    let words = match &word {
+        Some(word) if adj => {
+            let (stem, reading, pos) = conjugate::classify_adjective(word.trim())?;
+            let conjugations =
+                conjugate::conjugate(&stem, &reading, pos).ok_or_else(|| format!("'{}' is not an adjective (e.g. 高い, 静か)", word))?;
+            vec![conjugations]
+        }
+        Some(word) => {
+            let word = word.trim();
+            let (reading, pos) = conjugate::classify(word)?;
+            let conjugations = conjugate::conjugate(word, &reading, pos)
+                .ok_or_else(|| format!("'{}' is not a verb in dictionary form (e.g. 書く, 食べる)", word))?;
+            vec![conjugations]
+        }
+        None => conjugate::drill_words(adj),
+    };
+
+    if drill {
+        let (kind, name) = if adj { ("conjugation-adjective", "形容词活用练习") } else { ("conjugation-verb", "动词活用练习") };
+        let title = match &word {
+            Some(word) => format!("{}（{}）", name, word.trim()),
+            None => name.to_string(),
+        };
+        let questions = conjugate::drill_questions(&words, count.max(1), &romanizer);
+        quiz::run(kind, &title, &questions, &romanizer);
+    } else if json {
+        println!("{}", serde_json::to_string_pretty(&words[0])?);
+    } else {
+        conjugate::print_conjugations(&words[0], &romanizer);
+    }
+    Ok(())
+}
+
+// jp number / jp date / jp time
+#[derive(Clone, Copy)]
+enum ReadingKind {
+    Number,
+    Date,
+    Time,
+}
+
+// Handle the number, date and time subcommands：输出读法，或者随机出题
+fn handle_reading(kind: ReadingKind, value: Option<String>, quiz: bool, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    if quiz {
+        let count = count.max(1);
+        let (quiz_kind, title, questions) = match kind {
+            ReadingKind::Number => ("number", "数字读法测验", numbers::number_questions(count, &romanizer)),
+            ReadingKind::Date => ("date", "日期读法测验", numbers::date_questions(count, &romanizer)),
+            ReadingKind::Time => ("time", "时间读法测验", numbers::time_questions(count, &romanizer)),
+        };
+        quiz::run(quiz_kind, title, &questions, &romanizer);
+        return Ok(());
+    }
+
+    let value = value.unwrap_or_default();
+    match kind {
+        ReadingKind::Number => {
+            let (n, yen) = numbers::parse_number(&value)?;
+            let reading = if yen { numbers::yen(n) } else { numbers::number(n) };
+            numbers::print_readings("Number (数字)", &value, &numbers::with_sen_variant(reading), &romanizer);
+        }
+        ReadingKind::Date => {
+            let (year, month, day) = numbers::parse_date(&value)?;
+            numbers::print_readings("Date (日期)", &value, &[numbers::date(year, month, day)], &romanizer);
+        }
+        ReadingKind::Time => {
+            let (hour, minute) = numbers::parse_time(&value)?;
+            numbers::print_readings("Time (时间)", &value, &numbers::time(hour, minute), &romanizer);
+        }
+    }
+    Ok(())
+}
+
+// Handle the counter subcommand
+fn handle_counter(counter: Option<String>, number: Option<String>, drill: bool, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    let matches = match &counter {
+        Some(text) => {
+            let matches = counters::find(text, &romanizer);
+            if matches.is_empty() {
+                return Err(format!("'{}' is not a known counter or object, run 'jp counter' to list the counters", text).into());
+            }
+            matches
+        }
+        None => Vec::new(),
+    };
+
+    if drill {
+        let only = matches.first().copied();
+        let title = match only {
+            Some(c) => format!("量词练习（{}）", c.kanji),
+            None => "量词练习".to_string(),
+        };
+        let questions = counters::drill_questions(only, count.max(1), &romanizer);
+        quiz::run("counter", &title, &questions, &romanizer);
+        return Ok(());
+    }
+    if matches.is_empty() {
+        counters::print_list();
+        return Ok(());
+    }
+    match number {
+        Some(number) => {
+            let (n, _) = numbers::parse_number(&number)?;
+            for counter in matches {
+                counters::print_reading(counter, n, &romanizer);
+            }
+        }
+        None => {
+            for (i, counter) in matches.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                counters::print_table(counter, &romanizer);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Handle the katakana subcommand
+fn handle_katakana(word: Vec<String>, approx: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let english = word.join(" ").trim().to_lowercase();
+    if !english.chars().any(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("'{}' has no English letters to transliterate", english).into());
+    }
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    let mut found = false;
+    if let Some(loanword) = loanwords::builtin(&english) {
+        loanwords::print_result(&english, &loanword.katakana, &loanword.meaning, "📚 来自内置外来语词典", false, &romanizer);
+        found = true;
+    }
+    for entry in loanwords::from_jmdict(&english, 3) {
+        if found && loanwords::builtin(&english).is_some_and(|l| l.katakana == entry.headword()) {
+            continue;
+        }
+        let meaning = entry.senses.first().map(|s| s.glosses.join("; ")).unwrap_or_default();
+        if found {
+            println!();
+        }
+        loanwords::print_result(&english, entry.headword(), &meaning, "📚 来自 JMdict", false, &romanizer);
+        found = true;
+    }
+    if !found || approx {
+        if found {
+            println!();
+        }
+        loanwords::print_result(&english, &loanwords::transliterate(&english), "", "", true, &romanizer);
+    }
+    Ok(())
+}
+
+// 测验和复习用的单词
+struct VocabItem {
+    word: String,
+    reading: String,
+    meaning: String,
+    tags: levels::Tags,
+}
+
+impl VocabItem {
+    fn describe(&self, romanizer: &kana::Romanizer) -> String {
+        let mut text = self.word.clone();
+        if !self.reading.is_empty() && self.reading != self.word {
+            text.push_str(&format!("（{} / {}）", self.reading, romanizer.romanize(&self.reading)));
+        } else {
+            text.push_str(&format!("（{}）", romanizer.romanize(&kana::to_hiragana(&self.word))));
+        }
+        if !self.meaning.is_empty() {
+            text.push_str(&format!(" {}", self.meaning));
+        }
+        if !self.tags.is_empty() {
+            text.push_str(&format!(" [{}]", self.tags));
+        }
+        text
+    }
+}
+
+// 筛选范围的说明，例如 "N4 以内 · 前 2000"
+fn filter_label(jlpt: Option<JlptLevel>, max_rank: Option<u32>) -> String {
+    let mut parts = Vec::new();
+    if let Some(level) = jlpt {
+        parts.push(format!("{} 以内", level));
+    }
+    if let Some(rank) = max_rank {
+        parts.push(format!("词频前 {}", rank));
+    }
+    parts.join(" · ")
+}
+
+// 单词测验的题库：导入的 JLPT 词表、内置中文字典和假名例词，同一个词只出现一次
+fn quiz_pool(levels: &levels::LevelIndex) -> Vec<VocabItem> {
+    let mut pool: Vec<VocabItem> = Vec::new();
+    let mut add = |word: &str, reading: &str, meaning: &str| {
+        if word.is_empty() || pool.iter().any(|item| item.word == word) {
+            return;
+        }
+        pool.push(VocabItem {
+            word: word.to_string(),
+            reading: kana::to_hiragana(reading),
+            meaning: meaning.to_string(),
+            tags: levels.tags(word, Some(reading).filter(|r| !r.is_empty())),
+        });
+    };
+
+    for word in levels::jlpt_words() {
+        add(&word.word, &word.reading, &word.meaning);
+    }
+    let mut chinese: Vec<(String, String)> = init_chinese_map().into_iter().collect();
+    chinese.sort();
+    for (meaning, entry) in &chinese {
+        let (word, kana) = split_japanese_entry(entry);
+        add(word, kana.first().copied().unwrap_or_default(), meaning);
+    }
+    let mut romaji: Vec<JapaneseChar> = init_romaji_map().into_values().collect();
+    romaji.sort_by(|a, b| a.romaji.cmp(&b.romaji));
+    for jp_char in &romaji {
+        for example in &jp_char.examples {
+            let Some((left, english)) = example.split_once(" - ") else {
+                continue;
+            };
+            let (word, kana) = split_japanese_entry(left);
+            add(word, kana.first().copied().unwrap_or_default(), english);
+        }
+    }
+    pool
+}
+
+// --max-rank 需要词频表或 JMdict（nf 标记的近似排名）
+fn require_rank_source(max_rank: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    if max_rank.is_some() && !levels::frequency_path().exists() && !jmdict::is_imported() {
+        return Err("no frequency list imported, run: jp import frequency frequency.txt (or import JMdict)".into());
+    }
+    Ok(())
+}
+
+// Handle the quiz subcommand：有汉字的词回答读音，只有假名的词看释义回答日语
+fn handle_quiz(jlpt: Option<JlptLevel>, max_rank: Option<u32>, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+    require_rank_source(max_rank)?;
+    let levels = levels::LevelIndex::load();
+    let mut pool: Vec<VocabItem> = quiz_pool(&levels).into_iter().filter(|item| item.tags.within(jlpt, max_rank)).collect();
+    if pool.is_empty() {
+        return Err(format!("no words match {}", filter_label(jlpt, max_rank)).into());
+    }
+
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    quiz::Shuffler::new().shuffle(&mut pool);
+    let questions: Vec<quiz::Question> = pool
+        .iter()
+        .take(count.max(1))
+        .map(|item| {
+            let has_kanji = item.word.chars().any(kana::is_kanji);
+            let (prompt, answer) = if has_kanji && !item.reading.is_empty() {
+                (format!("{}  的读音？", item.word), item.reading.clone())
+            } else {
+                (format!("「{}」用日语怎么说？", item.meaning), item.word.clone())
+            };
+            quiz::Question { prompt, answers: vec![answer], explanation: item.describe(&romanizer) }
+        })
+        .collect();
+
+    let label = filter_label(jlpt, max_rank);
+    let title = if label.is_empty() { "单词测验".to_string() } else { format!("单词测验（{}）", label) };
+    let kind = match jlpt {
+        Some(level) => format!("vocab-{}", level),
+        None => "vocab".to_string(),
+    };
+    quiz::run(&kind, &title, &questions, &romanizer);
+    Ok(())
+}
+
+// Handle the review subcommand：复习卡片和用户词典中的词，看答案后自评
+fn handle_review(jlpt: Option<JlptLevel>, max_rank: Option<u32>, count: usize, list: bool) -> Result<(), Box<dyn std::error::Error>> {
+    require_rank_source(max_rank)?;
+    let levels = levels::LevelIndex::load();
+    let mut cards: Vec<VocabItem> = Vec::new();
+    for card in review::ReviewDeck::load().cards() {
+        cards.push(VocabItem {
+            word: card.word.clone(),
+            reading: card.reading.clone(),
+            meaning: card.meaning.clone(),
+            tags: levels.tags(&card.word, Some(&card.reading)),
+        });
+    }
+    for entry in UserDictionary::load().entries() {
+        let translation = &entry.translation;
+        if cards.iter().any(|c| c.word == translation.japanese) {
+            continue;
+        }
+        let kana = Some(translation.kana.as_str()).filter(|k| !k.is_empty());
+        cards.push(VocabItem {
+            word: translation.japanese.clone(),
+            reading: translation.kana.clone(),
+            meaning: entry.chinese.clone(),
+            tags: levels.tags(&translation.japanese, kana),
+        });
+    }
+    if cards.is_empty() {
+        return Err("no saved words yet, add some with: jp read --file <PATH> --add, or save an LLM translation".into());
+    }
+    let mut cards: Vec<VocabItem> = cards.into_iter().filter(|c| c.tags.within(jlpt, max_rank)).collect();
+    if cards.is_empty() {
+        return Err(format!("no saved words match {}", filter_label(jlpt, max_rank)).into());
+    }
+
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    if list {
+        println!("📚 复习卡片（{} 张）", cards.len());
+        for card in &cards {
+            println!("   {}", card.describe(&romanizer));
+        }
+        return Ok(());
+    }
+
+    quiz::Shuffler::new().shuffle(&mut cards);
+    let questions: Vec<quiz::Question> = cards
+        .iter()
+        .take(count.max(1))
+        .map(|card| quiz::Question {
+            prompt: card.word.clone(),
+            answers: Vec::new(),
+            explanation: card.describe(&romanizer),
+        })
+        .collect();
+    let label = filter_label(jlpt, max_rank);
+    let title = if label.is_empty() { "复习".to_string() } else { format!("复习（{}）", label) };
+    quiz::run("review", &title, &questions, &romanizer);
+    Ok(())
+}
+
+// Handle the kanji subcommand: 每个汉字一张卡片
+fn handle_kanji(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !kanjidic::is_imported() {
+        return Err("KANJIDIC2 is not imported, run: jp import kanjidic kanjidic2.xml".into());
+    }
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    let mut found = false;
+    for c in text.chars().filter(|&c| kana::is_kanji(c)) {
+        let kanji = hanzi::to_japanese(&c.to_string()).chars().next().unwrap_or(c);
+        match kanjidic::lookup(kanji).or_else(|| kanjidic::lookup(c)) {
+            Some(entry) => kanjidic::print_card(&entry, &c.to_string(), &romanizer),
+            None => println!("❌ '{}' not found in KANJIDIC2", c),
+        }
+        found = true;
+    }
+    if !found {
+        return Err(format!("no kanji in '{}'", text).into());
+    }
+    Ok(())
+}
+
+// Handle the accent subcommand
+fn handle_accent(word: &str, svg: bool, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    if !accent::is_imported() {
+        return Err("no pitch accent data imported, run: jp import accent accents.txt".into());
+    }
+    let entries = accent::lookup(word);
+    if entries.is_empty() {
+        return Err(format!("'{}' not found in the pitch accent data", word).into());
+    }
+
+    if svg {
+        let items: Vec<(&str, u8)> = entries
+            .iter()
+            .flat_map(|e| e.patterns.iter().map(|p| (e.reading.as_str(), *p)))
+            .collect();
+        let image = accent::svg_sheet(&items);
+        match output {
+            Some(path) => {
+                std::fs::write(&path, image)?;
+                println!("✅ Wrote {} diagram(s) to {}", items.len(), path.display());
+            }
+            None => print!("{}", image),
+        }
+        return Ok(());
+    }
+
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    println!("╔═══════════════════════════════════════════════");
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            println!("╟───────────────────────────────────────────────");
+        }
+        println!("║ Japanese (日文): {}", entry.word);
+        println!("║ Kana (假名): {} ({})", entry.reading, romanizer.romanize(&entry.reading));
+        accent::print_accent(&entry.word, Some(&entry.reading));
+    }
+    println!("║");
+    println!("║ 💡 ┌ 表示音调升高，┐ 表示下降；最后的 が 是后接助词的音高");
+    println!("╚═══════════════════════════════════════════════");
+    Ok(())
+}
+
+// Handle the radicals subcommand：没有给出部件时进入交互模式，逐步添加部件缩小范围
+fn handle_radicals(parts: Vec<String>, list: bool, limit: usize, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, Write};
+
+    let index = radicals::RadicalIndex::load();
+    if index.is_empty() {
+        return Err("no radical data imported, run: jp import kradfile kradfile (or jp import radkfile radkfile)".into());
+    }
+    if list {
+        radicals::print_parts(&index);
+        return Ok(());
+    }
+    if !parts.is_empty() {
+        let result = index.search(&parts.join(" "));
+        if json {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        } else {
+            radicals::print_result(&result, limit);
+        }
+        return Ok(());
+    }
+
+    println!("🔍 按部件查汉字：输入部件添加（氵 每 / each），-部件 删除，clear 清空，list 显示全部部件，q 退出");
+    let mut query: Vec<String> = Vec::new();
+    let stdin = std::io::stdin();
+    loop {
+        print!("部件> ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "q" | "quit" | "exit" => break,
+            "clear" => {
+                query.clear();
+                continue;
+            }
+            "list" => {
+                radicals::print_parts(&index);
+                continue;
+            }
+            _ => {}
+        }
+        match line.strip_prefix('-') {
+            Some(part) => query.retain(|p| p != part.trim()),
+            None => query.push(line.to_string()),
+        }
+        if query.is_empty() {
+            continue;
+        }
+        radicals::print_result(&index.search(&query.join(" ")), limit);
+    }
+    Ok(())
+}
+
+// Handle the read subcommand
+fn handle_read(
+    text: Option<String>,
+    file: Option<PathBuf>,
+    format: Option<ReadFormat>,
+    output: Option<PathBuf>,
+    add: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tokenizer = tokenizer::Tokenizer::load();
+    let (title, content) = match (&file, text) {
+        (Some(path), _) => {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let title = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            (title, content)
+        }
+        (None, Some(text)) => (text.clone(), text),
+        (None, None) => return Err("no text or --file given".into()),
+    };
+
+    let mut deck = review::ReviewDeck::load();
+    let document = reader::read_document(&tokenizer, &title, &content, &deck);
+    let rendered = match format {
+        Some(ReadFormat::Html) => Some(reader::render_html(&document)),
+        Some(ReadFormat::Markdown) => Some(reader::render_markdown(&document)),
+        // 单句的 JSON 只包含分词结果
+        None if json && file.is_none() => Some(serde_json::to_string_pretty(&reader::read(&tokenizer, &content))?),
+        None if json => Some(serde_json::to_string_pretty(&document)?),
+        None => None,
+    };
+
+    // 结果输出到标准输出时，提示信息写到标准错误
+    let mut to_stdout = false;
+    match (rendered, &output) {
+        (Some(rendered), Some(path)) => {
+            std::fs::write(path, rendered).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            println!("✅ 已写入 {}", path.display());
+        }
+        (Some(rendered), None) => {
+            println!("{}", rendered.trim_end());
+            to_stdout = true;
+        }
+        (None, Some(_)) => return Err("--output requires --format or --json".into()),
+        (None, None) if file.is_some() => reader::print_document(&document),
+        (None, None) => reader::print_reading(&reader::read(&tokenizer, &content), &tokenizer),
+    }
+
+    let message = if add {
+        let added = reader::add_to_deck(&document, &mut deck);
+        deck.save()?;
+        Some(format!("✅ 已添加 {} 个生词到复习卡片: {}", added, review::review_path().display()))
+    } else if file.is_some() && !document.vocabulary.is_empty() {
+        Some("💡 使用 --add 把生词表添加到复习卡片".to_string())
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        if to_stdout {
+            eprintln!("{}", message);
+        } else {
+            println!();
+            println!("{}", message);
+        }
+    }
+    Ok(())
+}
+
+// Handle the examples subcommand
+async fn handle_examples(
+    word: String,
+    count: usize,
+    level: JlptLevel,
+    attach: bool,
+    card: Option<String>,
+    refresh: bool,
+    options: &LookupOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cached = if refresh && !options.offline {
+        None
+    } else {
+        examples::cached_examples(&word, level, count, options.offline)
+    };
+
+    let (set, from_cache) = match cached {
+        Some(set) => (set, true),
+        None if options.offline => {
+            return Err(format!("离线模式：缓存中没有 '{}' 的 {} 例句", word, level).into());
+        }
+        None => {
+            println!("🔍 正在使用 LLM 生成例句...");
+            let (set, dropped) = examples::generate_examples(&word, count, level).await?;
+            if dropped > 0 {
+                eprintln!("⚠️  丢弃了 {} 个没有包含「{}」的例句", dropped, word);
+            }
+            if let Err(e) = examples::cache_examples(level, count, &set) {
+                eprintln!("⚠️  Failed to save example cache: {}", e);
+            }
+            (set, false)
+        }
+    };
+
+    println!("╔═══════════════════════════════════════════════");
+    if set.reading.is_empty() {
+        println!("║ 📝 Examples (例句): {} [{}]", set.word, level);
+    } else {
+        println!("║ 📝 Examples (例句): {} ({}) [{}]", set.word, set.reading, level);
+    }
+    println!("╠═══════════════════════════════════════════════");
+    for (i, example) in set.examples.iter().enumerate() {
+        println!("║ {}. {}", i + 1, example.japanese);
+        if let Some(furigana) = &example.furigana {
+            println!("║    {}", furigana);
+        }
+        println!("║    {}", example.translation);
+    }
+    println!("║");
+    if from_cache {
+        println!("║ 💡 提示：这是由 AI 生成的例句（来自本地缓存）");
+    } else {
+        println!("║ 💡 提示：这是由 AI 生成的例句");
+    }
+    println!("╚═══════════════════════════════════════════════");
+
+    if attach {
+        let romaji_map = init_romaji_map();
+        let card = match card {
+            Some(card) => card.to_lowercase(),
+            None => examples::card_for_reading(&romaji_map, &set.reading)
+                .ok_or("无法根据读音确定单词卡，请使用 --card 指定罗马音")?,
+        };
+        if !romaji_map.contains_key(&card) {
+            return Err(format!("'{}' is not a kana flashcard", card).into());
+        }
+        let added = examples::attach_to_card(&card, &set.examples)?;
+        println!("✅ 已添加 {} 个例句到单词卡 {}（jp {} 查看）", added, card.to_uppercase(), card);
+    }
+
+    Ok(())
+}
+
+// Handle the usage subcommand
+fn handle_usage(since: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let now = storage::now_secs();
+    let (start, period) = match &since {
+        Some(since) => (now.saturating_sub(usage::parse_duration(since)?), format!("last {}", since)),
+        None => (usage::month_start(now), "this month".to_string()),
+    };
+    let summary = usage::summarize(start);
+    let total = &summary.total;
+
+    println!("📊 LLM usage ({}, since {}): {}", period, usage::format_date(start), usage::usage_path().display());
+    if total.calls == 0 {
+        println!("   No LLM calls recorded");
+    } else {
+        let tokens = |t: &usage::UsageTotals| t.prompt_tokens + t.completion_tokens;
+        println!("   Calls: {}", total.calls);
+        println!(
+            "   Tokens: {} prompt + {} completion = {}",
+            total.prompt_tokens, total.completion_tokens, tokens(total)
+        );
+        println!("   Cost: ${:.4}", total.cost);
+        println!("   Average latency: {} ms", total.avg_latency_ms());
+        if total.estimated > 0 {
+            println!("   ⚠️  {} call(s) without usage from the API, tokens estimated from text length", total.estimated);
+        }
+        if total.unpriced > 0 {
+            println!("   ⚠️  {} call(s) with unknown model price, set OPENAI_PRICE_INPUT / OPENAI_PRICE_OUTPUT", total.unpriced);
+        }
+        for (title, groups) in [("By model", &summary.models), ("By feature", &summary.features)] {
+            println!("   {}:", title);
+            for (name, t) in groups {
+                println!("   - {}: {} call(s), {} tokens, ${:.4}", name, t.calls, tokens(t), t.cost);
+            }
+        }
+    }
+
+    if let Some((spent, budget)) = usage::budget_status() {
+        let percent = spent / budget * 100.0;
+        let mark = if spent >= budget { "🚫" } else { "💰" };
+        println!("{} Monthly budget: ${:.4} of ${} ({:.0}%)", mark, spent, budget, percent);
+        let model = llm_model();
+        if usage::price(&model).is_none() {
+            println!("   🚫 Model '{}' has no known price, LLM calls are refused until OPENAI_PRICE_INPUT / OPENAI_PRICE_OUTPUT are set", model);
+        }
+    }
+
+    Ok(())
+}
+
+// Handle the prompts subcommands
+fn handle_prompts(action: PromptAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        PromptAction::List => {
+            if let Some(profile) = prompts::profile() {
+                println!("👤 Profile: {}", profile);
+            }
+            for name in prompts::names() {
+                match prompts::load(name) {
+                    Ok(template) => println!("   {:<16} {:08x}  {}", name, template.version(), template.source),
+                    Err(e) => println!("   {:<16} ❌ {}", name, e),
+                }
+            }
+        }
+        PromptAction::Show { name } => {
+            let template = prompts::load(&name)?;
+            println!("📝 {} ({}, version {:08x})", template.name, template.source, template.version());
+            let placeholders: Vec<String> = template.placeholders.iter().map(|p| format!("{{{}}}", p)).collect();
+            println!("   Placeholders: {}", placeholders.join(", "));
+            println!();
+            print!("{}", template.text);
+            if !template.text.ends_with('\n') {
+                println!();
+            }
+        }
+        PromptAction::Edit { name, global } => {
+            let builtin = prompts::builtin(&name)?;
+            let profile = if global { None } else { prompts::profile() };
+            let path = prompts::override_path(builtin.name, profile.as_deref());
+
+            // 第一次编辑时以当前生效的模板为起点；已有的覆盖文件即使有错误也可以继续编辑
+            if !path.exists() {
+                let current = prompts::load(builtin.name).unwrap_or(builtin);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, &current.text)?;
+            }
+            let before = std::fs::read_to_string(&path)?;
+
+            prompts::open_editor(&path)?;
+
+            let after = std::fs::read_to_string(&path)?;
+            let template = prompts::load(&name)
+                .map_err(|e| format!("{} (run 'jp prompts edit {}' to fix it or 'jp prompts reset {}')", e, name, name))?;
+            if before == after {
+                println!("ℹ️  {} unchanged (version {:08x})", path.display(), template.version());
+            } else {
+                println!("✅ Saved {} (version {:08x})", path.display(), template.version());
+                println!("   Cached results from the previous version will no longer be used");
+            }
+        }
+        PromptAction::Reset { name, global } => {
+            let builtin = prompts::builtin(&name)?;
+            let profile = if global { None } else { prompts::profile() };
+            let path = prompts::override_path(builtin.name, profile.as_deref());
+            if !path.exists() {
+                return Err(format!("{} has no override at {}", builtin.name, path.display()).into());
+            }
+            std::fs::remove_file(&path)?;
+            println!("🗑️  Removed {}", path.display());
+            match prompts::load(builtin.name) {
+                Ok(template) => println!("   Now using: {}", template.source),
+                Err(e) => eprintln!("⚠️  {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Handle the cache subcommands
+fn handle_cache(action: CacheAction) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cache = TranslationCache::load();
+
+    match action {
+        CacheAction::Stats => {
+            let stats = cache.stats();
+            let now = storage::now_secs();
+            let days_ago = |t: u64| now.saturating_sub(t) / (24 * 60 * 60);
+
+            println!("📦 Translation cache: {}", cache::cache_path().display());
+            println!("   Entries: {} ({} expired)", stats.total, stats.expired);
+            for (model, count) in &stats.models {
+                println!("   - {}: {}", model, count);
+            }
+            if let (Some(oldest), Some(newest)) = (stats.oldest, stats.newest) {
+                println!("   Oldest: {} days ago, newest: {} days ago", days_ago(oldest), days_ago(newest));
+            }
+        }
+        CacheAction::Clear { expired } => {
+            let removed = cache.clear(expired);
+            cache.save()?;
+            println!("🗑️  Removed {} cached translation(s)", removed);
+        }
+        CacheAction::Export { output } => {
+            let json = serde_json::to_string_pretty(cache.entries())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json)?;
+                    println!("✅ Exported {} entries to {}", cache.entries().len(), path.display());
+                }
+                None => println!("{}", json),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Handle the lookup functionality (original main logic)
+async fn handle_lookup(text: String, options: &LookupOptions) {
+    let romaji_map = init_romaji_map();
+    let chinese_map = init_chinese_map();
+    let mut user_dict = UserDictionary::load();
+
+    // Helpers for width-aware padding and printing
+    const INNER_WIDTH: usize = 85; // width between the vertical borders
+    fn pad_right(text: &str, width: usize) -> String {
+        let w = UnicodeWidthStr::width(text);
+        if w >= width { text.to_string() } else { format!("{}{}", text, " ".repeat(width - w)) }
+    }
+    fn center_text(text: &str, width: usize) -> String {
+        let w = UnicodeWidthStr::width(text);
+        if w >= width { text.to_string() } else {
+            let left = (width - w) / 2;
+            let right = width - w - left;
+            format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+        }
+    }
+    fn emit(text: &str) {
+        println!("║{}║", pad_right(text, INNER_WIDTH));
+    }
+    fn emit_center(text: &str) {
+        println!("║{}║", center_text(text, INNER_WIDTH));
     }
 
     let input = text.to_lowercase();
@@ -2236,61 +4192,277 @@ async fn handle_lookup(text: String) {
         }
         println!("╠═════════════════════════════════════════════════════════════════════════════════════╣");
         emit("  Example Words (例词):");
-        for (i, example) in jp_char.examples.iter().enumerate() {
-            emit(&format!("  {}. {}", i + 1, example));
+        let attached = examples::attached_examples(&jp_char.romaji);
+        let levels = levels::LevelIndex::load();
+        for (i, example) in jp_char.examples.iter().chain(attached.iter()).enumerate() {
+            let tags = example_tags(&levels, example);
+            if tags.is_empty() {
+                emit(&format!("  {}. {}", i + 1, example));
+            } else {
+                emit(&format!("  {}. {}  [{}]", i + 1, example, tags));
+            }
         }
         println!("╚═════════════════════════════════════════════════════════════════════════════════════╝\n");
     }
-    // 否则尝试作为中文查询
-    else if let Some(japanese) = chinese_map.get(&text) {
+    else {
+        match Direction::detect(&text, options.to) {
+            Some(Direction::ZhJa) => lookup_chinese(&text, &chinese_map, &mut user_dict, options).await,
+            Some(direction) => lookup_japanese(&text, direction, &romaji_map, &chinese_map, &user_dict, options).await,
+            None => {
+                // 英文单词：按 JMdict 的释义查询
+                let entries = jmdict::search_gloss(&input, 5);
+                if !entries.is_empty() {
+                    let romanizer = kana::Romanizer::new(&romaji_map);
+                    println!("╔═══════════════════════════════════════════════");
+                    println!("║ English (英文): {}", text);
+                    println!("╟───────────────────────────────────────────────");
+                    jmdict::print_entries(&entries, &romanizer, "English (英文)");
+                    println!("╚═══════════════════════════════════════════════");
+                    return;
+                }
+                println!("❌ Sorry, '{}' not found in the database.", text);
+                println!("💡 Try:");
+                println!("   - Romaji like: a, ka, chi, tsu, etc.");
+                println!("   - Chinese words like: 你好, 谢谢, 爱, 水, etc.");
+                println!("   - Japanese words like: ありがとう, 友達 --to en, etc.");
+                if !jmdict::is_imported() {
+                    println!("   - English words after importing JMdict: jp import jmdict JMdict_e.xml");
+                }
+            }
+        }
+    }
+}
+
+// 中译日：内置字典 → 用户词典 → LLM；繁体字和日文写法的汉字先转为简体再查
+async fn lookup_chinese(
+    text: &str,
+    chinese_map: &HashMap<String, String>,
+    user_dict: &mut UserDictionary,
+    options: &LookupOptions,
+) {
+    let simplified = hanzi::to_simplified(text);
+    if let Some(japanese) = chinese_map.get(text).or_else(|| chinese_map.get(&simplified)) {
         println!("╔═══════════════════════════════════════════════");
         println!("║ Chinese (中文): {}", text);
         println!("║ Japanese (日文): {}", japanese);
+        hanzi::print_comparison(text);
+        let (word, kana) = split_japanese_entry(japanese);
+        accent::print_accent(word, kana.first().copied());
+        levels::print_tags(levels::LevelIndex::load().tags(word, kana.first().copied()));
         println!("╚═══════════════════════════════════════════════");
     }
-    // 如果本地字典中找不到，尝试使用 LLM 翻译
-    else {
-        // 检查是否包含中文字符
-        let has_chinese = text.chars().any(|c| {
-            ('\u{4E00}'..='\u{9FFF}').contains(&c) || // CJK统一汉字
-            ('\u{3400}'..='\u{4DBF}').contains(&c)    // CJK扩展A
-        });
+    // 再查用户词典（从 LLM 翻译中收录的词条）
+    else if let Some(entry) = user_dict.get(text).or_else(|| user_dict.get(&simplified)) {
+        println!("╔═══════════════════════════════════════════════");
+        print_translation(text, &entry.translation, Direction::ZhJa);
+        println!("║");
+        if entry.review_needed {
+            println!("║ 📒 来自用户词典（AI 生成，待复核）");
+        } else {
+            println!("║ 📒 来自用户词典");
+        }
+        println!("╚═══════════════════════════════════════════════");
+    }
+    // 再通过 CC-CEDICT 的英文释义和相同的汉字在 JMdict 中查找候选词
+    else if !lookup_cedict_bridge(text) {
+        // 如果本地字典中找不到，尝试使用 LLM 翻译
+        if let Some(translation) = lookup_with_llm(text, Direction::ZhJa, options).await {
+            offer_to_save(user_dict, text, translation, options);
+        }
+    }
+}
 
-        if has_chinese {
-            println!("╔═══════════════════════════════════════════════");
-            println!("║ 🔍 本地字典未找到，正在使用 LLM 翻译...");
-            println!("╠═══════════════════════════════════════════════");
-
-            match translate_with_llm(&text).await {
-                Ok(translation) => {
-                    println!("║ Chinese (中文): {}", text);
-                    println!("║ Japanese (日文): {}", translation);
-                    println!("║");
-                    println!("║ 💡 提示：这是由 AI 生成的翻译");
-                    println!("╚═══════════════════════════════════════════════");
+// 中文 → CC-CEDICT → JMdict 的离线候选词，找到时输出并返回 true
+fn lookup_cedict_bridge(text: &str) -> bool {
+    if !jmdict::is_imported() {
+        return false;
+    }
+    let mut cedict_entries = cedict::lookup(text);
+    if cedict_entries.is_empty() {
+        cedict_entries = cedict::lookup(&hanzi::to_simplified(text));
+    }
+    let candidates = cedict::bridge(text, &cedict_entries, 5);
+    if candidates.is_empty() {
+        return false;
+    }
+    let romanizer = kana::Romanizer::new(&init_romaji_map());
+    println!("╔═══════════════════════════════════════════════");
+    cedict::print_bridge(text, &cedict_entries, &candidates, &romanizer);
+    println!("╚═══════════════════════════════════════════════");
+    true
+}
+
+// 日译中 / 日译英：先在本地字典中反查，找不到再使用 LLM
+async fn lookup_japanese(
+    text: &str,
+    direction: Direction,
+    romaji_map: &HashMap<String, JapaneseChar>,
+    chinese_map: &HashMap<String, String>,
+    user_dict: &UserDictionary,
+    options: &LookupOptions,
+) {
+    let matches = match direction {
+        Direction::JaEn => reverse_lookup_english(romaji_map, text),
+        _ => {
+            let mut matches = reverse_lookup_chinese(chinese_map, text);
+            for entry in user_dict.entries() {
+                let translation = &entry.translation;
+                if (translation.japanese == text || translation.kana == text) && !matches.contains(&entry.chinese) {
+                    matches.push(entry.chinese.clone());
                 }
-                Err(e) => {
-                    println!("║ ❌ LLM 翻译失败: {}", e);
-                    println!("║");
-                    println!("║ 💡 请确保已设置以下环境变量：");
-                    println!("║    - OPENAI_API_KEY: 你的 API key");
-                    println!("║    - OPENAI_API_BASE: API 地址 (可选)");
-                    println!("║    - OPENAI_MODEL: 模型名称 (可选)");
-                    println!("╚═══════════════════════════════════════════════");
+            }
+            matches
+        }
+    };
+
+    if !matches.is_empty() {
+        println!("╔═══════════════════════════════════════════════");
+        println!("║ Japanese (日文): {}", text);
+        println!("║ {}: {}", direction.target_label(), matches.join("、"));
+        accent::print_accent(text, None);
+        levels::print_tags(levels::LevelIndex::load().tags(text, None));
+        println!("╚═══════════════════════════════════════════════");
+        return;
+    }
+
+    // 再查导入的 JMdict（英文释义）；日译中时只作为参考，中文翻译仍然交给 LLM
+    let entries = jmdict::lookup(text);
+    if !entries.is_empty() {
+        let romanizer = kana::Romanizer::new(romaji_map);
+        println!("╔═══════════════════════════════════════════════");
+        jmdict::print_entries(&entries[..entries.len().min(3)], &romanizer, "English (英文)");
+        if direction == Direction::JaEn {
+            println!("╚═══════════════════════════════════════════════");
+            return;
+        }
+        println!("║");
+        println!("║ 💡 JMdict 只有英文释义，中文翻译见下方");
+        println!("╚═══════════════════════════════════════════════");
+    }
+
+    lookup_with_llm(text, direction, options).await;
+}
+
+// 通过翻译缓存或 LLM 翻译并输出结果，成功时返回翻译
+async fn lookup_with_llm(text: &str, direction: Direction, options: &LookupOptions) -> Option<Translation> {
+    let mut cache = TranslationCache::load();
+    let model = llm_model();
+    // 提示词模板的版本，模板修改后旧的缓存不再命中
+    let prompt_version = prompts::load(direction.template_name())
+        .map(|template| template.version())
+        .unwrap_or(0);
+
+    // 先查缓存；离线模式下不区分模型和有效期
+    let cached = if options.offline {
+        cache.get_any(text, direction).map(|entry| entry.translation.clone())
+    } else {
+        cache.get(text, direction, &model, prompt_version).cloned()
+    };
+
+    if let Some(translation) = cached {
+        println!("╔═══════════════════════════════════════════════");
+        print_translation(text, &translation, direction);
+        println!("║");
+        println!("║ 💡 提示：这是由 AI 生成的翻译（来自本地缓存）");
+        println!("╚═══════════════════════════════════════════════");
+        return Some(translation);
+    }
+
+    if options.offline {
+        println!("❌ 离线模式：本地字典和翻译缓存中都没有找到 '{}'", text);
+        println!("💡 去掉 --offline 参数即可使用 LLM 翻译");
+        return None;
+    }
+
+    println!("╔═══════════════════════════════════════════════");
+    println!("║ 🔍 本地字典未找到，正在使用 LLM 翻译...");
+    println!("╠═══════════════════════════════════════════════");
+
+    // 流式输出时边接收边显示各字段，结束后不再重复输出结果
+    let mut streamed = false;
+    let result = if streaming_enabled() {
+        let mut header = (direction == Direction::ZhJa).then(|| format!("║ Chinese (中文): {}", text));
+        let mut printer = stream::FieldPrinter::new(vec![
+            ("japanese", "Japanese (日文)".to_string()),
+            ("kana", "Kana (假名)".to_string()),
+            ("romaji", "Romaji (罗马音)".to_string()),
+            ("meaning", direction.target_label().to_string()),
+            ("notes", "Notes (说明)".to_string()),
+            ("alternatives", "Alternatives (其他说法)".to_string()),
+        ]);
+        let mut on_token = |token: &str| {
+            if let Some(header) = header.take() {
+                println!("{}", header);
+            }
+            printer.push(token);
+        };
+        let result = translate_with_llm(text, direction, Some(&mut on_token)).await;
+        printer.finish();
+        // 校验会规范化字段（如 Tōkyō → Toukyou），显示的内容与结果不同时视为临时输出，下面再输出结果
+        streamed = match &result {
+            Ok(translation) => printer.has_output() && printer.matches(&translation.fields()),
+            Err(_) => {
+                if printer.has_output() {
+                    println!("║ ⚠️  以上流式输出没有通过校验，已丢弃");
                 }
+                true
             }
-        } else {
-            println!("❌ Sorry, '{}' not found in the database.", text);
-            println!("💡 Try:");
-            println!("   - Romaji like: a, ka, chi, tsu, etc.");
-            println!("   - Chinese words like: 你好, 谢谢, 爱, 水, etc.");
+        };
+        if !streamed && printer.has_output() {
+            println!("╟───────────────────────────────────────────────");
+            println!("║ ✅ 校验后的结果（以上为流式输出的原文）：");
+        }
+        result
+    } else {
+        translate_with_llm(text, direction, None).await
+    };
+
+    match result {
+        Ok(translation) => {
+            if !streamed {
+                print_translation(text, &translation, direction);
+            }
+            println!("║");
+            println!("║ 💡 提示：这是由 AI 生成的翻译");
+            println!("╚═══════════════════════════════════════════════");
+
+            cache.insert(text, direction, &model, prompt_version, translation.clone());
+            if let Err(e) = cache.save() {
+                eprintln!("⚠️  Failed to save translation cache: {}", e);
+            }
+            Some(translation)
+        }
+        Err(e) => {
+            println!("║ ❌ LLM 翻译失败: {}", e);
+            println!("║");
+            for (i, hint) in e.hints().iter().enumerate() {
+                println!("║ {}{}", if i == 0 { "💡 " } else { "   " }, hint);
+            }
+            println!("╚═══════════════════════════════════════════════");
+            None
+        }
+    }
+}
+
+// 输出错误并退出；LLM 错误会附带处理建议
+fn exit_with_error(context: &str, e: &(dyn std::error::Error + 'static)) -> ! {
+    eprintln!("❌ {}: {}", context, e);
+    if let Some(llm_error) = e.downcast_ref::<LlmError>() {
+        for (i, hint) in llm_error.hints().iter().enumerate() {
+            eprintln!("{}{}", if i == 0 { "💡 " } else { "   " }, hint);
         }
     }
+    std::process::exit(1);
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let options = LookupOptions {
+        offline: cli.offline || env::var("JP_OFFLINE").map(|v| v == "1").unwrap_or(false),
+        save: cli.save,
+        to: cli.to,
+    };
 
     match (cli.command, cli.text) {
         (Some(Commands::Serve { port, host }), _) => {
@@ -2300,20 +4472,188 @@ async fn main() {
             }
         }
         (Some(Commands::Lookup { text }), _) => {
-            handle_lookup(text).await;
+            handle_lookup(text, &options).await;
+        }
+        (Some(Commands::Cache { action }), _) => {
+            if let Err(e) = handle_cache(action) {
+                eprintln!("❌ Cache error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Explain { sentence, json }), _) => {
+            if options.offline {
+                eprintln!("❌ 离线模式下无法使用 LLM 讲解");
+                std::process::exit(1);
+            }
+            match explain::explain_sentence(&sentence).await {
+                Ok(explanation) if json => match serde_json::to_string_pretty(&explanation) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => exit_with_error("Explain error", &e),
+                },
+                Ok(explanation) => explain::print_explanation(&explanation),
+                Err(e) => exit_with_error("Explain error", &e),
+            }
+        }
+        (Some(Commands::Read { text, file, format, output, add, json }), _) => {
+            if let Err(e) = handle_read(text, file, format, output, add, json) {
+                eprintln!("❌ Read error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Examples { word, count, level, attach, card, refresh }), _) => {
+            if let Err(e) = handle_examples(word, count as usize, level, attach, card, refresh, &options).await {
+                exit_with_error("Examples error", e.as_ref());
+            }
+        }
+        (Some(Commands::Dict { action }), _) => {
+            if let Err(e) = handle_dict(action) {
+                eprintln!("❌ Dictionary error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Usage { since }), _) => {
+            if let Err(e) = handle_usage(since) {
+                eprintln!("❌ Usage error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::MockLlm { port, host, script }), _) => {
+            if let Err(e) = mock_llm::serve(host, port, script).await {
+                eprintln!("❌ Error starting mock LLM server: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Kanji { text }), _) => {
+            if let Err(e) = handle_kanji(&text) {
+                eprintln!("❌ Kanji error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Accent { word, svg, output }), _) => {
+            if let Err(e) = handle_accent(&word, svg, output) {
+                eprintln!("❌ Accent error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Radicals { parts, list, limit, json }), _) => {
+            if let Err(e) = handle_radicals(parts, list, limit, json) {
+                eprintln!("❌ Radicals error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Conjugate { word, adj, drill, count, json }), _) => {
+            if let Err(e) = handle_conjugate(word, adj, drill, count, json) {
+                eprintln!("❌ Conjugate error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Number { value, quiz, count }), _) => {
+            if let Err(e) = handle_reading(ReadingKind::Number, value, quiz, count) {
+                eprintln!("❌ Number error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Date { value, quiz, count }), _) => {
+            if let Err(e) = handle_reading(ReadingKind::Date, value, quiz, count) {
+                eprintln!("❌ Date error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Time { value, quiz, count }), _) => {
+            if let Err(e) = handle_reading(ReadingKind::Time, value, quiz, count) {
+                eprintln!("❌ Time error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Counter { counter, number, drill, count }), _) => {
+            if let Err(e) = handle_counter(counter, number, drill, count) {
+                eprintln!("❌ Counter error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Katakana { word, approx }), _) => {
+            if let Err(e) = handle_katakana(word, approx) {
+                eprintln!("❌ Katakana error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Quiz { jlpt, max_rank, count }), _) => {
+            if let Err(e) = handle_quiz(jlpt, max_rank, count) {
+                eprintln!("❌ Quiz error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Review { jlpt, max_rank, count, list }), _) => {
+            if let Err(e) = handle_review(jlpt, max_rank, count, list) {
+                eprintln!("❌ Review error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Import { kind, path }), _) => {
+            if let Err(e) = handle_import(kind, &path) {
+                eprintln!("❌ Import error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        (Some(Commands::Prompts { action }), _) => {
+            if let Err(e) = handle_prompts(action) {
+                eprintln!("❌ Prompt error: {}", e);
+                std::process::exit(1);
+            }
         }
         (None, Some(text)) => {
-            handle_lookup(text).await;
+            handle_lookup(text, &options).await;
         }
         (None, None) => {
             // Default behavior: show help if no command or text provided
             eprintln!("❌ No input provided.");
             eprintln!("\nUsage:");
-            eprintln!("  jp <TEXT>                Lookup romaji or translate Chinese");
+            eprintln!("  jp <TEXT>                Lookup romaji or translate Chinese/Japanese");
             eprintln!("  jp serve [OPTIONS]       Start web server");
-            eprintln!("  jp lookup <TEXT>         Lookup romaji or translate Chinese");
+            eprintln!("  jp lookup <TEXT>         Lookup romaji or translate Chinese/Japanese");
+            eprintln!("  jp cache <ACTION>        Show, clear or export the translation cache");
+            eprintln!("  jp dict <ACTION>         List or review saved translations");
+            eprintln!("  jp read <TEXT>           Show a sentence with furigana and romaji (offline)");
+            eprintln!("  jp read --file <PATH>    Annotate a text file and list its new vocabulary");
+            eprintln!("  jp examples <WORD>       Generate example sentences with the LLM");
+            eprintln!("  jp explain <SENTENCE>    Explain the grammar of a Japanese sentence");
+            eprintln!("  jp kanji <KANJI>         Show kanji flashcards (needs KANJIDIC2)");
+            eprintln!("  jp radicals [PARTS]...   Find kanji by components (interactive without PARTS)");
+            eprintln!("  jp accent <WORD>         Show the pitch accent of a word (--svg for SVG)");
+            eprintln!("  jp conjugate <VERB>      Show the conjugated forms of a verb (--drill to practise)");
+            eprintln!("  jp conjugate --adj <ADJ> Show the forms of an い/な-adjective (--drill to practise)");
+            eprintln!("  jp number <NUMBER>       Read a number or price, e.g. 1,234円 (--quiz to practise)");
+            eprintln!("  jp date <DATE>           Read a date, e.g. 2026-03-14 (--quiz to practise)");
+            eprintln!("  jp time <TIME>           Read a time, e.g. 7:30 (--quiz to practise)");
+            eprintln!("  jp counter <本> [3]      Read a number with a counter, e.g. さんぼん (--drill to practise)");
+            eprintln!("  jp katakana <computer>   Katakana form of an English loanword (approximate for unknown words)");
+            eprintln!("  jp quiz [--jlpt N5]      Vocabulary quiz (--max-rank 2000 for common words only)");
+            eprintln!("  jp review [--list]       Review saved words (--jlpt, --max-rank filters)");
+            eprintln!("  jp import <KIND> <PATH>  Import a dictionary dataset (jmdict, cedict, kanjidic, kradfile, radkfile, accent, jlpt, frequency)");
+            eprintln!("  jp prompts <ACTION>      List, show or edit the LLM prompt templates");
+            eprintln!("  jp usage [--since 7d]    Show LLM token usage, cost and budget");
+            eprintln!("  jp mock-llm [OPTIONS]    Start a local mock LLM server for offline testing");
             eprintln!("\nRun 'jp --help' for more information.");
             std::process::exit(1);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation_accepts_macron_romaji() {
+        let translation: Translation = serde_json::from_str(r#"{"japanese":"東京","kana":"とうきょう","romaji":"Tōkyō"}"#).unwrap();
+        let translation = translation.validate().unwrap();
+        assert_eq!(translation.romaji, "Toukyou");
+        assert_eq!(expand_long_vowels("ōkii", "おおきい"), "ookii");
+        assert_eq!(expand_long_vowels("Ōsaka", "おおさか"), "Oosaka");
+        assert_eq!(expand_long_vowels("sensē", "せんせい"), "sensei");
+        assert_eq!(expand_long_vowels("rāmen", "ラーメン"), "raamen");
+        assert_eq!(expand_long_vowels("kōkō", "こうこう"), "koukou");
+        assert_eq!(expand_long_vowels("Tōkyō", ""), "Toukyou");
+        assert!(serde_json::from_str::<Translation>(r#"{"japanese":"東京","kana":"","romaji":"Tōkyō→"}"#).unwrap().validate().is_err());
+    }
+}
@@ -0,0 +1,457 @@
+// 本地模拟的 OpenAI 兼容接口（jp mock-llm）：不联网也能测试和演示所有 LLM 功能。
+// 先按脚本文件中的规则回复，没有匹配的规则时识别提示词模板，用内置字典生成回复
+use crate::{
+    init_chinese_map, init_romaji_map, is_kana, prompts, reverse_lookup_chinese, reverse_lookup_english,
+    split_japanese_entry, storage, ChatMessage, JapaneseChar, Translation,
+};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// 脚本规则：请求中最后一条 user 消息包含 match 时返回 reply（省略 match 表示匹配所有请求）
+#[derive(Deserialize)]
+struct ScriptRule {
+    #[serde(default, rename = "match")]
+    pattern: Option<String>,
+    // 字符串原样作为回复内容，其他 JSON 值序列化后作为回复内容
+    reply: serde_json::Value,
+    // 非 200 时 reply 作为错误信息，用于测试重试和错误提示
+    #[serde(default)]
+    status: Option<u16>,
+    // 错误回复附带的 Retry-After（秒）
+    #[serde(default)]
+    retry_after: Option<u64>,
+}
+
+struct MockState {
+    rules: Vec<ScriptRule>,
+    chinese_map: HashMap<String, String>,
+    romaji_map: HashMap<String, JapaneseChar>,
+}
+
+#[derive(Deserialize)]
+struct MockRequest {
+    #[serde(default)]
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    stream_options: Option<serde_json::Value>,
+}
+
+pub async fn serve(host: String, port: u16, script: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let rules: Vec<ScriptRule> = match &script {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&text).map_err(|e| format!("Invalid script {}: {}", path.display(), e))?
+        }
+        None => Vec::new(),
+    };
+
+    let state = Arc::new(MockState {
+        rules,
+        chinese_map: init_chinese_map(),
+        romaji_map: init_romaji_map(),
+    });
+    let app = router(state.clone());
+
+    let addr = format!("{}:{}", host, port).parse::<SocketAddr>()?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    println!("🤖 Mock LLM server started on http://{}:{}/v1", host, port);
+    if let Some(path) = &script {
+        println!("📜 Script: {} ({} rules)", path.display(), state.rules.len());
+    }
+    println!("💡 Point jp at it with:");
+    println!("   export OPENAI_API_BASE=http://{}:{}/v1", host, port);
+    println!("   export OPENAI_API_KEY=mock");
+    println!("📝 Press Ctrl+C to stop the server\n");
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+fn router(state: Arc<MockState>) -> Router {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/chat/completions", post(chat_completions))
+        .with_state(state)
+}
+
+async fn chat_completions(State(state): State<Arc<MockState>>, Json(request): Json<MockRequest>) -> Response {
+    let prompt = request
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(|m| m.content.as_str())
+        .unwrap_or("");
+    let model = if request.model.is_empty() { "mock".to_string() } else { request.model.clone() };
+
+    let rule = state
+        .rules
+        .iter()
+        .find(|r| r.pattern.as_deref().map(|p| prompt.contains(p)).unwrap_or(true));
+    let content = match rule {
+        Some(rule) => {
+            let status = rule.status.and_then(|s| StatusCode::from_u16(s).ok()).unwrap_or(StatusCode::OK);
+            if status != StatusCode::OK {
+                return error_response(status, &rule.reply, rule.retry_after);
+            }
+            match &rule.reply {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            }
+        }
+        None => dictionary_reply(&state, prompt),
+    };
+
+    println!("← {} chars → {} chars", prompt.chars().count(), content.chars().count());
+    let include_usage = request
+        .stream_options
+        .as_ref()
+        .and_then(|o| o.get("include_usage"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if request.stream {
+        stream_response(&model, prompt, &content, include_usage)
+    } else {
+        completion_response(&model, prompt, &content)
+    }
+}
+
+// 粗略估算的 token 数：每个字符算一个
+fn usage_json(prompt: &str, content: &str) -> serde_json::Value {
+    let prompt_tokens = prompt.chars().count();
+    let completion_tokens = content.chars().count();
+    serde_json::json!({
+        "prompt_tokens": prompt_tokens,
+        "completion_tokens": completion_tokens,
+        "total_tokens": prompt_tokens + completion_tokens
+    })
+}
+
+// OpenAI 格式的错误；reply 是对象时原样返回，方便模拟 insufficient_quota 等错误码
+fn error_response(status: StatusCode, reply: &serde_json::Value, retry_after: Option<u64>) -> Response {
+    let body = match reply {
+        serde_json::Value::Object(_) => reply.clone(),
+        serde_json::Value::String(message) => serde_json::json!({ "error": { "message": message } }),
+        other => serde_json::json!({ "error": { "message": other.to_string() } }),
+    };
+    let mut response = (status, Json(body)).into_response();
+    if let Some(secs) = retry_after {
+        response.headers_mut().insert(header::RETRY_AFTER, secs.into());
+    }
+    response
+}
+
+fn completion_response(model: &str, prompt: &str, content: &str) -> Response {
+    Json(serde_json::json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion",
+        "created": storage::now_secs(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }],
+        "usage": usage_json(prompt, content)
+    }))
+    .into_response()
+}
+
+// SSE 流式回复，每个分块几个字符
+fn stream_response(model: &str, prompt: &str, content: &str, include_usage: bool) -> Response {
+    let chars: Vec<char> = content.chars().collect();
+    let mut body = String::new();
+    for chunk in chars.chunks(4) {
+        let token: String = chunk.iter().collect();
+        let event = serde_json::json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [{ "index": 0, "delta": { "content": token } }]
+        });
+        body.push_str(&format!("data: {}\n\n", event));
+    }
+    if include_usage {
+        let event = serde_json::json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [],
+            "usage": usage_json(prompt, content)
+        });
+        body.push_str(&format!("data: {}\n\n", event));
+    }
+    body.push_str("data: [DONE]\n\n");
+    ([(header::CONTENT_TYPE, "text/event-stream")], body).into_response()
+}
+
+// 根据提示词模板识别请求类型，用本地字典生成回复
+fn dictionary_reply(state: &MockState, prompt: &str) -> String {
+    let Some((name, values)) = prompts::identify(prompt) else {
+        return "mock-llm: 无法识别的提示词".to_string();
+    };
+    let value = |key: &str| {
+        values
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let reply = match name {
+        "translate-zh-ja" => serde_json::json!(translate_zh_ja(state, &value("text"))),
+        "translate-ja-zh" => {
+            let text = value("text");
+            let meanings = reverse_lookup_chinese(&state.chinese_map, &text);
+            serde_json::json!(translate_ja(state, &text, meanings, "（模拟翻译）"))
+        }
+        "translate-ja-en" => {
+            let text = value("text");
+            let meanings = reverse_lookup_english(&state.romaji_map, &text);
+            serde_json::json!(translate_ja(state, &text, meanings, "(mock translation) "))
+        }
+        "examples" => {
+            let count = value("count").parse().unwrap_or(3);
+            examples(state, &value("word"), count)
+        }
+        "explain" => explain(state, &value("sentence")),
+        _ => return format!("mock-llm: 模板 {} 没有模拟回复", name),
+    };
+    reply.to_string()
+}
+
+// 在中文字典和假名例词中查找单词的 (平假名, 罗马音)
+fn reading(state: &MockState, word: &str) -> Option<(String, String)> {
+    let entries = state.chinese_map.values().cloned().chain(
+        state
+            .romaji_map
+            .values()
+            .flat_map(|c| c.examples.iter().filter_map(|e| e.split_once(" - ").map(|(left, _)| left.to_string()))),
+    );
+    for entry in entries {
+        let (entry_word, kana) = split_japanese_entry(&entry);
+        let romaji = entry
+            .split_once('(')
+            .and_then(|(_, reading)| reading.trim_end_matches(')').split('/').find(|part| part.is_ascii()))
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if entry_word == word || kana.contains(&word) {
+            let kana = kana.first().map(|k| k.to_string()).unwrap_or_else(|| entry_word.to_string());
+            return Some((kana, romaji));
+        }
+    }
+
+    // 字典中没有的假名逐个转换为罗马音
+    if !word.is_empty() && word.chars().all(is_kana) {
+        let romaji: String = word
+            .chars()
+            .filter_map(|c| {
+                let c = c.to_string();
+                state.romaji_map.values().find(|j| j.hiragana == c || j.katakana == c).map(|j| j.romaji.clone())
+            })
+            .collect();
+        return Some((word.to_string(), romaji));
+    }
+    None
+}
+
+fn translate_zh_ja(state: &MockState, text: &str) -> Translation {
+    match state.chinese_map.get(text) {
+        Some(entry) => {
+            let (word, _) = split_japanese_entry(entry);
+            let (kana, romaji) = reading(state, word).unwrap_or_default();
+            Translation {
+                japanese: word.to_string(),
+                kana,
+                romaji,
+                meaning: None,
+                notes: Some("mock-llm: 来自本地字典".to_string()),
+                alternatives: Vec::new(),
+            }
+        }
+        None => Translation {
+            japanese: "モック".to_string(),
+            kana: "もっく".to_string(),
+            romaji: "mokku".to_string(),
+            meaning: None,
+            notes: Some(format!("mock-llm: 本地字典中没有「{}」", text)),
+            alternatives: Vec::new(),
+        },
+    }
+}
+
+fn translate_ja(state: &MockState, text: &str, mut meanings: Vec<String>, fallback_prefix: &str) -> Translation {
+    let (kana, romaji) = reading(state, text).unwrap_or_else(|| ("もっく".to_string(), "mokku".to_string()));
+    let (meaning, notes) = if meanings.is_empty() {
+        (format!("{}{}", fallback_prefix, text), format!("mock-llm: 本地字典中没有「{}」", text))
+    } else {
+        (meanings.remove(0), "mock-llm: 来自本地字典".to_string())
+    };
+    Translation {
+        japanese: text.to_string(),
+        kana,
+        romaji,
+        meaning: Some(meaning),
+        notes: Some(notes),
+        alternatives: meanings,
+    }
+}
+
+fn examples(state: &MockState, word: &str, count: usize) -> serde_json::Value {
+    const PATTERNS: &[(&str, &str)] = &[
+        ("これは{}です。", "这是{}。"),
+        ("{}が好きです。", "我喜欢{}。"),
+        ("{}を見ました。", "我看到了{}。"),
+        ("{}はどこですか。", "{}在哪里？"),
+        ("毎日{}について考えます。", "我每天都会想到{}。"),
+    ];
+    let reading = reading(state, word).map(|(kana, _)| kana).unwrap_or_default();
+    let examples: Vec<serde_json::Value> = (0..count)
+        .map(|i| {
+            let (japanese, chinese) = PATTERNS[i % PATTERNS.len()];
+            let japanese = japanese.replace("{}", word);
+            serde_json::json!({
+                "japanese": japanese,
+                "furigana": "",
+                "translation": chinese.replace("{}", word),
+            })
+        })
+        .collect();
+    serde_json::json!({ "reading": reading, "examples": examples })
+}
+
+// 按文字种类（汉字、平假名、片假名）切分句子，单个平假名视为助词
+fn explain(state: &MockState, sentence: &str) -> serde_json::Value {
+    fn script(c: char) -> u8 {
+        match c {
+            '\u{3040}'..='\u{309F}' => 1,
+            '\u{30A0}'..='\u{30FF}' => 2,
+            c if c.is_alphanumeric() => 3,
+            _ => 0,
+        }
+    }
+
+    let mut segments: Vec<String> = Vec::new();
+    let mut last = 0;
+    for c in sentence.chars() {
+        let kind = script(c);
+        if kind == 0 {
+            last = 0;
+            continue;
+        }
+        match segments.last_mut() {
+            Some(segment) if kind == last => segment.push(c),
+            _ => segments.push(c.to_string()),
+        }
+        last = kind;
+    }
+
+    let tokens: Vec<serde_json::Value> = segments
+        .iter()
+        .map(|surface| {
+            let is_particle = surface.chars().count() == 1 && surface.chars().all(|c| script(c) == 1);
+            let (reading, _) = reading(state, surface).unwrap_or_default();
+            let meaning = reverse_lookup_chinese(&state.chinese_map, surface).into_iter().next().unwrap_or_default();
+            serde_json::json!({
+                "surface": surface,
+                "reading": reading,
+                "part_of_speech": if is_particle { "助词" } else { "未知" },
+                "base_form": null,
+                "conjugation": null,
+                "meaning": meaning,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "sentence": sentence,
+        "translation": format!("（模拟翻译）{}", sentence),
+        "tokens": tokens,
+        "grammar_points": ["mock-llm 按文字种类切分的示例讲解"],
+        "nuance": "",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chat_completion, translate_with_llm, ChatRequest, Direction, LlmError};
+
+    // 在随机端口上启动模拟服务，返回 API 地址
+    async fn start(rules: serde_json::Value) -> String {
+        let state = Arc::new(MockState {
+            rules: serde_json::from_value(rules).unwrap(),
+            chinese_map: init_chinese_map(),
+            romaji_map: init_romaji_map(),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        format!("http://{}/v1", addr)
+    }
+
+    fn request(prompt: &str) -> ChatRequest {
+        ChatRequest {
+            model: "mock".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            temperature: 0.3,
+            response_format: None,
+            stream: false,
+            stream_options: None,
+            feature: "test",
+        }
+    }
+
+    // chat_completion 从环境变量读取配置，几种回复放在同一个测试里，避免并行测试互相修改环境变量
+    #[tokio::test]
+    async fn chat_completion_against_mock_server() {
+        let dir = std::env::temp_dir().join(format!("jp-mock-llm-{}", std::process::id()));
+        let base = start(serde_json::json!([
+            { "match": "限流", "status": 429, "retry_after": 0, "reply": "Rate limit reached" },
+            { "match": "坏的", "reply": "{\"japanese\": \"壊れ" },
+            { "match": "你好", "reply": { "japanese": "こんにちは", "kana": "こんにちは", "romaji": "konnichiwa", "notes": null, "alternatives": [] } }
+        ]))
+        .await;
+        std::env::set_var("JP_DATA_DIR", &dir);
+        std::env::set_var("OPENAI_API_KEY", "mock");
+        std::env::set_var("OPENAI_API_BASE", &base);
+        std::env::set_var("OPENAI_MAX_RETRIES", "2");
+
+        let content = chat_completion(request("你好"), None).await.unwrap();
+        assert!(content.contains("こんにちは"));
+        let mut streamed = String::new();
+        let mut on_token = |token: &str| streamed.push_str(token);
+        let content = chat_completion(request("你好"), Some(&mut on_token)).await.unwrap();
+        assert_eq!(streamed, content);
+
+        // Retry-After: 0 优先于 1s、2s 的退避，重试用完后返回 Quota
+        let started = std::time::Instant::now();
+        let error = chat_completion(request("限流"), None).await.unwrap_err();
+        assert!(matches!(error, LlmError::Quota(_)), "{}", error);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+
+        let translation = translate_with_llm("你好", Direction::ZhJa, None).await.unwrap();
+        assert_eq!(translation.romaji, "konnichiwa");
+        let error = translate_with_llm("坏的", Direction::ZhJa, None).await.unwrap_err();
+        assert!(matches!(error, LlmError::BadResponse(_)), "{}", error);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
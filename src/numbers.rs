@@ -0,0 +1,442 @@
+// 数字、日期和时间的读法：汉数字写法和假名读音，处理 さんびゃく、ろっぴゃく、はっせん、ついたち、はつか 等音变，
+// 用于 jp number / jp date / jp time 和读法测验
+use crate::kana::Romanizer;
+use crate::quiz;
+
+const DIGIT_KANJI: [&str; 10] = ["〇", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+const DIGIT_KANA: [&str; 10] = ["", "いち", "に", "さん", "よん", "ご", "ろく", "なな", "はち", "きゅう"];
+
+// 万、億、兆
+const GROUPS: [(&str, &str); 4] = [("", ""), ("万", "まん"), ("億", "おく"), ("兆", "ちょう")];
+
+// 可以读的最大的数（9999 兆）
+pub const MAX_NUMBER: u64 = 9_999_999_999_999_999;
+
+const MONTH_KANA: [&str; 13] = [
+    "", "いちがつ", "にがつ", "さんがつ", "しがつ", "ごがつ", "ろくがつ", "しちがつ", "はちがつ", "くがつ", "じゅうがつ", "じゅういちがつ",
+    "じゅうにがつ",
+];
+
+// 日期读法中的特殊读音（其余是 数字 + にち）
+const DAY_KANA: &[(u64, &str)] = &[
+    (1, "ついたち"),
+    (2, "ふつか"),
+    (3, "みっか"),
+    (4, "よっか"),
+    (5, "いつか"),
+    (6, "むいか"),
+    (7, "なのか"),
+    (8, "ようか"),
+    (9, "ここのか"),
+    (10, "とおか"),
+    (14, "じゅうよっか"),
+    (20, "はつか"),
+    (24, "にじゅうよっか"),
+];
+
+// 星期，从星期日开始
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("日曜日", "にちようび"),
+    ("月曜日", "げつようび"),
+    ("火曜日", "かようび"),
+    ("水曜日", "すいようび"),
+    ("木曜日", "もくようび"),
+    ("金曜日", "きんようび"),
+    ("土曜日", "どようび"),
+];
+
+// 各种数量词中个位数（1-9）的读音和 10 的读音：(个位数, 十)
+const YEN_ONES: ([&str; 10], &str) = (["", "いちえん", "にえん", "さんえん", "よえん", "ごえん", "ろくえん", "ななえん", "はちえん", "きゅうえん"], "じゅうえん");
+const YEAR_ONES: ([&str; 10], &str) = (["", "いちねん", "にねん", "さんねん", "よねん", "ごねん", "ろくねん", "しちねん", "はちねん", "きゅうねん"], "じゅうねん");
+const HOUR_ONES: ([&str; 10], &str) = (["", "いちじ", "にじ", "さんじ", "よじ", "ごじ", "ろくじ", "しちじ", "はちじ", "くじ"], "じゅうじ");
+const MINUTE_ONES: ([&str; 10], &str) = (["", "いっぷん", "にふん", "さんぷん", "よんぷん", "ごふん", "ろっぷん", "ななふん", "はっぷん", "きゅうふん"], "じゅっぷん");
+const DAY_ONES: ([&str; 10], &str) = (["", "いちにち", "ににち", "さんにち", "よっか", "ごにち", "ろくにち", "しちにち", "はちにち", "くにち"], "じゅうにち");
+
+pub struct Reading {
+    // 写法（汉数字或日常写法）
+    pub written: String,
+    // 平假名读音
+    pub kana: String,
+}
+
+// 0-9999 的汉数字和读音；单独的 千 读作 せん，在 万 等前面时写作 一千、读作 いっせん
+fn group_reading(n: u64, before_unit: bool) -> (String, String) {
+    let digits = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10];
+    let mut kanji = String::new();
+    let mut kana = String::new();
+    for (place, &d) in digits.iter().enumerate() {
+        if d == 0 {
+            continue;
+        }
+        let d = d as usize;
+        match place {
+            0 => {
+                if d != 1 || before_unit {
+                    kanji.push_str(DIGIT_KANJI[d]);
+                }
+                kanji.push('千');
+                kana.push_str(&match d {
+                    1 if before_unit => "いっせん".to_string(),
+                    1 => "せん".to_string(),
+                    3 => "さんぜん".to_string(),
+                    8 => "はっせん".to_string(),
+                    _ => format!("{}せん", DIGIT_KANA[d]),
+                });
+            }
+            1 => {
+                if d != 1 {
+                    kanji.push_str(DIGIT_KANJI[d]);
+                }
+                kanji.push('百');
+                kana.push_str(&match d {
+                    1 => "ひゃく".to_string(),
+                    3 => "さんびゃく".to_string(),
+                    6 => "ろっぴゃく".to_string(),
+                    8 => "はっぴゃく".to_string(),
+                    _ => format!("{}ひゃく", DIGIT_KANA[d]),
+                });
+            }
+            2 => {
+                if d != 1 {
+                    kanji.push_str(DIGIT_KANJI[d]);
+                    kana.push_str(DIGIT_KANA[d]);
+                }
+                kanji.push('十');
+                kana.push_str("じゅう");
+            }
+            _ => {
+                kanji.push_str(DIGIT_KANJI[d]);
+                kana.push_str(DIGIT_KANA[d]);
+            }
+        }
+    }
+    (kanji, kana)
+}
+
+// 整数的汉数字写法和读音：1234 → 千二百三十四 / せんにひゃくさんじゅうよん
+pub fn number(n: u64) -> Reading {
+    if n == 0 {
+        return Reading { written: "零".to_string(), kana: "ゼロ".to_string() };
+    }
+    let mut written = String::new();
+    let mut kana = String::new();
+    for (i, &(unit_kanji, unit_kana)) in GROUPS.iter().enumerate().rev() {
+        let group = n / 10_000u64.pow(i as u32) % 10_000;
+        if group == 0 {
+            continue;
+        }
+        let (group_kanji, mut group_kana) = group_reading(group, i > 0);
+        // 一万、一億 要读出 いち；兆 前面的 1、8、10 促音化：いっちょう、はっちょう、じゅっちょう
+        if i > 0 && group == 1 {
+            written.push('一');
+            group_kana = if unit_kanji == "兆" { "いっ".to_string() } else { "いち".to_string() };
+        } else {
+            written.push_str(&group_kanji);
+            if unit_kanji == "兆" && group % 10 == 8 {
+                group_kana = format!("{}はっ", group_kana.strip_suffix("はち").unwrap_or(&group_kana));
+            } else if unit_kanji == "兆" && group % 100 == 10 {
+                group_kana = format!("{}じゅっ", group_kana.strip_suffix("じゅう").unwrap_or(&group_kana));
+            }
+        }
+        written.push_str(unit_kanji);
+        kana.push_str(&group_kana);
+        kana.push_str(unit_kana);
+    }
+    Reading { written, kana }
+}
+
+// 数字加量词的读音：exact 是整个数的特殊读音（ひとり、ついたち），ones 是个位数（和 10）接量词时的读音，
+// 其余是数字的读音加上 base
+pub fn with_counter(n: u64, exact: &[(u64, &str)], ones: &([&str; 10], &str), base: &str) -> String {
+    if let Some((_, kana)) = exact.iter().find(|(value, _)| *value == n) {
+        return kana.to_string();
+    }
+    let last = (n % 10) as usize;
+    if last != 0 {
+        let rest = if n >= 10 { number(n - last as u64).kana } else { String::new() };
+        return format!("{}{}", rest, ones.0[last]);
+    }
+    if !n.is_multiple_of(100) {
+        // 30 → さんじゅう + っぷん
+        let tens = number(n).kana;
+        return format!("{}{}", tens.strip_suffix("じゅう").unwrap_or(&tens), ones.1);
+    }
+    format!("{}{}", number(n).kana, base)
+}
+
+// 一千万 / いっせんまん 也可以说成 千万 / せんまん，测验时两种读法都算对
+pub fn with_sen_variant(reading: Reading) -> Vec<Reading> {
+    let variant = reading.kana.contains("いっせん").then(|| Reading {
+        written: reading.written.replace("一千", "千"),
+        kana: reading.kana.replace("いっせん", "せん"),
+    });
+    std::iter::once(reading).chain(variant).collect()
+}
+
+// 千位分隔的阿拉伯数字：1234 → 1,234
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+// 解析 "1,234"、"１２３４"、"1234円"，返回数字和是否是金额
+pub fn parse_number(text: &str) -> Result<(u64, bool), Box<dyn std::error::Error>> {
+    let text = text.trim();
+    let (text, yen) = match text.strip_suffix('円').or_else(|| text.strip_prefix('¥')).or_else(|| text.strip_prefix('￥')) {
+        Some(rest) => (rest, true),
+        None => (text, false),
+    };
+    let digits: String = text
+        .chars()
+        .filter(|c| !matches!(c, ',' | '，' | '_' | ' '))
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+    let n: u64 = digits.parse().map_err(|_| format!("'{}' is not a whole number", text))?;
+    if n > MAX_NUMBER {
+        return Err(format!("{} is too large (up to 9999兆)", text).into());
+    }
+    Ok((n, yen))
+}
+
+// 金额：1,234円 → せんにひゃくさんじゅうよえん
+pub fn yen(n: u64) -> Reading {
+    let kana = if n == 0 { "ゼロえん".to_string() } else { with_counter(n, &[], &YEN_ONES, "えん") };
+    Reading { written: format!("{}円", group_digits(n)), kana }
+}
+
+fn is_leap_year(year: u64) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: Option<u64>, month: u64) -> u64 {
+    match month {
+        2 if year.is_none_or(is_leap_year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// 星期几（0 是星期日），Zeller 公式
+fn weekday(year: u64, month: u64, day: u64) -> usize {
+    let (y, m) = if month < 3 { (year - 1, month + 12) } else { (year, month) };
+    let h = (day + 13 * (m + 1) / 5 + y + y / 4 - y / 100 + y / 400) % 7;
+    ((h + 6) % 7) as usize
+}
+
+// 解析 "2026-03-14"、"2026/3/14"、"2026年3月14日"、"3月14日"、"3/14"，返回 (年, 月, 日)
+pub fn parse_date(text: &str) -> Result<(Option<u64>, u64, u64), Box<dyn std::error::Error>> {
+    let numbers: Vec<u64> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("'{}' is not a date", text))?;
+    let (year, month, day) = match numbers[..] {
+        [year, month, day] => (Some(year), month, day),
+        [month, day] => (None, month, day),
+        _ => return Err(format!("'{}' is not a date (e.g. 2026-03-14 or 3月14日)", text).into()),
+    };
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(format!("'{}' is not a valid date", text).into());
+    }
+    if year.is_some_and(|y| y == 0 || y > 9999) {
+        return Err(format!("'{}' has an unsupported year", text).into());
+    }
+    Ok((year, month, day))
+}
+
+// 日期：2026年3月14日（土曜日）→ にせんにじゅうろくねん さんがつ じゅうよっか（どようび）
+pub fn date(year: Option<u64>, month: u64, day: u64) -> Reading {
+    let mut written = String::new();
+    let mut kana = Vec::new();
+    if let Some(year) = year {
+        written.push_str(&format!("{}年", year));
+        kana.push(with_counter(year, &[], &YEAR_ONES, "ねん"));
+    }
+    written.push_str(&format!("{}月{}日", month, day));
+    kana.push(MONTH_KANA[month as usize].to_string());
+    let mut day_kana = with_counter(day, DAY_KANA, &DAY_ONES, "にち");
+    if let Some(year) = year {
+        let (name, reading) = WEEKDAYS[weekday(year, month, day)];
+        written.push_str(&format!("（{}）", name));
+        day_kana.push_str(&format!("（{}）", reading));
+    }
+    kana.push(day_kana);
+    Reading { written, kana: kana.join(" ") }
+}
+
+// 解析 "7:30"、"19:05"、"7時30分"、"7時"，返回 (时, 分)
+pub fn parse_time(text: &str) -> Result<(u64, u64), Box<dyn std::error::Error>> {
+    let numbers: Vec<u64> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("'{}' is not a time", text))?;
+    let (hour, minute) = match numbers[..] {
+        [hour, minute] => (hour, minute),
+        [hour] => (hour, 0),
+        _ => return Err(format!("'{}' is not a time (e.g. 7:30 or 19:05)", text).into()),
+    };
+    if hour > 24 || minute > 59 {
+        return Err(format!("'{}' is not a valid time", text).into());
+    }
+    Ok((hour, minute))
+}
+
+fn clock(hour: u64, minute: u64, half: bool) -> Reading {
+    let mut written = format!("{}時", hour);
+    let mut kana = if hour == 0 { "れいじ".to_string() } else { with_counter(hour, &[], &HOUR_ONES, "じ") };
+    if half && minute == 30 {
+        written.push('半');
+        kana.push_str("はん");
+    } else if minute > 0 {
+        written.push_str(&format!("{}分", minute));
+        kana.push(' ');
+        kana.push_str(&with_counter(minute, &[], &MINUTE_ONES, "ふん"));
+    }
+    Reading { written, kana }
+}
+
+// 时间的各种说法：24 小时制、午前/午后（12 小时制），30 分还可以说 半
+pub fn time(hour: u64, minute: u64) -> Vec<Reading> {
+    let mut readings = vec![clock(hour, minute, false)];
+    let (period, period_kana, hour12) = if hour < 12 || hour == 24 { ("午前", "ごぜん", hour % 12) } else { ("午後", "ごご", hour - 12) };
+    for half in [false, true] {
+        if half && minute != 30 {
+            continue;
+        }
+        let reading = clock(hour12, minute, half);
+        readings.push(Reading {
+            written: format!("{}{}", period, reading.written),
+            kana: format!("{} {}", period_kana, reading.kana),
+        });
+    }
+    if minute == 30 {
+        readings.push(clock(hour, minute, true));
+    }
+    readings
+}
+
+pub fn print_readings(label: &str, input: &str, readings: &[Reading], romanizer: &Romanizer) {
+    println!("╔═══════════════════════════════════════════════");
+    println!("║ {}: {}", label, input);
+    for (i, reading) in readings.iter().enumerate() {
+        if i > 0 {
+            println!("╟───────────────────────────────────────────────");
+        }
+        println!("║ Japanese (日文): {}", reading.written);
+        println!("║ Kana (假名): {}", reading.kana);
+        println!("║ Romaji (罗马音): {}", romanizer.romanize(&reading.kana));
+    }
+    println!("╚═══════════════════════════════════════════════");
+}
+
+fn question(prompt: String, readings: &[Reading], romanizer: &Romanizer) -> quiz::Question {
+    let answers: Vec<String> = readings.iter().map(|r| r.kana.clone()).collect();
+    let explanation = readings
+        .iter()
+        .map(|r| format!("{}（{}）", r.written, romanizer.romanize(&r.kana)))
+        .collect::<Vec<_>>()
+        .join(" / ");
+    quiz::Question { prompt, answers, explanation }
+}
+
+// 随机的数字：位数也随机，让小数字和大数字都能出现
+fn random_number(shuffler: &mut quiz::Shuffler) -> u64 {
+    let digits = 1 + shuffler.next(8) as u32;
+    let low = if digits == 1 { 1 } else { 10u64.pow(digits - 1) };
+    low + shuffler.next((10u64.pow(digits) - low) as usize) as u64
+}
+
+// 读数字：一半是金额
+pub fn number_questions(count: usize, romanizer: &Romanizer) -> Vec<quiz::Question> {
+    let mut shuffler = quiz::Shuffler::new();
+    (0..count)
+        .map(|_| {
+            let n = random_number(&mut shuffler);
+            if shuffler.next(2) == 0 {
+                let reading = yen(n);
+                question(format!("{} 怎么读？", reading.written), &with_sen_variant(reading), romanizer)
+            } else {
+                question(format!("{} 怎么读？", group_digits(n)), &with_sen_variant(number(n)), romanizer)
+            }
+        })
+        .collect()
+}
+
+pub fn date_questions(count: usize, romanizer: &Romanizer) -> Vec<quiz::Question> {
+    let mut shuffler = quiz::Shuffler::new();
+    (0..count)
+        .map(|_| {
+            let month = 1 + shuffler.next(12) as u64;
+            let day = 1 + shuffler.next(days_in_month(None, month) as usize) as u64;
+            let reading = date(None, month, day);
+            question(format!("{} 怎么读？", reading.written), &[reading], romanizer)
+        })
+        .collect()
+}
+
+pub fn time_questions(count: usize, romanizer: &Romanizer) -> Vec<quiz::Question> {
+    let mut shuffler = quiz::Shuffler::new();
+    (0..count)
+        .map(|_| {
+            let hour = shuffler.next(24) as u64;
+            let minute = shuffler.next(60) as u64;
+            let readings = time(hour, minute);
+            question(format!("{}:{:02} 怎么读？", hour, minute), &readings, romanizer)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kana(readings: &[Reading]) -> Vec<&str> {
+        readings.iter().map(|r| r.kana.as_str()).collect()
+    }
+
+    #[test]
+    fn bare_sen_is_not_issen() {
+        assert_eq!(number(1000).written, "千");
+        assert_eq!(number(1000).kana, "せん");
+        assert_eq!(number(3000).kana, "さんぜん");
+        assert_eq!(number(8000).kana, "はっせん");
+        assert_eq!(kana(&with_sen_variant(number(1000))), ["せん"]);
+    }
+
+    #[test]
+    fn issen_before_man_accepts_both_readings() {
+        let readings = with_sen_variant(number(10_000_000));
+        assert_eq!(readings[0].written, "一千万");
+        assert_eq!(kana(&readings), ["いっせんまん", "せんまん"]);
+    }
+
+    #[test]
+    fn sound_changes() {
+        assert_eq!(number(300).kana, "さんびゃく");
+        assert_eq!(number(600).kana, "ろっぴゃく");
+        assert_eq!(number(1_000_000_000_000).kana, "いっちょう");
+        assert_eq!(yen(4).kana, "よえん");
+    }
+
+    #[test]
+    fn special_day_readings() {
+        assert_eq!(date(None, 1, 1).kana, "いちがつ ついたち");
+        assert_eq!(date(None, 4, 10).kana, "しがつ とおか");
+        assert_eq!(date(None, 4, 20).kana, "しがつ はつか");
+        assert_eq!(date(None, 9, 14).kana, "くがつ じゅうよっか");
+    }
+}
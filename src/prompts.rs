@@ -0,0 +1,262 @@
+// 可编辑的 LLM 提示词模板。
+// 查找顺序：当前 profile 的覆盖文件（JP_PROFILE）→ 全局覆盖文件 → 内置模板（prompts/ 目录）。
+// 模板的版本号由内容计算，修改模板后旧的缓存自动失效
+use crate::storage;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// (名称, 内置模板, 可用的占位符)
+const BUILTIN_TEMPLATES: &[(&str, &str, &[&str])] = &[
+    ("translate-zh-ja", include_str!("../prompts/translate-zh-ja.txt"), &["text"]),
+    ("translate-ja-zh", include_str!("../prompts/translate-ja-zh.txt"), &["text"]),
+    ("translate-ja-en", include_str!("../prompts/translate-ja-en.txt"), &["text"]),
+    ("examples", include_str!("../prompts/examples.txt"), &["word", "count", "level"]),
+    ("explain", include_str!("../prompts/explain.txt"), &["sentence"]),
+];
+
+pub enum TemplateSource {
+    BuiltIn,
+    User(PathBuf),
+    Profile(String, PathBuf),
+}
+
+impl fmt::Display for TemplateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateSource::BuiltIn => write!(f, "built-in"),
+            TemplateSource::User(path) => write!(f, "{}", path.display()),
+            TemplateSource::Profile(profile, path) => write!(f, "profile {}: {}", profile, path.display()),
+        }
+    }
+}
+
+pub struct PromptTemplate {
+    pub name: &'static str,
+    pub source: TemplateSource,
+    pub text: String,
+    pub placeholders: &'static [&'static str],
+}
+
+pub fn names() -> Vec<&'static str> {
+    BUILTIN_TEMPLATES.iter().map(|(name, _, _)| *name).collect()
+}
+
+// 当前 profile，通过 JP_PROFILE 设置
+pub fn profile() -> Option<String> {
+    env::var("JP_PROFILE").ok().map(|p| p.trim().to_string()).filter(|p| !p.is_empty())
+}
+
+// 覆盖文件的路径：profile 为 None 时是全局覆盖
+pub fn override_path(name: &str, profile: Option<&str>) -> PathBuf {
+    let dir = match profile {
+        Some(profile) => storage::data_dir().join("profiles").join(profile).join("prompts"),
+        None => storage::data_dir().join("prompts"),
+    };
+    dir.join(format!("{}.txt", name))
+}
+
+fn builtin_entry(name: &str) -> Result<(&'static str, &'static str, &'static [&'static str]), String> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .copied()
+        .ok_or_else(|| format!("unknown prompt template '{}' (available: {})", name, names().join(", ")))
+}
+
+// 内置模板，不受覆盖文件影响
+pub fn builtin(name: &str) -> Result<PromptTemplate, String> {
+    let (name, text, placeholders) = builtin_entry(name)?;
+    Ok(PromptTemplate {
+        name,
+        source: TemplateSource::BuiltIn,
+        text: text.to_string(),
+        placeholders,
+    })
+}
+
+// 找出与 user 提示词相符的模板，返回模板名和占位符的值
+pub fn identify(prompt: &str) -> Option<(&'static str, Vec<(&'static str, String)>)> {
+    names()
+        .into_iter()
+        .filter_map(|name| load(name).ok())
+        .find_map(|template| template.extract(prompt).map(|values| (template.name, values)))
+}
+
+pub fn load(name: &str) -> Result<PromptTemplate, String> {
+    let (name, _, placeholders) = builtin_entry(name)?;
+
+    let mut candidates = Vec::new();
+    if let Some(profile) = profile() {
+        let path = override_path(name, Some(&profile));
+        candidates.push((TemplateSource::Profile(profile, path.clone()), path));
+    }
+    let path = override_path(name, None);
+    candidates.push((TemplateSource::User(path.clone()), path));
+
+    for (source, path) in candidates {
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let template = PromptTemplate { name, source, text, placeholders };
+            template.sections().map_err(|e| format!("{}: {}", path.display(), e))?;
+            return Ok(template);
+        }
+    }
+
+    builtin(name)
+}
+
+// 用 $VISUAL / $EDITOR 打开文件，等待编辑器退出
+pub fn open_editor(path: &Path) -> Result<(), String> {
+    let default_editor = if cfg!(windows) { "notepad" } else { "vi" };
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| default_editor.to_string());
+
+    // 编辑器变量可能带参数，例如 "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(default_editor);
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| format!("Failed to start editor '{}': {}", editor, e))?;
+    if !status.success() {
+        return Err(format!("Editor '{}' exited with {}", editor, status));
+    }
+    Ok(())
+}
+
+// 32 位 FNV-1a 哈希
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x01000193))
+}
+
+impl PromptTemplate {
+    pub fn version(&self) -> u32 {
+        fnv1a(&self.text)
+    }
+
+    // 拆分 [system] 和 [user] 两部分
+    fn sections(&self) -> Result<(String, String), String> {
+        let mut system = Vec::new();
+        let mut user = Vec::new();
+        let mut current: Option<&mut Vec<&str>> = None;
+        let mut has_user = false;
+
+        for line in self.text.lines() {
+            match line.trim() {
+                "[system]" => current = Some(&mut system),
+                "[user]" => {
+                    has_user = true;
+                    current = Some(&mut user);
+                }
+                _ => {
+                    if let Some(section) = current.as_mut() {
+                        section.push(line);
+                    }
+                }
+            }
+        }
+
+        if !has_user {
+            return Err("missing [user] section".to_string());
+        }
+
+        // 检查未知的占位符，避免拼写错误被原样发给模型
+        for (start, _) in self.text.match_indices('{') {
+            let rest = &self.text[start + 1..];
+            if let Some(end) = rest.find('}') {
+                let key = &rest[..end];
+                let is_placeholder = !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+                if is_placeholder && !self.placeholders.contains(&key) {
+                    return Err(format!(
+                        "unknown placeholder {{{}}} (available: {})",
+                        key,
+                        self.placeholders.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+                    ));
+                }
+            }
+        }
+
+        Ok((system.join("\n").trim().to_string(), user.join("\n").trim().to_string()))
+    }
+
+    // 从渲染后的 user 提示词中取回占位符的值，与模板不符时返回 None（用于 mock-llm 识别请求）
+    pub fn extract(&self, prompt: &str) -> Option<Vec<(&'static str, String)>> {
+        let (_, section) = self.sections().ok()?;
+        let next_placeholder = |text: &str| {
+            self.placeholders
+                .iter()
+                .filter_map(|p| text.find(&format!("{{{}}}", p)).map(|i| (i, *p)))
+                .min_by_key(|(i, _)| *i)
+        };
+
+        let mut values: Vec<(&'static str, String)> = Vec::new();
+        let mut template = section.as_str();
+        let mut rest = prompt.trim();
+        while let Some((start, key)) = next_placeholder(template) {
+            rest = rest.strip_prefix(&template[..start])?;
+            template = &template[start + key.len() + 2..];
+
+            // 值一直到下一段固定文本为止
+            let literal_end = next_placeholder(template).map(|(i, _)| i).unwrap_or(template.len());
+            let literal = &template[..literal_end];
+            let value_end = if literal.is_empty() { rest.len() } else { rest.find(literal)? };
+            if !values.iter().any(|(k, _)| *k == key) {
+                values.push((key, rest[..value_end].to_string()));
+            }
+            rest = &rest[value_end..];
+        }
+
+        (rest == template).then_some(values)
+    }
+
+    // 填充占位符，返回 (system, user) 提示词
+    pub fn render(&self, values: &[(&str, &str)]) -> Result<(String, String), String> {
+        let (system, user) = self.sections()?;
+        Ok((substitute(&system, values), substitute(&user, values)))
+    }
+}
+
+// 按顺序扫描一遍模板替换 {key}；填入的值不会再被当作占位符（用户输入中的 {count} 原样保留）
+fn substitute(template: &str, values: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest[1..]
+            .find('}')
+            .and_then(|end| values.iter().find(|(key, _)| *key == &rest[1..end + 1]).map(|(key, value)| (key.len() + 2, *value)));
+        match value {
+            Some((len, value)) => {
+                result.push_str(value);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_substituted_once() {
+        let values = [("word", "{count}"), ("count", "3"), ("level", "N5")];
+        assert_eq!(substitute("{word} x{count} ({level}) {other} {", &values), "{count} x3 (N5) {other} {");
+        assert_eq!(substitute("{count}{count}", &values), "33");
+    }
+}
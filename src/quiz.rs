@@ -0,0 +1,177 @@
+// 测验：出题、判分和成绩记录（quiz.json）。单词测验、复习和各种练习共用这里的流程和计分
+use crate::kana::{self, Romanizer};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+const QUIZ_FILE: &str = "quiz.json";
+// 每种测验保留的成绩条数
+const HISTORY_LIMIT: usize = 100;
+
+pub struct Question {
+    pub prompt: String,
+    // 接受的答案，假名答案也接受对应的罗马音；为空时是自评题（显示答案后问是否记住）
+    pub answers: Vec<String>,
+    // 答题后显示的说明
+    pub explanation: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuizRecord {
+    kind: String,
+    total: usize,
+    correct: usize,
+    at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct QuizHistory {
+    records: Vec<QuizRecord>,
+}
+
+pub struct Score {
+    // 作答的题数，跳过的题不计入
+    pub total: usize,
+    pub correct: usize,
+}
+
+// 简单的伪随机数（xorshift），用于打乱题目顺序
+pub struct Shuffler(u64);
+
+impl Shuffler {
+    pub fn new() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Shuffler(nanos | 1)
+    }
+
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound.max(1) as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.next(i + 1));
+        }
+    }
+}
+
+// 比较时忽略空格、标点和大小写，片假名视为平假名
+fn normalize(text: &str) -> String {
+    kana::to_hiragana(text)
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '。' | '、' | '.' | ',' | '・' | '-' | '\''))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn is_correct(input: &str, answers: &[String], romanizer: &Romanizer) -> bool {
+    let input = normalize(input);
+    !input.is_empty()
+        && answers.iter().any(|answer| {
+            let answer = normalize(answer);
+            answer == input || (input.is_ascii() && normalize(&romanizer.romanize(&answer)) == input)
+        })
+}
+
+fn read_answer(prompt: &str) -> Option<String> {
+    print!("{}", prompt);
+    let _ = std::io::stdout().flush();
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+// 依次出题，直接回车跳过，q 提前结束；返回得分并记录到成绩中（一题都没答时不记录）
+pub fn run(kind: &str, title: &str, questions: &[Question], romanizer: &Romanizer) -> Score {
+    println!("📝 {}：共 {} 题（直接回车跳过，q 结束）", title, questions.len());
+    let mut score = Score { total: 0, correct: 0 };
+    let mut skipped: Vec<&Question> = Vec::new();
+    for (i, question) in questions.iter().enumerate() {
+        println!();
+        println!("[{}/{}] {}", i + 1, questions.len(), question.prompt);
+        let correct = if question.answers.is_empty() {
+            let Some(input) = read_answer("   回车显示答案 > ") else {
+                break;
+            };
+            if input == "q" {
+                break;
+            }
+            println!("   {}", question.explanation);
+            let Some(remembered) = read_answer("   记住了吗？[y/N] > ") else {
+                break;
+            };
+            matches!(remembered.to_lowercase().as_str(), "y" | "yes" | "是")
+        } else {
+            let Some(input) = read_answer("   > ") else {
+                break;
+            };
+            if input == "q" {
+                break;
+            }
+            if input.is_empty() {
+                println!("   ⏭️  跳过");
+                skipped.push(question);
+                continue;
+            }
+            let correct = is_correct(&input, &question.answers, romanizer);
+            if correct {
+                println!("   ✅ 正确！{}", question.explanation);
+            } else {
+                println!("   ❌ 答案: {}  {}", question.answers[0], question.explanation);
+            }
+            correct
+        };
+        score.total += 1;
+        if correct {
+            score.correct += 1;
+        }
+    }
+
+    println!();
+    if !skipped.is_empty() {
+        println!("⏭️  跳过 {} 题：", skipped.len());
+        for question in &skipped {
+            println!("   {} → {}  {}", question.prompt, question.answers[0], question.explanation);
+        }
+    }
+    if score.total == 0 {
+        return score;
+    }
+    println!("🏁 得分: {}/{}（{}%）", score.correct, score.total, score.correct * 100 / score.total);
+    match record(kind, &score) {
+        Ok(average) => println!("📈 最近 {} 次「{}」平均正确率: {}%", average.0, title, average.1),
+        Err(e) => eprintln!("⚠️  Failed to save quiz history: {}", e),
+    }
+    score
+}
+
+// 保存成绩，返回同类测验最近 10 次的次数和平均正确率
+fn record(kind: &str, score: &Score) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    let mut history: QuizHistory = storage::load_json(QUIZ_FILE).unwrap_or_default();
+    history.records.push(QuizRecord {
+        kind: kind.to_string(),
+        total: score.total,
+        correct: score.correct,
+        at: storage::now_secs(),
+    });
+    let same_kind = history.records.iter().filter(|r| r.kind == kind).count();
+    if same_kind > HISTORY_LIMIT {
+        if let Some(oldest) = history.records.iter().position(|r| r.kind == kind) {
+            history.records.remove(oldest);
+        }
+    }
+    storage::save_json(QUIZ_FILE, &history)?;
+
+    let recent: Vec<&QuizRecord> = history.records.iter().rev().filter(|r| r.kind == kind).take(10).collect();
+    let total: usize = recent.iter().map(|r| r.total).sum();
+    let correct: usize = recent.iter().map(|r| r.correct).sum();
+    Ok((recent.len(), correct * 100 / total.max(1)))
+}
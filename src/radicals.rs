@@ -0,0 +1,339 @@
+// 按部件查汉字：导入 KRADFILE（汉字 → 部件）或 RADKFILE（部件 → 汉字），合并保存为数据目录下的 radicals.json。
+// 输入的部件可以是部首的常见写法（氵、亻、扌）、一个汉字（使用它的全部部件）或英文释义（each → 毎 的部件）
+use crate::hanzi;
+use crate::kanjidic::{self, KanjiEntry};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+const RADICALS_FILE: &str = "radicals.json";
+
+// KRADFILE/RADKFILE 用字形相近的汉字代替不能单独编码的偏旁，这里把常见写法对应过去
+// （简体中文的偏旁对应到日文写法）
+const ALIASES: &[(&str, &[&str])] = &[
+    ("亻", &["化"]),
+    ("⺅", &["化"]),
+    ("𠆢", &["个"]),
+    ("丷", &["并"]),
+    ("刂", &["刈"]),
+    ("⺉", &["刈"]),
+    ("辶", &["込"]),
+    ("⻌", &["込"]),
+    ("⻍", &["込"]),
+    ("⺌", &["尚"]),
+    ("⺍", &["尚"]),
+    ("忄", &["忙"]),
+    ("⺖", &["忙"]),
+    ("扌", &["扎"]),
+    ("⺘", &["扎"]),
+    ("氵", &["汁"]),
+    ("⺡", &["汁"]),
+    ("犭", &["犯"]),
+    ("⺨", &["犯"]),
+    ("艹", &["艾"]),
+    ("⺾", &["艾"]),
+    ("⻏", &["邦"]),
+    ("⻖", &["阡"]),
+    ("阝", &["阡", "邦"]),
+    ("耂", &["老"]),
+    ("⺹", &["老"]),
+    ("灬", &["杰"]),
+    ("⺣", &["杰"]),
+    ("礻", &["礼"]),
+    ("⺭", &["礼"]),
+    ("衤", &["初"]),
+    ("⻂", &["初"]),
+    ("罒", &["買"]),
+    ("⺲", &["買"]),
+    ("𠂉", &["乞"]),
+    ("讠", &["言"]),
+    ("钅", &["金"]),
+    ("饣", &["食"]),
+    ("纟", &["糸"]),
+    ("糹", &["糸"]),
+    ("釒", &["金"]),
+    ("飠", &["食"]),
+];
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct RadicalIndex {
+    // 部件的笔画数（只有 RADKFILE 提供）
+    #[serde(default)]
+    strokes: BTreeMap<String, u8>,
+    // 汉字 → 部件
+    #[serde(default)]
+    kanji: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct KanjiMatch {
+    pub kanji: String,
+    pub strokes: Option<u8>,
+    pub freq: Option<u32>,
+    pub meanings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    // 实际用于检索的部件
+    pub parts: Vec<String>,
+    // 无法识别的输入
+    pub unknown: Vec<String>,
+    pub kanji: Vec<KanjiMatch>,
+}
+
+impl RadicalIndex {
+    pub fn load() -> Self {
+        storage::load_json(RADICALS_FILE).unwrap_or_else(|e| {
+            eprintln!("⚠️  Ignoring radical index: {}", e);
+            RadicalIndex::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        storage::save_json(RADICALS_FILE, self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kanji.is_empty()
+    }
+
+    fn add_part(&mut self, kanji: &str, part: &str) {
+        let parts = self.kanji.entry(kanji.to_string()).or_default();
+        if !parts.iter().any(|p| p == part) {
+            parts.push(part.to_string());
+        }
+    }
+
+    fn is_part(&self, part: &str) -> bool {
+        self.strokes.contains_key(part) || self.kanji.values().any(|parts| parts.iter().any(|p| p == part))
+    }
+
+    // 所有部件，按笔画数排列（没有笔画数时按字符顺序）
+    pub fn parts(&self) -> Vec<(String, Option<u8>)> {
+        let mut parts: Vec<(String, Option<u8>)> = self.strokes.iter().map(|(p, s)| (p.clone(), Some(*s))).collect();
+        for part in self.kanji.values().flatten() {
+            if !parts.iter().any(|(p, _)| p == part) {
+                parts.push((part.clone(), None));
+            }
+        }
+        parts.sort_by(|a, b| (a.1.is_none(), a.1, &a.0).cmp(&(b.1.is_none(), b.1, &b.0)));
+        parts
+    }
+
+    // 把一个输入解析为部件，每个部件可以有几种写法（阝 分左右）
+    fn resolve(&self, token: &str, dictionary: &[KanjiEntry]) -> Option<Vec<Vec<String>>> {
+        if let Some((_, parts)) = ALIASES.iter().find(|(alias, _)| *alias == token) {
+            // 转换过的数据文件中可能直接使用偏旁本身
+            let options = std::iter::once(token).chain(parts.iter().copied()).map(str::to_string).collect();
+            return Some(vec![options]);
+        }
+        if self.is_part(token) {
+            return Some(vec![vec![token.to_string()]]);
+        }
+        // 简体、繁体的写法
+        let japanese = hanzi::to_japanese(token);
+        if japanese != token && self.is_part(&japanese) {
+            return Some(vec![vec![japanese]]);
+        }
+        // 一个完整的汉字：使用它的全部部件
+        if let Some(parts) = self.kanji.get(&japanese) {
+            return Some(parts.iter().map(|p| vec![p.clone()]).collect());
+        }
+        // 英文释义：使用 KANJIDIC2 中释义完全相同的最常用汉字
+        if token.is_ascii() {
+            let word = token.to_lowercase();
+            let entry = dictionary
+                .iter()
+                .filter(|e| e.meanings.iter().any(|m| m.to_lowercase() == word))
+                .min_by_key(|e| e.freq.unwrap_or(u32::MAX))?;
+            let parts = self.kanji.get(&entry.literal)?;
+            return Some(parts.iter().map(|p| vec![p.clone()]).collect());
+        }
+        None
+    }
+
+    // 检索包含全部部件的汉字，按笔画数排序，笔画数相同时常用字在前
+    pub fn search(&self, query: &str) -> SearchResult {
+        let dictionary = kanjidic::entries();
+        let mut required: Vec<Vec<String>> = Vec::new();
+        let mut unknown = Vec::new();
+        for token in split_query(query) {
+            match self.resolve(&token, &dictionary) {
+                Some(parts) => {
+                    for part in parts {
+                        if !required.contains(&part) {
+                            required.push(part);
+                        }
+                    }
+                }
+                None => unknown.push(token),
+            }
+        }
+
+        let info: HashMap<&str, &KanjiEntry> = dictionary.iter().map(|e| (e.literal.as_str(), e)).collect();
+        let mut kanji: Vec<KanjiMatch> = Vec::new();
+        if !required.is_empty() {
+            for (literal, parts) in &self.kanji {
+                if required.iter().all(|options| options.iter().any(|o| parts.contains(o))) {
+                    let entry = info.get(literal.as_str());
+                    kanji.push(KanjiMatch {
+                        kanji: literal.clone(),
+                        strokes: entry.map(|e| e.strokes),
+                        freq: entry.and_then(|e| e.freq),
+                        meanings: entry.map(|e| e.meanings.clone()).unwrap_or_default(),
+                    });
+                }
+            }
+        }
+        kanji.sort_by_key(|k| (k.strokes.is_none(), k.strokes, k.freq.is_none(), k.freq));
+
+        SearchResult {
+            parts: required.into_iter().map(|options| options.join("/")).collect(),
+            unknown,
+            kanji,
+        }
+    }
+}
+
+// "氵+每"、"氵 each"、"氵每" 都拆成单独的部件；英文单词保持完整
+fn split_query(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in query.split(|c: char| c.is_whitespace() || matches!(c, '+' | ',' | '，' | '、' | '＋')) {
+        if word.is_empty() {
+            continue;
+        }
+        if word.is_ascii() {
+            tokens.push(word.to_string());
+        } else {
+            tokens.extend(word.chars().map(|c| c.to_string()));
+        }
+    }
+    tokens
+}
+
+// 读取 EDRDG 的数据文件：原始文件是 EUC-JP 编码，也接受转换过的 UTF-8
+fn read_text(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(e) => {
+            let (text, _, _) = encoding_rs::EUC_JP.decode(e.as_bytes());
+            Ok(text.into_owned())
+        }
+    }
+}
+
+// KRADFILE：每行 "亜 : ｜ 一 口"；合并到已导入的索引中，返回汉字数
+pub fn import_kradfile(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let content = read_text(path)?;
+    let mut index = RadicalIndex::load();
+    let mut count = 0;
+    for line in content.lines().filter(|l| !l.starts_with('#')) {
+        let Some((kanji, parts)) = line.split_once(" : ") else {
+            continue;
+        };
+        for part in parts.split_whitespace() {
+            index.add_part(kanji.trim(), part);
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(format!("no KRADFILE entries found in {}", path.display()).into());
+    }
+    index.save()?;
+    Ok(count)
+}
+
+// RADKFILE：部件行 "$ 一 1"（部件和笔画数），后面几行是包含该部件的汉字；返回部件数
+pub fn import_radkfile(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let content = read_text(path)?;
+    let mut index = RadicalIndex::load();
+    let mut current: Option<String> = None;
+    let mut count = 0;
+    for line in content.lines().filter(|l| !l.starts_with('#')) {
+        if let Some(header) = line.strip_prefix("$ ") {
+            let mut fields = header.split_whitespace();
+            let (Some(part), Some(strokes)) = (fields.next(), fields.next().and_then(|s| s.parse().ok())) else {
+                current = None;
+                continue;
+            };
+            index.strokes.insert(part.to_string(), strokes);
+            current = Some(part.to_string());
+            count += 1;
+        } else if let Some(part) = &current {
+            for kanji in line.trim().chars() {
+                index.add_part(&kanji.to_string(), part);
+            }
+        }
+    }
+    if count == 0 {
+        return Err(format!("no RADKFILE radicals found in {}", path.display()).into());
+    }
+    index.save()?;
+    Ok(count)
+}
+
+pub fn radicals_path() -> std::path::PathBuf {
+    storage::data_file(RADICALS_FILE)
+}
+
+// 输出检索结果：按笔画数分组，最多 limit 个
+pub fn print_result(result: &SearchResult, limit: usize) {
+    if !result.unknown.is_empty() {
+        println!("⚠️  未识别的部件: {}", result.unknown.join("、"));
+    }
+    if result.parts.is_empty() {
+        return;
+    }
+    println!("🔍 部件: {}  →  {} 个汉字", result.parts.join(" + "), result.kanji.len());
+
+    let mut groups: Vec<(Option<u8>, Vec<&str>)> = Vec::new();
+    for m in result.kanji.iter().take(limit) {
+        match groups.last_mut() {
+            Some((strokes, chars)) if *strokes == m.strokes => chars.push(&m.kanji),
+            _ => groups.push((m.strokes, vec![&m.kanji])),
+        }
+    }
+    for (strokes, chars) in groups {
+        let label = strokes.map(|s| format!("{:>2} 画", s)).unwrap_or_else(|| " ? 画".to_string());
+        println!("  {}  {}", label, chars.join(" "));
+    }
+    if result.kanji.len() > limit {
+        println!("  … 还有 {} 个，添加更多部件缩小范围", result.kanji.len() - limit);
+    }
+    // 结果不多时显示释义，方便确认
+    if (1..=5).contains(&result.kanji.len()) {
+        for m in &result.kanji {
+            if !m.meanings.is_empty() {
+                println!("  {}  {}", m.kanji, m.meanings.join("; "));
+            }
+        }
+    }
+}
+
+// 输出全部部件，按笔画数分行
+pub fn print_parts(index: &RadicalIndex) {
+    let mut line_strokes: Option<Option<u8>> = None;
+    let mut line: Vec<String> = Vec::new();
+    for (part, strokes) in index.parts() {
+        if line_strokes != Some(strokes) {
+            if let Some(previous) = line_strokes {
+                print_parts_line(previous, &line);
+            }
+            line_strokes = Some(strokes);
+            line.clear();
+        }
+        line.push(part);
+    }
+    if let Some(previous) = line_strokes {
+        print_parts_line(previous, &line);
+    }
+    println!("💡 偏旁也可以直接输入常见写法：氵 亻 扌 忄 艹 辶 刂 阝 灬 礻 衤 罒");
+}
+
+fn print_parts_line(strokes: Option<u8>, parts: &[String]) {
+    let label = strokes.map(|s| format!("{:>2} 画", s)).unwrap_or_else(|| " ? 画".to_string());
+    println!("  {}  {}", label, parts.join(" "));
+}
@@ -0,0 +1,437 @@
+// 离线阅读（jp read）：分词后在汉字上方标注假名，并输出罗马音和每个词的原形、活用。
+// 读取整个文件时输出带 <ruby> 注音的 HTML / Markdown，并列出复习卡片中还没有的生词
+use crate::explain::render_table;
+use crate::kana;
+use crate::review::{ReviewCard, ReviewDeck};
+use crate::storage;
+use crate::tokenizer::{Pos, Token, Tokenizer};
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+// 终端中每行最多显示的宽度
+const LINE_WIDTH: usize = 70;
+
+#[derive(Serialize)]
+pub struct Reading {
+    pub text: String,
+    // 括号注音格式：今日[きょう]は良[よ]い天気[てんき]です
+    pub furigana: String,
+    pub romaji: String,
+    pub tokens: Vec<Token>,
+}
+
+#[derive(Serialize)]
+pub struct VocabWord {
+    pub word: String,
+    pub reading: String,
+    pub pos: Pos,
+    pub meaning: String,
+    // 在文中出现的次数
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct Document {
+    pub title: String,
+    // 每行一个 Reading，空行表示段落分隔
+    pub lines: Vec<Reading>,
+    pub vocabulary: Vec<VocabWord>,
+    // 词典中没有的词
+    pub unknown: Vec<String>,
+}
+
+// 把一个词拆成 (原文, 注音) 片段：每段汉字单独注音，送假名和词中的假名不注音
+// 例：食べ物 / たべもの → [("食", "た"), ("べ", None), ("物", "もの")]
+pub fn furigana_segments(surface: &str, reading: &str) -> Vec<(String, Option<String>)> {
+    let needs_ruby = surface.chars().any(kana::is_kanji) && !reading.is_empty();
+    if !needs_ruby {
+        return vec![(surface.to_string(), None)];
+    }
+
+    // 连续的汉字 / 假名分成一段
+    let is_kana = |c: char| kana::is_hiragana(c) || kana::is_katakana(c);
+    let mut runs: Vec<(String, bool)> = Vec::new();
+    for c in surface.chars() {
+        match runs.last_mut() {
+            Some((text, kana_run)) if *kana_run == is_kana(c) => text.push(c),
+            _ => runs.push((c.to_string(), is_kana(c))),
+        }
+    }
+    let reading: Vec<char> = kana::to_hiragana(reading).chars().collect();
+    match align_runs(&runs, &reading) {
+        Some(rubies) => runs
+            .into_iter()
+            .zip(rubies)
+            .map(|((text, _), ruby)| (text, ruby))
+            .collect(),
+        // 读音和写法对不上（熟字训等）：整个词注音
+        None => vec![(surface.to_string(), Some(reading.iter().collect()))],
+    }
+}
+
+// 假名段必须和读音完全一致，汉字段至少对应一个假名；返回每段的注音
+fn align_runs(runs: &[(String, bool)], reading: &[char]) -> Option<Vec<Option<String>>> {
+    let Some(((text, kana_run), rest)) = runs.split_first() else {
+        return reading.is_empty().then(Vec::new);
+    };
+    if *kana_run {
+        let kana: Vec<char> = kana::to_hiragana(text).chars().collect();
+        let tail = reading.strip_prefix(kana.as_slice())?;
+        let mut rubies = align_runs(rest, tail)?;
+        rubies.insert(0, None);
+        return Some(rubies);
+    }
+    // 汉字段：从短到长尝试，直到后面的假名对得上
+    let max = if rest.is_empty() { reading.len() } else { reading.len().saturating_sub(1) };
+    let min = if rest.is_empty() { reading.len().max(1) } else { 1 };
+    (min..=max).find_map(|len| {
+        let mut rubies = align_runs(rest, &reading[len..])?;
+        rubies.insert(0, Some(reading[..len].iter().collect()));
+        Some(rubies)
+    })
+}
+
+fn bracket_notation(tokens: &[Token]) -> String {
+    let mut result = String::new();
+    for token in tokens {
+        for (text, ruby) in furigana_segments(&token.surface, &token.reading) {
+            result.push_str(&text);
+            if let Some(ruby) = ruby {
+                result.push_str(&format!("[{}]", ruby));
+            }
+        }
+    }
+    result
+}
+
+fn romaji_line(tokens: &[Token], tokenizer: &Tokenizer) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for token in tokens {
+        let romaji = match token.surface.as_str() {
+            "。" => ".".to_string(),
+            "、" => ",".to_string(),
+            "？" => "?".to_string(),
+            "！" => "!".to_string(),
+            _ => token.romaji(tokenizer.romanizer()),
+        };
+        match token.pos {
+            // 标点紧跟前一个词
+            Pos::Punctuation => match words.last_mut() {
+                Some(last) => last.push_str(&romaji),
+                None => words.push(romaji),
+            },
+            _ if romaji.trim().is_empty() => {}
+            _ => words.push(romaji),
+        }
+    }
+    words.join(" ")
+}
+
+pub fn read(tokenizer: &Tokenizer, text: &str) -> Reading {
+    let tokens = tokenizer.tokenize(text.trim());
+    Reading {
+        text: text.trim().to_string(),
+        furigana: bracket_notation(&tokens),
+        romaji: romaji_line(&tokens, tokenizer),
+        tokens,
+    }
+}
+
+// 分词整篇文本，收集不在复习卡片中的生词（按首次出现的顺序）
+pub fn read_document(tokenizer: &Tokenizer, title: &str, text: &str, deck: &ReviewDeck) -> Document {
+    let lines: Vec<Reading> = text.lines().map(|line| read(tokenizer, line)).collect();
+    let mut vocabulary: Vec<VocabWord> = Vec::new();
+    let mut unknown: Vec<String> = Vec::new();
+
+    for token in lines.iter().flat_map(|line| &line.tokens) {
+        if !token.is_known() {
+            if !unknown.contains(&token.surface) {
+                unknown.push(token.surface.clone());
+            }
+            continue;
+        }
+        if !token.pos.is_content_word() || deck.contains(token.dictionary_form()) {
+            continue;
+        }
+        if let Some(word) = vocabulary.iter_mut().find(|w| w.word == token.dictionary_form()) {
+            word.count += 1;
+            continue;
+        }
+        let entry = tokenizer.dictionary_entry(token);
+        vocabulary.push(VocabWord {
+            word: token.dictionary_form().to_string(),
+            reading: entry.map(|e| e.reading.clone()).unwrap_or_else(|| token.reading.clone()),
+            pos: token.pos,
+            meaning: token.meaning.clone().unwrap_or_default(),
+            count: 1,
+        });
+    }
+
+    Document {
+        title: title.to_string(),
+        lines,
+        vocabulary,
+        unknown,
+    }
+}
+
+// 把生词表添加到复习卡片，返回新增的数量
+pub fn add_to_deck(document: &Document, deck: &mut ReviewDeck) -> usize {
+    let mut added = 0;
+    for word in &document.vocabulary {
+        let card = ReviewCard {
+            word: word.word.clone(),
+            reading: word.reading.clone(),
+            pos: word.pos,
+            meaning: word.meaning.clone(),
+            source: document.title.clone(),
+            added_at: storage::now_secs(),
+        };
+        if deck.add(card) {
+            added += 1;
+        }
+    }
+    added
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// <ruby>今日<rt>きょう</rt></ruby>は…
+fn ruby_html(tokens: &[Token]) -> String {
+    let mut html = String::new();
+    for token in tokens {
+        for (text, ruby) in furigana_segments(&token.surface, &token.reading) {
+            match ruby {
+                Some(ruby) => html.push_str(&format!("<ruby>{}<rt>{}</rt></ruby>", escape_html(&text), escape_html(&ruby))),
+                None => html.push_str(&escape_html(&text)),
+            }
+        }
+    }
+    html
+}
+
+// 连续的非空行合为一段
+fn paragraphs(lines: &[Reading]) -> Vec<Vec<&Reading>> {
+    let mut paragraphs = Vec::new();
+    let mut current = Vec::new();
+    for line in lines {
+        if line.tokens.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+pub fn render_html(document: &Document) -> String {
+    let title = escape_html(&document.title);
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str("<style>\nbody { max-width: 48em; margin: 2em auto; line-height: 2.2; font-size: 1.2em; }\n");
+    html.push_str("rt { font-size: 0.55em; color: #666; }\ntable { border-collapse: collapse; line-height: 1.6; }\n");
+    html.push_str("th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; }\n</style>\n</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", title));
+    for paragraph in paragraphs(&document.lines) {
+        let lines: Vec<String> = paragraph.iter().map(|line| ruby_html(&line.tokens)).collect();
+        html.push_str(&format!("<p>{}</p>\n", lines.join("<br>\n")));
+    }
+
+    if !document.vocabulary.is_empty() {
+        html.push_str("<h2>生词表</h2>\n<table>\n<tr><th>词</th><th>读音</th><th>词性</th><th>含义</th><th>次数</th></tr>\n");
+        for word in &document.vocabulary {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&word.word),
+                escape_html(&word.reading),
+                word.pos.label(),
+                escape_html(&word.meaning),
+                word.count
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// Markdown 没有注音语法，直接使用 <ruby> 标签（GitHub 等渲染器支持）
+pub fn render_markdown(document: &Document) -> String {
+    let mut markdown = format!("# {}\n\n", document.title);
+    for paragraph in paragraphs(&document.lines) {
+        let lines: Vec<String> = paragraph.iter().map(|line| ruby_html(&line.tokens)).collect();
+        markdown.push_str(&lines.join("<br>\n"));
+        markdown.push_str("\n\n");
+    }
+
+    if !document.vocabulary.is_empty() {
+        markdown.push_str("## 生词表\n\n| 词 | 读音 | 词性 | 含义 | 次数 |\n|---|---|---|---|---|\n");
+        for word in &document.vocabulary {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                word.word,
+                word.reading,
+                word.pos.label(),
+                word.meaning.replace('|', "\\|"),
+                word.count
+            ));
+        }
+    }
+    markdown
+}
+
+// 注音行和原文行按显示宽度对齐，超过 LINE_WIDTH 时换行
+fn ruby_lines(tokens: &[Token]) -> Vec<(String, String)> {
+    let mut lines = Vec::new();
+    let (mut top, mut bottom) = (String::new(), String::new());
+    for token in tokens {
+        let segments = furigana_segments(&token.surface, &token.reading);
+        let width: usize = segments
+            .iter()
+            .map(|(text, ruby)| {
+                let ruby_width = ruby.as_deref().map(UnicodeWidthStr::width).unwrap_or(0);
+                UnicodeWidthStr::width(text.as_str()).max(ruby_width)
+            })
+            .sum();
+        // 标点不放到行首
+        let overflow = UnicodeWidthStr::width(bottom.as_str()) + width > LINE_WIDTH;
+        if !bottom.is_empty() && overflow && token.pos != Pos::Punctuation {
+            lines.push((std::mem::take(&mut top), std::mem::take(&mut bottom)));
+        }
+        for (text, ruby) in segments {
+            let ruby = ruby.unwrap_or_default();
+            let w = UnicodeWidthStr::width(text.as_str()).max(UnicodeWidthStr::width(ruby.as_str()));
+            top.push_str(&ruby);
+            top.push_str(&" ".repeat(w - UnicodeWidthStr::width(ruby.as_str())));
+            bottom.push_str(&text);
+            bottom.push_str(&" ".repeat(w - UnicodeWidthStr::width(text.as_str())));
+        }
+    }
+    if !bottom.is_empty() {
+        lines.push((top, bottom));
+    }
+    lines
+}
+
+fn print_ruby(tokens: &[Token]) {
+    for (top, bottom) in ruby_lines(tokens) {
+        if !top.trim().is_empty() {
+            println!("  {}", top.trim_end());
+        }
+        println!("  {}", bottom.trim_end());
+    }
+}
+
+pub fn print_reading(reading: &Reading, tokenizer: &Tokenizer) {
+    print_ruby(&reading.tokens);
+    println!();
+    println!("📝 {}", reading.furigana);
+    println!("🔤 {}", reading.romaji);
+    println!();
+
+    let rows: Vec<Vec<String>> = reading
+        .tokens
+        .iter()
+        .filter(|t| t.pos != Pos::Punctuation)
+        .map(|t| {
+            let base = match (&t.base_form, &t.conjugation) {
+                (Some(base), Some(form)) => format!("{} · {}", base, form),
+                (Some(base), None) => base.clone(),
+                _ => String::new(),
+            };
+            vec![
+                t.surface.clone(),
+                t.reading.clone(),
+                if t.reading.is_empty() { String::new() } else { t.romaji(tokenizer.romanizer()) },
+                t.pos.label().to_string(),
+                base,
+                t.meaning.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    for line in render_table(&["词", "读音", "罗马音", "词性", "原形·活用", "含义"], &rows) {
+        println!("{}", line);
+    }
+
+    let unknown: Vec<&str> = reading.tokens.iter().filter(|t| !t.is_known()).map(|t| t.surface.as_str()).collect();
+    if !unknown.is_empty() {
+        println!();
+        println!("💡 未收录的词（没有读音）：{}", unknown.join("、"));
+    }
+}
+
+pub fn print_document(document: &Document) {
+    println!("📄 {}", document.title);
+    println!();
+    for line in &document.lines {
+        if line.tokens.is_empty() {
+            println!();
+        } else {
+            print_ruby(&line.tokens);
+        }
+    }
+    println!();
+
+    if document.vocabulary.is_empty() {
+        println!("📒 没有新的生词（都已在复习卡片中）");
+    } else {
+        println!("📒 生词表（{} 个）", document.vocabulary.len());
+        let rows: Vec<Vec<String>> = document
+            .vocabulary
+            .iter()
+            .map(|w| vec![w.word.clone(), w.reading.clone(), w.pos.label().to_string(), w.meaning.clone(), w.count.to_string()])
+            .collect();
+        for line in render_table(&["词", "读音", "词性", "含义", "次数"], &rows) {
+            println!("{}", line);
+        }
+    }
+
+    if !document.unknown.is_empty() {
+        println!();
+        println!("💡 未收录的词（没有读音）：{}", document.unknown.join("、"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, ruby: Option<&str>) -> (String, Option<String>) {
+        (text.to_string(), ruby.map(str::to_string))
+    }
+
+    #[test]
+    fn kana_inside_a_word_is_not_ruby() {
+        assert_eq!(
+            furigana_segments("食べ物", "たべもの"),
+            [segment("食", Some("た")), segment("べ", None), segment("物", Some("もの"))]
+        );
+    }
+
+    #[test]
+    fn each_kanji_run_gets_its_own_ruby() {
+        assert_eq!(
+            furigana_segments("本を読む", "ほんをよむ"),
+            [segment("本", Some("ほん")), segment("を", None), segment("読", Some("よ")), segment("む", None)]
+        );
+    }
+
+    #[test]
+    fn leading_kana_is_not_ruby() {
+        assert_eq!(furigana_segments("お茶", "おちゃ"), [segment("お", None), segment("茶", Some("ちゃ"))]);
+    }
+
+    #[test]
+    fn words_without_okurigana() {
+        assert_eq!(furigana_segments("今日", "きょう"), [segment("今日", Some("きょう"))]);
+        assert_eq!(furigana_segments("ねこ", "ねこ"), [segment("ねこ", None)]);
+    }
+}
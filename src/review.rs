@@ -0,0 +1,59 @@
+// 复习卡片（review.json）：从阅读材料中收集的生词，已收录的词不再出现在生词表中
+use crate::storage;
+use crate::tokenizer::Pos;
+use serde::{Deserialize, Serialize};
+
+const REVIEW_FILE: &str = "review.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReviewCard {
+    // 词典形
+    pub word: String,
+    pub reading: String,
+    pub pos: Pos,
+    #[serde(default)]
+    pub meaning: String,
+    // 来源文件或句子
+    #[serde(default)]
+    pub source: String,
+    pub added_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ReviewDeck {
+    cards: Vec<ReviewCard>,
+}
+
+impl ReviewDeck {
+    pub fn load() -> Self {
+        storage::load_json(REVIEW_FILE).unwrap_or_else(|e| {
+            eprintln!("⚠️  Ignoring review deck: {}", e);
+            ReviewDeck::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        storage::save_json(REVIEW_FILE, self)
+    }
+
+    pub fn cards(&self) -> &[ReviewCard] {
+        &self.cards
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.cards.iter().any(|c| c.word == word)
+    }
+
+    // 添加卡片，返回是否是新卡片
+    pub fn add(&mut self, card: ReviewCard) -> bool {
+        if self.contains(&card.word) {
+            return false;
+        }
+        self.cards.push(card);
+        true
+    }
+}
+
+pub fn review_path() -> std::path::PathBuf {
+    storage::data_file(REVIEW_FILE)
+}
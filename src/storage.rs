@@ -0,0 +1,57 @@
+// 本地数据文件（翻译缓存、用户词典等）的存放位置和读写
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 数据目录：优先使用 JP_DATA_DIR，否则为用户主目录下的 .jp
+pub fn data_dir() -> PathBuf {
+    if let Ok(dir) = env::var("JP_DATA_DIR") {
+        if !dir.trim().is_empty() {
+            return PathBuf::from(dir);
+        }
+    }
+
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".jp"))
+        .unwrap_or_else(|_| PathBuf::from(".jp"))
+}
+
+pub fn data_file(name: &str) -> PathBuf {
+    data_dir().join(name)
+}
+
+// 读取 JSON 数据文件，文件不存在时返回默认值
+pub fn load_json<T: DeserializeOwned + Default>(name: &str) -> Result<T, Box<dyn std::error::Error>> {
+    let path = data_file(name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e).into())
+}
+
+// 写入 JSON 数据文件：先写临时文件再重命名，避免中途失败留下损坏的文件
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let path = data_file(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string_pretty(value)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
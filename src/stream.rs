@@ -0,0 +1,288 @@
+// 流式输出：解析 chat completions 的 SSE 响应，并把 JSON 回复逐字显示在方框中
+use crate::usage::TokenUsage;
+use crate::LlmError;
+use serde::Deserialize;
+use std::io::Write;
+
+#[derive(Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    // 请求了 include_usage 时，最后一个分块只有 usage
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+// 读取 SSE 响应，每收到一段内容就调用 on_token，返回完整的回复文本和 usage
+pub async fn read_sse(
+    mut response: reqwest::Response,
+    on_token: &mut (dyn FnMut(&str) + Send),
+) -> Result<(String, Option<TokenUsage>), LlmError> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut content = String::new();
+    let mut usage = None;
+
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+
+        // 只处理完整的行，避免把多字节字符截断
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok((content, usage));
+            }
+
+            let chunk: StreamChunk = serde_json::from_str(data)
+                .map_err(|e| LlmError::BadResponse(format!("invalid stream chunk ({}): {}", e, data)))?;
+            for choice in chunk.choices {
+                if let Some(token) = choice.delta.content.filter(|t| !t.is_empty()) {
+                    on_token(&token);
+                    content.push_str(&token);
+                }
+            }
+            if chunk.usage.is_some() {
+                usage = chunk.usage;
+            }
+        }
+    }
+
+    Ok((content, usage))
+}
+
+// 把流式到达的 JSON 对象显示为 "║ 标签: 值" 的行；不是 JSON 时原样输出
+pub struct FieldPrinter {
+    labels: Vec<(&'static str, String)>,
+    mode: PrinterMode,
+    in_string: bool,
+    escape: bool,
+    unicode: Option<String>,
+    is_value: bool,
+    in_array: bool,
+    key: String,
+    key_printed: bool,
+    line_open: bool,
+    // 已显示的字段和值，结束后与校验过的结果比较
+    shown: Vec<(String, String)>,
+}
+
+#[derive(PartialEq)]
+enum PrinterMode {
+    Start,
+    Json,
+    Raw,
+}
+
+impl FieldPrinter {
+    pub fn new(labels: Vec<(&'static str, String)>) -> Self {
+        FieldPrinter {
+            labels,
+            mode: PrinterMode::Start,
+            in_string: false,
+            escape: false,
+            unicode: None,
+            is_value: false,
+            in_array: false,
+            key: String::new(),
+            key_printed: false,
+            line_open: false,
+            shown: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, token: &str) {
+        for c in token.chars() {
+            self.push_char(c);
+        }
+        let _ = std::io::stdout().flush();
+    }
+
+    // 是否已经收到并显示了内容（服务端不支持流式时为 false）
+    pub fn has_output(&self) -> bool {
+        self.mode != PrinterMode::Start
+    }
+
+    // 显示的内容是否和校验、规范化之后的字段完全一致；原样输出的文本总是不一致
+    pub fn matches(&self, fields: &[(&str, String)]) -> bool {
+        self.mode == PrinterMode::Json
+            && fields.iter().all(|(key, value)| {
+                let shown = self.shown.iter().find(|(k, _)| k == key).map_or("", |(_, v)| v.as_str());
+                shown.trim() == value.trim()
+            })
+            && self.shown.iter().all(|(key, _)| fields.iter().any(|(k, _)| k == key))
+    }
+
+    // 结束当前行
+    pub fn finish(&mut self) {
+        if self.line_open {
+            println!();
+            self.line_open = false;
+        }
+    }
+
+    fn start_line(&mut self) {
+        if self.line_open {
+            println!();
+        }
+        print!("║ ");
+        self.line_open = true;
+    }
+
+    fn label(&self) -> String {
+        self.labels
+            .iter()
+            .find(|(key, _)| *key == self.key)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_else(|| self.key.clone())
+    }
+
+    fn push_char(&mut self, c: char) {
+        match self.mode {
+            PrinterMode::Start => {
+                if c.is_whitespace() {
+                    return;
+                }
+                if c == '{' {
+                    self.mode = PrinterMode::Json;
+                } else {
+                    // 忽略 JSON 要求的模型，直接输出原文
+                    self.mode = PrinterMode::Raw;
+                    self.start_line();
+                    self.push_char(c);
+                }
+            }
+            PrinterMode::Raw => {
+                if c == '\n' {
+                    self.start_line();
+                } else {
+                    print!("{}", c);
+                }
+                self.shown_value(c);
+            }
+            PrinterMode::Json => self.push_json_char(c),
+        }
+    }
+
+    fn push_json_char(&mut self, c: char) {
+        if !self.in_string {
+            match c {
+                '"' => {
+                    self.in_string = true;
+                    if self.is_value {
+                        if self.key_printed {
+                            print!("、");
+                            self.shown_value('、');
+                        } else {
+                            let label = self.label();
+                            self.start_line();
+                            print!("{}: ", label);
+                            self.key_printed = true;
+                            self.shown.push((self.key.clone(), String::new()));
+                        }
+                    } else {
+                        self.key.clear();
+                        self.key_printed = false;
+                    }
+                }
+                ':' => self.is_value = true,
+                '[' => self.in_array = true,
+                ']' => {
+                    self.in_array = false;
+                    self.is_value = false;
+                }
+                ',' if !self.in_array => self.is_value = false,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(hex) = self.unicode.as_mut() {
+            hex.push(c);
+            if hex.len() == 4 {
+                let decoded = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+                self.unicode = None;
+                if let Some(decoded) = decoded {
+                    self.emit_string_char(decoded);
+                }
+            }
+            return;
+        }
+
+        if self.escape {
+            self.escape = false;
+            match c {
+                'u' => self.unicode = Some(String::new()),
+                'n' | 't' | 'r' => self.emit_string_char(' '),
+                other => self.emit_string_char(other),
+            }
+            return;
+        }
+
+        match c {
+            '\\' => self.escape = true,
+            '"' => {
+                self.in_string = false;
+                if self.is_value && !self.in_array {
+                    self.is_value = false;
+                }
+            }
+            other => self.emit_string_char(other),
+        }
+    }
+
+    fn shown_value(&mut self, c: char) {
+        match self.shown.last_mut() {
+            Some((_, value)) => value.push(c),
+            None => self.shown.push((String::new(), c.to_string())),
+        }
+    }
+
+    fn emit_string_char(&mut self, c: char) {
+        if self.is_value {
+            print!("{}", c);
+            self.shown_value(c);
+        } else {
+            self.key.push(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(japanese: &str, kana: &str, romaji: &str) -> Vec<(&'static str, String)> {
+        vec![("japanese", japanese.to_string()), ("kana", kana.to_string()), ("romaji", romaji.to_string()), ("alternatives", String::new())]
+    }
+
+    #[test]
+    fn printer_compares_shown_fields() {
+        let mut printer = FieldPrinter::new(Vec::new());
+        printer.push(r#"{"japanese": "東京", "kana": "とうきょう", "romaji": "Tōkyō"}"#);
+        printer.finish();
+        assert!(printer.has_output());
+        assert!(printer.matches(&fields("東京", "とうきょう", "Tōkyō")));
+        assert!(!printer.matches(&fields("東京", "とうきょう", "Toukyou")));
+
+        let mut raw = FieldPrinter::new(Vec::new());
+        raw.push("東京 (とうきょう/toukyou)");
+        raw.finish();
+        assert!(!raw.matches(&fields("東京", "とうきょう", "toukyou")));
+    }
+}
@@ -0,0 +1,898 @@
+// 离线分词：在词典中查找所有可能的词（包括动词、形容词的活用形），用最小代价切分（Viterbi）得到句子的分词结果。
+// 词典来源：内置核心词汇、中文字典、假名例词、用户词典和导入的 JMdict 常用词
+use crate::jmdict;
+use crate::kana::{self, Romanizer};
+use crate::user_dict::UserDictionary;
+use crate::{init_chinese_map, init_romaji_map, split_japanese_entry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// 词性，代码与 JMdict 的词性标记一致（v5k、v1、adj-i 等）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pos {
+    Noun,
+    Pronoun,
+    GodanVerb,
+    IchidanVerb,
+    SuruVerb,
+    KuruVerb,
+    IAdjective,
+    NaAdjective,
+    Adverb,
+    Particle,
+    Auxiliary,
+    Conjunction,
+    Interjection,
+    Number,
+    Punctuation,
+    Unknown,
+}
+
+impl Pos {
+    pub fn from_code(code: &str) -> Option<Pos> {
+        let pos = match code {
+            "n" | "n-adv" | "n-t" | "n-suf" | "n-pref" | "ctr" | "suf" | "pref" => Pos::Noun,
+            "pn" => Pos::Pronoun,
+            "v1" | "v1-s" => Pos::IchidanVerb,
+            "vs-i" | "vs-s" => Pos::SuruVerb,
+            "vk" => Pos::KuruVerb,
+            "adj-i" | "adj-ix" => Pos::IAdjective,
+            "adj-na" | "adj-no" => Pos::NaAdjective,
+            "adv" | "adv-to" => Pos::Adverb,
+            "prt" => Pos::Particle,
+            "aux" | "aux-v" | "cop" => Pos::Auxiliary,
+            "conj" => Pos::Conjunction,
+            "int" | "exp" => Pos::Interjection,
+            "num" => Pos::Number,
+            c if c.starts_with("v5") => Pos::GodanVerb,
+            _ => return None,
+        };
+        Some(pos)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Pos::Noun => "名词",
+            Pos::Pronoun => "代词",
+            Pos::GodanVerb => "动词（五段）",
+            Pos::IchidanVerb => "动词（一段）",
+            Pos::SuruVerb => "动词（サ变）",
+            Pos::KuruVerb => "动词（カ变）",
+            Pos::IAdjective => "形容词",
+            Pos::NaAdjective => "形容动词",
+            Pos::Adverb => "副词",
+            Pos::Particle => "助词",
+            Pos::Auxiliary => "助动词",
+            Pos::Conjunction => "连词",
+            Pos::Interjection => "感叹词",
+            Pos::Number => "数字",
+            Pos::Punctuation => "标点",
+            Pos::Unknown => "未知",
+        }
+    }
+
+    // 是否是实词（用于生词表）
+    pub fn is_content_word(self) -> bool {
+        !matches!(
+            self,
+            Pos::Particle | Pos::Auxiliary | Pos::Punctuation | Pos::Number | Pos::Unknown
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct LexEntry {
+    pub surface: String,
+    // 平假名读音
+    pub reading: String,
+    pub pos: Pos,
+    pub meaning: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Token {
+    pub surface: String,
+    // 平假名读音，未知词为空
+    pub reading: String,
+    pub pos: Pos,
+    // 活用形的原形，如 食べました → 食べる
+    pub base_form: Option<String>,
+    pub conjugation: Option<String>,
+    pub meaning: Option<String>,
+}
+
+impl Token {
+    pub fn is_known(&self) -> bool {
+        !self.reading.is_empty() || matches!(self.pos, Pos::Punctuation | Pos::Number)
+    }
+
+    // 词典形：活用词返回原形，其他返回原文
+    pub fn dictionary_form(&self) -> &str {
+        self.base_form.as_deref().unwrap_or(&self.surface)
+    }
+
+    // 罗马音；助词 は、へ、を 读作 wa、e、o
+    pub fn romaji(&self, romanizer: &Romanizer) -> String {
+        if self.pos == Pos::Particle {
+            match self.reading.as_str() {
+                "は" => return "wa".to_string(),
+                "へ" => return "e".to_string(),
+                "を" => return "o".to_string(),
+                _ => {}
+            }
+        }
+        if self.reading.is_empty() {
+            self.surface.clone()
+        } else {
+            romanizer.romanize(&self.reading)
+        }
+    }
+}
+
+// 内置核心词汇：写法、读音、词性代码、中文释义
+const CORE_VOCABULARY: &str = "\
+私	わたし	pn	我
+僕	ぼく	pn	我（男性）
+あなた	あなた	pn	你
+彼	かれ	pn	他
+彼女	かのじょ	pn	她；女朋友
+誰	だれ	pn	谁
+何	なに	pn	什么
+これ	これ	pn	这个
+それ	それ	pn	那个
+あれ	あれ	pn	那个（远处）
+どれ	どれ	pn	哪个
+ここ	ここ	pn	这里
+そこ	そこ	pn	那里
+あそこ	あそこ	pn	那里（远处）
+どこ	どこ	pn	哪里
+この	この	adj-pn	这个
+その	その	adj-pn	那个
+あの	あの	adj-pn	那个（远处）
+どの	どの	adj-pn	哪个
+は	は	prt	提示主题
+が	が	prt	表示主语
+を	を	prt	表示宾语
+に	に	prt	表示时间、地点、对象
+へ	へ	prt	表示方向
+で	で	prt	表示地点、手段
+と	と	prt	和；表示引用
+も	も	prt	也
+の	の	prt	的
+や	や	prt	和（列举）
+か	か	prt	表示疑问
+ね	ね	prt	表示确认、感叹
+よ	よ	prt	表示提醒、强调
+から	から	prt	从；因为
+まで	まで	prt	到
+より	より	prt	比
+だけ	だけ	prt	只
+しか	しか	prt	只（接否定）
+など	など	prt	等等
+けど	けど	prt	但是
+けれど	けれど	prt	但是
+ので	ので	prt	因为
+のに	のに	prt	却；明明
+です	です	cop	是（礼貌）
+でした	でした	cop	是（过去，礼貌）
+ではありません	ではありません	cop	不是（礼貌）
+じゃありません	じゃありません	cop	不是（礼貌）
+ではない	ではない	cop	不是
+じゃない	じゃない	cop	不是
+だ	だ	cop	是
+だった	だった	cop	是（过去）
+でしょう	でしょう	cop	吧（推测）
+ください	ください	aux	请
+今日	きょう	n	今天
+明日	あした	n	明天
+昨日	きのう	n	昨天
+今	いま	n	现在
+毎日	まいにち	n	每天
+朝	あさ	n	早上
+夜	よる	n	晚上
+時間	じかん	n	时间
+天気	てんき	n	天气
+雨	あめ	n	雨
+学生	がくせい	n	学生
+先生	せんせい	n	老师
+学校	がっこう	n	学校
+大学	だいがく	n	大学
+会社	かいしゃ	n	公司
+仕事	しごと	n	工作
+友達	ともだち	n	朋友
+人	ひと	n	人
+家族	かぞく	n	家人
+名前	なまえ	n	名字
+日本	にほん	n	日本
+日本語	にほんご	n	日语
+中国	ちゅうごく	n	中国
+中国語	ちゅうごくご	n	汉语
+英語	えいご	n	英语
+言葉	ことば	n	语言；词语
+東京	とうきょう	n	东京
+駅	えき	n	车站
+電車	でんしゃ	n	电车
+車	くるま	n	汽车
+道	みち	n	路
+家	いえ	n	家；房子
+部屋	へや	n	房间
+店	みせ	n	商店
+本	ほん	n	书
+映画	えいが	n	电影
+音楽	おんがく	n	音乐
+料理	りょうり	n	料理；做菜
+水	みず	n	水
+お茶	おちゃ	n	茶
+ご飯	ごはん	n	饭
+猫	ねこ	n	猫
+犬	いぬ	n	狗
+花	はな	n	花
+勉強	べんきょう	n	学习
+旅行	りょこう	n	旅行
+問題	もんだい	n	问题
+する	する	vs-i	做
+来る	くる	vk	来
+行く	いく	v5k-s	去
+見る	みる	v1	看
+食べる	たべる	v1	吃
+寝る	ねる	v1	睡觉
+起きる	おきる	v1	起床
+出る	でる	v1	出去
+いる	いる	v1	在（有生命）
+教える	おしえる	v1	教
+飲む	のむ	v5m	喝
+読む	よむ	v5m	读
+休む	やすむ	v5m	休息
+住む	すむ	v5m	住
+書く	かく	v5k	写
+聞く	きく	v5k	听；问
+働く	はたらく	v5k	工作
+歩く	あるく	v5k	走路
+泳ぐ	およぐ	v5g	游泳
+話す	はなす	v5s	说话
+待つ	まつ	v5t	等
+持つ	もつ	v5t	拿；持有
+死ぬ	しぬ	v5n	死
+遊ぶ	あそぶ	v5b	玩
+買う	かう	v5u	买
+会う	あう	v5u	见面
+言う	いう	v5u	说
+思う	おもう	v5u	想；认为
+使う	つかう	v5u	使用
+ある	ある	v5r-i	有；在（无生命）
+分かる	わかる	v5r	明白
+なる	なる	v5r	变成
+帰る	かえる	v5r	回去
+入る	はいる	v5r	进入
+作る	つくる	v5r	做；制作
+知る	しる	v5r	知道
+良い	よい	adj-i	好
+いい	いい	adj-ix	好
+大きい	おおきい	adj-i	大
+小さい	ちいさい	adj-i	小
+高い	たかい	adj-i	高；贵
+安い	やすい	adj-i	便宜
+新しい	あたらしい	adj-i	新
+古い	ふるい	adj-i	旧
+暑い	あつい	adj-i	热
+寒い	さむい	adj-i	冷
+楽しい	たのしい	adj-i	快乐
+美しい	うつくしい	adj-i	美丽
+面白い	おもしろい	adj-i	有趣
+難しい	むずかしい	adj-i	难
+優しい	やさしい	adj-i	温柔
+悪い	わるい	adj-i	坏
+多い	おおい	adj-i	多
+少ない	すくない	adj-i	少
+早い	はやい	adj-i	早
+長い	ながい	adj-i	长
+好き	すき	adj-na	喜欢
+嫌い	きらい	adj-na	讨厌
+元気	げんき	adj-na	精神；健康
+静か	しずか	adj-na	安静
+有名	ゆうめい	adj-na	有名
+綺麗	きれい	adj-na	漂亮；干净
+大丈夫	だいじょうぶ	adj-na	没关系
+上手	じょうず	adj-na	擅长
+とても	とても	adv	非常
+よく	よく	adv	经常；很好地
+もう	もう	adv	已经
+まだ	まだ	adv	还
+少し	すこし	adv	一点
+ちょっと	ちょっと	adv	稍微
+たくさん	たくさん	adv	很多
+いつも	いつも	adv	总是
+全然	ぜんぜん	adv	完全（不）
+そして	そして	conj	然后
+でも	でも	conj	但是
+しかし	しかし	conj	但是
+だから	だから	conj	所以
+はい	はい	int	是
+いいえ	いいえ	int	不
+";
+
+// 活用规则：去掉 inflected 加上 base 得到原形
+struct Rule {
+    inflected: String,
+    base: &'static str,
+    pos: Pos,
+    form: String,
+    // 活用形的完整读音（来る 这样词干读音也会变化的词）
+    reading: Option<String>,
+}
+
+// 活用后变成一段动词的形（被动、使役、可能），后面还能再接一段动词的词尾：書かれた、食べられなかった
+const DERIVED_ICHIDAN_FORMS: &[&str] = &["被动形", "使役形", "可能形", "被动/可能形"];
+
+// 五段动词各行：(词尾, い段, あ段, え段, お段, て形, た形)
+pub const GODAN_ROWS: &[(&str, &str, &str, &str, &str, &str, &str)] = &[
+    ("う", "い", "わ", "え", "お", "って", "った"),
+    ("く", "き", "か", "け", "こ", "いて", "いた"),
+    ("ぐ", "ぎ", "が", "げ", "ご", "いで", "いだ"),
+    ("す", "し", "さ", "せ", "そ", "して", "した"),
+    ("つ", "ち", "た", "て", "と", "って", "った"),
+    ("ぬ", "に", "な", "ね", "の", "んで", "んだ"),
+    ("ぶ", "び", "ば", "べ", "ぼ", "んで", "んだ"),
+    ("む", "み", "ま", "め", "も", "んで", "んだ"),
+    ("る", "り", "ら", "れ", "ろ", "って", "った"),
+];
+
+// ます形一类的词尾，接在连用形后面
+const MASU_FORMS: &[(&str, &str)] = &[
+    ("ます", "ます形"),
+    ("ました", "ました（过去）"),
+    ("ません", "ません（否定）"),
+    ("ませんでした", "ませんでした（过去否定）"),
+    ("ましょう", "ましょう（劝诱）"),
+    ("たい", "たい（愿望）"),
+    ("たくない", "たくない（愿望否定）"),
+    ("たかった", "たかった（愿望过去）"),
+    ("ながら", "ながら（同时）"),
+];
+
+// 一段动词和する共用的词尾（接在词干 / し 后面）
+const ICHIDAN_FORMS: &[(&str, &str)] = &[
+    ("ない", "ない形（否定）"),
+    ("なかった", "なかった（过去否定）"),
+    ("て", "て形"),
+    ("た", "た形（过去）"),
+    ("たら", "たら（条件）"),
+    ("よう", "意志形"),
+];
+
+// 来る的活用形（假名）
+const KURU_FORMS: &[(&str, &str)] = &[
+    ("きます", "ます形"),
+    ("きました", "ました（过去）"),
+    ("きません", "ません（否定）"),
+    ("きませんでした", "ませんでした（过去否定）"),
+    ("きましょう", "ましょう（劝诱）"),
+    ("きたい", "たい（愿望）"),
+    ("こない", "ない形（否定）"),
+    ("こなかった", "なかった（过去否定）"),
+    ("きて", "て形"),
+    ("きた", "た形（过去）"),
+    ("きたら", "たら（条件）"),
+    ("こよう", "意志形"),
+    ("くれば", "ば形（条件）"),
+    ("こられる", "被动/可能形"),
+];
+
+fn rules() -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let mut push = |inflected: String, base: &'static str, pos: Pos, form: &'static str| {
+        rules.push(Rule { inflected, base, pos, form: form.to_string(), reading: None });
+    };
+
+    for &(ending, i, a, e, o, te, ta) in GODAN_ROWS {
+        for &(suffix, form) in MASU_FORMS {
+            push(format!("{}{}", i, suffix), ending, Pos::GodanVerb, form);
+        }
+        push(format!("{}ない", a), ending, Pos::GodanVerb, "ない形（否定）");
+        push(format!("{}なかった", a), ending, Pos::GodanVerb, "なかった（过去否定）");
+        push(format!("{}れる", a), ending, Pos::GodanVerb, "被动形");
+        push(format!("{}せる", a), ending, Pos::GodanVerb, "使役形");
+        push(format!("{}ば", e), ending, Pos::GodanVerb, "ば形（条件）");
+        push(format!("{}る", e), ending, Pos::GodanVerb, "可能形");
+        push(format!("{}う", o), ending, Pos::GodanVerb, "意志形");
+        push(te.to_string(), ending, Pos::GodanVerb, "て形");
+        push(ta.to_string(), ending, Pos::GodanVerb, "た形（过去）");
+        push(format!("{}ら", ta), ending, Pos::GodanVerb, "たら（条件）");
+    }
+    // 行く的て形、た形是例外：行って、行った
+    for (inflected, form) in [("って", "て形"), ("った", "た形（过去）"), ("ったら", "たら（条件）")] {
+        push(inflected.to_string(), "く", Pos::GodanVerb, form);
+    }
+
+    for &(suffix, form) in MASU_FORMS.iter().chain(ICHIDAN_FORMS) {
+        push(suffix.to_string(), "る", Pos::IchidanVerb, form);
+    }
+    for (inflected, form) in [
+        ("れば", "ば形（条件）"),
+        ("られる", "被动/可能形"),
+        ("させる", "使役形"),
+        ("ろ", "命令形"),
+    ] {
+        push(inflected.to_string(), "る", Pos::IchidanVerb, form);
+    }
+
+    // する：します、して、しない……
+    for &(suffix, form) in MASU_FORMS.iter().chain(ICHIDAN_FORMS) {
+        push(format!("し{}", suffix), "する", Pos::SuruVerb, form);
+    }
+    for (inflected, form) in [
+        ("すれば", "ば形（条件）"),
+        ("される", "被动形"),
+        ("させる", "使役形"),
+        ("できる", "可能形"),
+    ] {
+        push(inflected.to_string(), "する", Pos::SuruVerb, form);
+    }
+
+    // い形容词
+    for (inflected, form) in [
+        ("く", "く（副词形）"),
+        ("かった", "かった（过去）"),
+        ("くない", "くない（否定）"),
+        ("くなかった", "くなかった（过去否定）"),
+        ("くて", "くて（て形）"),
+        ("ければ", "ければ（条件）"),
+        ("そう", "そう（样态）"),
+    ] {
+        push(inflected.to_string(), "い", Pos::IAdjective, form);
+    }
+
+    // 来る：写作假名时 く 变为 き / こ，写作汉字时读音同样变化
+    for &(kana_form, form) in KURU_FORMS {
+        let tail: String = kana_form.chars().skip(1).collect();
+        let reading = Some(kana_form.to_string());
+        rules.push(Rule { inflected: kana_form.to_string(), base: "くる", pos: Pos::KuruVerb, form: form.to_string(), reading: reading.clone() });
+        rules.push(Rule { inflected: format!("来{}", tail), base: "来る", pos: Pos::KuruVerb, form: form.to_string(), reading });
+    }
+
+    // 被动、使役、可能形再按一段动词活用：書かれる → 書かれた，食べられる → 食べられなかった
+    let mut derived = Vec::new();
+    for rule in rules.iter().filter(|r| DERIVED_ICHIDAN_FORMS.contains(&r.form.as_str())) {
+        let Some(stem) = rule.inflected.strip_suffix('る') else {
+            continue;
+        };
+        for &(suffix, form) in MASU_FORMS.iter().chain(ICHIDAN_FORMS) {
+            derived.push(Rule {
+                inflected: format!("{}{}", stem, suffix),
+                base: rule.base,
+                pos: rule.pos,
+                form: format!("{}・{}", rule.form, form),
+                reading: rule.reading.as_ref().and_then(|r| r.strip_suffix('る')).map(|r| format!("{}{}", r, suffix)),
+            });
+        }
+    }
+    rules.extend(derived);
+
+    rules
+}
+
+// 片假名、汉字之间夹着助词的是短语（本を読む），不作为一个词
+fn is_phrase(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    let is_word_char = |c: char| kana::is_kanji(c) || kana::is_katakana(c);
+    word.contains(char::is_whitespace)
+        || chars.windows(3).any(|w| is_word_char(w[0]) && "をがはにでへとのや".contains(w[1]) && is_word_char(w[2]))
+}
+
+#[derive(Default)]
+pub struct Lexicon {
+    words: HashMap<String, Vec<LexEntry>>,
+    // 只用读音（假名）索引的词，匹配代价稍高
+    readings: HashMap<String, Vec<LexEntry>>,
+    max_len: usize,
+}
+
+// 没有词性信息的词按词尾猜测
+fn guess_pos(word: &str, reading: &str) -> Pos {
+    let has_kanji = word.chars().any(kana::is_kanji);
+    if !has_kanji {
+        return Pos::Interjection;
+    }
+    let mut chars = word.chars().rev();
+    let last = chars.next();
+    let prev = chars.next();
+    match (last, prev.map(kana::is_kanji)) {
+        (Some('い'), Some(true)) if reading.ends_with('い') => Pos::IAdjective,
+        (Some('る'), Some(true)) => {
+            // る 前面是 い段 / え段 的一般是一段动词
+            let before = reading.chars().rev().nth(1).unwrap_or(' ');
+            if "いきしちにひみりぎじびぴえけせてねへめれげぜべぺ".contains(before) {
+                Pos::IchidanVerb
+            } else {
+                Pos::GodanVerb
+            }
+        }
+        (Some(c), Some(true)) if "うくぐすつぬぶむ".contains(c) => Pos::GodanVerb,
+        _ => Pos::Noun,
+    }
+}
+
+impl Lexicon {
+    pub fn add(&mut self, entry: LexEntry) {
+        let exists = |list: &Vec<LexEntry>| list.iter().any(|e| e.reading == entry.reading && e.pos == entry.pos);
+        if self.words.get(&entry.surface).map(exists).unwrap_or(false) {
+            return;
+        }
+        self.max_len = self.max_len.max(entry.surface.chars().count());
+        if entry.reading != entry.surface && entry.reading.chars().count() >= 2 {
+            self.max_len = self.max_len.max(entry.reading.chars().count());
+            self.readings.entry(entry.reading.clone()).or_default().push(entry.clone());
+        }
+        self.words.entry(entry.surface.clone()).or_default().push(entry);
+    }
+
+    // 内置词汇 + 中文字典 + 假名例词 + 用户词典 + JMdict 常用词
+    pub fn builtin() -> Self {
+        let mut lexicon = Lexicon::default();
+        for line in CORE_VOCABULARY.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if let [surface, reading, code, meaning] = fields[..] {
+                let pos = if code == "adj-pn" { Pos::Pronoun } else { Pos::from_code(code).unwrap_or(Pos::Noun) };
+                lexicon.add(LexEntry {
+                    surface: surface.to_string(),
+                    reading: reading.to_string(),
+                    pos,
+                    meaning: meaning.to_string(),
+                });
+            }
+        }
+
+        let add_entry = |lexicon: &mut Lexicon, entry: &str, meaning: &str| {
+            let (word, kana) = split_japanese_entry(entry);
+            if is_phrase(word) {
+                return;
+            }
+            let reading = kana.first().map(|k| kana::to_hiragana(k)).unwrap_or_else(|| kana::to_hiragana(word));
+            if !reading.chars().all(|c| kana::is_hiragana(c) || c == 'ー') {
+                return;
+            }
+            lexicon.add(LexEntry {
+                surface: word.to_string(),
+                reading: reading.clone(),
+                pos: guess_pos(word, &reading),
+                meaning: meaning.to_string(),
+            });
+        };
+
+        let mut chinese: Vec<(String, String)> = init_chinese_map().into_iter().collect();
+        chinese.sort();
+        for (meaning, entry) in &chinese {
+            add_entry(&mut lexicon, entry, meaning);
+        }
+        let mut romaji: Vec<_> = init_romaji_map().into_values().collect();
+        romaji.sort_by(|a, b| a.romaji.cmp(&b.romaji));
+        for jp_char in &romaji {
+            for example in &jp_char.examples {
+                if let Some((left, english)) = example.split_once(" - ") {
+                    add_entry(&mut lexicon, left, english);
+                }
+            }
+        }
+        for entry in UserDictionary::load().entries() {
+            let translation = &entry.translation;
+            let reading = kana::to_hiragana(&translation.kana.replace(' ', ""));
+            if translation.japanese.is_empty() || reading.is_empty() {
+                continue;
+            }
+            lexicon.add(LexEntry {
+                surface: translation.japanese.clone(),
+                reading: reading.clone(),
+                pos: guess_pos(&translation.japanese, &reading),
+                meaning: entry.chinese.clone(),
+            });
+        }
+
+        // JMdict 只加入常用词；已有的写法保留内置词汇的词性和中文释义
+        for entry in jmdict::common_entries() {
+            let Some(pos) = entry.main_pos() else {
+                continue;
+            };
+            let reading = kana::to_hiragana(entry.reading());
+            let meaning = entry.senses[0].glosses.iter().take(3).cloned().collect::<Vec<_>>().join("; ");
+            let surfaces = if entry.kanji.is_empty() { &entry.kana } else { &entry.kanji };
+            for surface in surfaces {
+                if !lexicon.lookup(surface).is_empty() {
+                    continue;
+                }
+                lexicon.add(LexEntry {
+                    surface: surface.clone(),
+                    reading: reading.clone(),
+                    pos,
+                    meaning: meaning.clone(),
+                });
+            }
+        }
+        lexicon
+    }
+
+    pub fn lookup(&self, surface: &str) -> &[LexEntry] {
+        self.words.get(surface).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn lookup_reading(&self, reading: &str) -> &[LexEntry] {
+        self.readings.get(reading).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+// 字符类别，用于切分未知词
+#[derive(PartialEq, Clone, Copy)]
+enum CharClass {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Alnum,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if kana::is_kanji(c) {
+        CharClass::Kanji
+    } else if kana::is_hiragana(c) {
+        CharClass::Hiragana
+    } else if kana::is_katakana(c) {
+        CharClass::Katakana
+    } else if c.is_alphanumeric() {
+        CharClass::Alnum
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+struct Candidate {
+    len: usize,
+    cost: i32,
+    token: Token,
+    // 只按读音匹配到的词（かう → 買う）
+    by_reading: bool,
+}
+
+pub struct Tokenizer {
+    lexicon: Lexicon,
+    rules: Vec<Rule>,
+    // 最长的活用词尾（字数）
+    max_inflection: usize,
+    romanizer: Romanizer,
+}
+
+impl Tokenizer {
+    pub fn new(lexicon: Lexicon) -> Self {
+        let rules = rules();
+        let max_inflection = rules.iter().map(|r| r.inflected.chars().count()).max().unwrap_or(0);
+        Tokenizer {
+            lexicon,
+            rules,
+            max_inflection,
+            romanizer: Romanizer::new(&init_romaji_map()),
+        }
+    }
+
+    pub fn load() -> Self {
+        Tokenizer::new(Lexicon::builtin())
+    }
+
+    pub fn romanizer(&self) -> &Romanizer {
+        &self.romanizer
+    }
+
+    // 词语对应的词典条目（活用词查原形）
+    pub fn dictionary_entry(&self, token: &Token) -> Option<&LexEntry> {
+        self.lexicon.lookup(token.dictionary_form()).iter().find(|e| e.pos == token.pos)
+    }
+
+    fn entry_token(entry: &LexEntry, surface: &str) -> Token {
+        Token {
+            surface: surface.to_string(),
+            reading: entry.reading.clone(),
+            pos: entry.pos,
+            base_form: None,
+            conjugation: None,
+            meaning: Some(entry.meaning.clone()).filter(|m| !m.is_empty()),
+        }
+    }
+
+    // 从 start 开始的所有候选词
+    fn candidates(&self, chars: &[char], start: usize) -> Vec<Candidate> {
+        let mut candidates = Vec::new();
+        let rest = chars.len() - start;
+        // 活用词尾加在原形长度上
+        let max_len = rest.min(self.lexicon.max_len + self.max_inflection);
+
+        for len in 1..=max_len {
+            let surface: String = chars[start..start + len].iter().collect();
+            // 越长的词代价越低，让 "日本語" 优先于 "日本" + "語"
+            let word_cost = 20 - 2 * len.min(6) as i32;
+
+            for entry in self.lexicon.lookup(&surface) {
+                candidates.push(Candidate { len, cost: word_cost, token: Self::entry_token(entry, &surface), by_reading: false });
+            }
+            for entry in self.lexicon.lookup_reading(&surface) {
+                candidates.push(Candidate { len, cost: word_cost + 6, token: Self::entry_token(entry, &surface), by_reading: true });
+            }
+
+            for rule in &self.rules {
+                let Some(stem) = surface.strip_suffix(rule.inflected.as_str()) else {
+                    continue;
+                };
+                let base = format!("{}{}", stem, rule.base);
+                if base == surface {
+                    continue;
+                }
+                let by_surface = self.lexicon.lookup(&base).iter().map(|e| (e, 1, false));
+                let by_reading = self.lexicon.lookup_reading(&base).iter().map(|e| (e, 7, true));
+                for (entry, extra, kana_only) in by_surface.chain(by_reading) {
+                    if entry.pos != rule.pos {
+                        continue;
+                    }
+                    // 读音：原形读音去掉词尾，加上活用词尾
+                    let reading = match &rule.reading {
+                        Some(reading) => reading.clone(),
+                        None if kana_only => kana::to_hiragana(&surface),
+                        None => {
+                            let reading_stem = entry.reading.strip_suffix(rule.base).unwrap_or(&entry.reading);
+                            format!("{}{}", reading_stem, rule.inflected)
+                        }
+                    };
+                    candidates.push(Candidate {
+                        len,
+                        cost: word_cost + extra,
+                        token: Token {
+                            surface: surface.clone(),
+                            reading,
+                            pos: entry.pos,
+                            base_form: Some(entry.surface.clone()),
+                            conjugation: Some(rule.form.clone()),
+                            meaning: Some(entry.meaning.clone()).filter(|m| !m.is_empty()),
+                        },
+                        by_reading: kana_only,
+                    });
+                }
+            }
+        }
+
+        // 未知词：同类字符连成一个词
+        let class = char_class(chars[start]);
+        let run = chars[start..].iter().take_while(|c| char_class(**c) == class).count();
+        let (len, cost, pos, reading) = match class {
+            CharClass::Punctuation => (1, 0, Pos::Punctuation, String::new()),
+            CharClass::Alnum => {
+                let surface: String = chars[start..start + run].iter().collect();
+                let pos = if surface.chars().all(|c| c.is_numeric()) { Pos::Number } else { Pos::Noun };
+                (run, 5, pos, String::new())
+            }
+            CharClass::Katakana => {
+                let surface: String = chars[start..start + run].iter().collect();
+                (run, 15, Pos::Noun, kana::to_hiragana(&surface))
+            }
+            CharClass::Kanji => (run, 30 + 10 * run as i32, Pos::Noun, String::new()),
+            CharClass::Hiragana => (1, 40, Pos::Unknown, chars[start].to_string()),
+        };
+        candidates.push(Candidate {
+            len,
+            cost,
+            token: Token {
+                surface: chars[start..start + len].iter().collect(),
+                reading,
+                pos,
+                base_form: None,
+                conjugation: None,
+                meaning: None,
+            },
+            by_reading: false,
+        });
+        candidates
+    }
+
+    // 相邻两个词的连接代价
+    fn connection_cost(prev: Option<Pos>, next: Pos) -> i32 {
+        match (prev, next) {
+            (None | Some(Pos::Punctuation), Pos::Particle | Pos::Auxiliary) => 20,
+            (None | Some(Pos::Punctuation), _) => 0,
+            (Some(_), Pos::Interjection) => 40,
+            (Some(Pos::Noun | Pos::Pronoun | Pos::Number), Pos::Particle) => -3,
+            (Some(Pos::Noun | Pos::Pronoun | Pos::NaAdjective | Pos::IAdjective), Pos::Auxiliary) => -3,
+            (Some(Pos::Particle), Pos::Particle) => 5,
+            _ => 0,
+        }
+    }
+
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        let chars: Vec<char> = text.chars().collect();
+        let n = chars.len();
+        // best[i]：切分到第 i 个字时最后一个词的起点和内容
+        let mut best: Vec<Option<(usize, Token)>> = vec![None; n + 1];
+        let mut costs: Vec<Option<i32>> = vec![None; n + 1];
+        costs[0] = Some(0);
+
+        for start in 0..n {
+            let Some(cost) = costs[start] else {
+                continue;
+            };
+            let prev_pos = best[start].as_ref().map(|(_, token)| token.pos);
+            // 只按读音匹配的词必须从词的边界开始，不把 食べられなかった 剩下的 かった 当成 買った
+            let at_boundary = start == 0
+                || !kana::is_hiragana(chars[start - 1])
+                || matches!(prev_pos, Some(Pos::Particle | Pos::Punctuation));
+            for candidate in self.candidates(&chars, start) {
+                if candidate.by_reading && !at_boundary {
+                    continue;
+                }
+                let end = start + candidate.len;
+                let total = cost + candidate.cost + Self::connection_cost(prev_pos, candidate.token.pos);
+                if costs[end].map(|c| total < c).unwrap_or(true) {
+                    costs[end] = Some(total);
+                    best[end] = Some((start, candidate.token));
+                }
+            }
+        }
+
+        let mut tokens = Vec::new();
+        let mut end = n;
+        while end > 0 {
+            let Some((start, token)) = best[end].take() else {
+                break;
+            };
+            tokens.push(token);
+            end = start;
+        }
+        tokens.reverse();
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenizer() -> Tokenizer {
+        let mut lexicon = Lexicon::default();
+        for (surface, reading, pos) in [
+            ("食べる", "たべる", Pos::IchidanVerb),
+            ("書く", "かく", Pos::GodanVerb),
+            ("買う", "かう", Pos::GodanVerb),
+            ("本", "ほん", Pos::Noun),
+            ("を", "を", Pos::Particle),
+        ] {
+            lexicon.add(LexEntry { surface: surface.to_string(), reading: reading.to_string(), pos, meaning: String::new() });
+        }
+        Tokenizer::new(lexicon)
+    }
+
+    fn surfaces(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|t| t.surface.as_str()).collect()
+    }
+
+    #[test]
+    fn chained_inflections_are_one_token() {
+        let tokenizer = tokenizer();
+        let tokens = tokenizer.tokenize("食べられなかった");
+        assert_eq!(surfaces(&tokens), ["食べられなかった"]);
+        assert_eq!(tokens[0].base_form.as_deref(), Some("食べる"));
+        assert_eq!(tokens[0].reading, "たべられなかった");
+
+        let tokens = tokenizer.tokenize("書かれた");
+        assert_eq!(surfaces(&tokens), ["書かれた"]);
+        assert_eq!(tokens[0].base_form.as_deref(), Some("書く"));
+    }
+
+    #[test]
+    fn kana_fragments_do_not_match_readings() {
+        let tokenizer = tokenizer();
+        let tokens = tokenizer.tokenize("本をかう");
+        assert_eq!(surfaces(&tokens), ["本", "を", "かう"]);
+        assert_eq!(tokens[2].pos, Pos::GodanVerb);
+        assert!(tokenizer.tokenize("なかった").iter().all(|t| t.base_form.as_deref() != Some("買う")));
+    }
+
+    #[test]
+    fn phrases_are_not_words() {
+        assert!(is_phrase("本を読む"));
+        assert!(!is_phrase("食べ物"));
+        assert!(!is_phrase("お茶"));
+    }
+}
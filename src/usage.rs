@@ -0,0 +1,252 @@
+// LLM 调用的用量记录（usage.json）：token 数、模型、耗时和估算费用，以及每月预算
+use crate::storage;
+use crate::LlmError;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+const USAGE_FILE: &str = "usage.json";
+
+// API 返回的 usage 字段
+#[derive(Deserialize, Clone, Copy)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UsageRecord {
+    pub timestamp: u64,
+    pub model: String,
+    // 调用来源：translate、examples、explain
+    pub feature: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    // 服务端没有返回 usage 时按字符数估算
+    #[serde(default)]
+    pub estimated: bool,
+    pub latency_ms: u64,
+    // 美元；模型价格未知时为 None
+    #[serde(default)]
+    pub cost: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct UsageLog {
+    records: Vec<UsageRecord>,
+}
+
+#[derive(Default)]
+pub struct UsageTotals {
+    pub calls: usize,
+    pub estimated: usize,
+    pub unpriced: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost: f64,
+    pub latency_ms: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.calls += 1;
+        if record.estimated {
+            self.estimated += 1;
+        }
+        match record.cost {
+            Some(cost) => self.cost += cost,
+            None => self.unpriced += 1,
+        }
+        self.prompt_tokens += record.prompt_tokens;
+        self.completion_tokens += record.completion_tokens;
+        self.latency_ms += record.latency_ms;
+    }
+
+    pub fn avg_latency_ms(&self) -> u64 {
+        if self.calls == 0 { 0 } else { self.latency_ms / self.calls as u64 }
+    }
+}
+
+pub struct UsageSummary {
+    pub total: UsageTotals,
+    pub models: Vec<(String, UsageTotals)>,
+    pub features: Vec<(String, UsageTotals)>,
+}
+
+fn env_price(name: &str) -> Option<f64> {
+    env::var(name).ok().and_then(|v| v.trim().parse::<f64>().ok())
+}
+
+// 每百万 token 的价格（美元）：(输入, 输出)。OPENAI_PRICE_INPUT / OPENAI_PRICE_OUTPUT 优先
+pub fn price(model: &str) -> Option<(f64, f64)> {
+    if let (Some(input), Some(output)) = (env_price("OPENAI_PRICE_INPUT"), env_price("OPENAI_PRICE_OUTPUT")) {
+        return Some((input, output));
+    }
+    // 带日期后缀的模型名（gpt-4o-mini-2024-07-18）按前缀匹配，较长的名称在前
+    const PRICES: &[(&str, f64, f64)] = &[
+        ("gpt-4o-mini", 0.15, 0.6),
+        ("gpt-4o", 2.5, 10.0),
+        ("gpt-4.1-nano", 0.1, 0.4),
+        ("gpt-4.1-mini", 0.4, 1.6),
+        ("gpt-4.1", 2.0, 8.0),
+        ("gpt-4-turbo", 10.0, 30.0),
+        ("gpt-3.5-turbo", 0.5, 1.5),
+        ("deepseek-chat", 0.27, 1.1),
+    ];
+    PRICES
+        .iter()
+        .find(|(name, _, _)| model.starts_with(name))
+        .map(|(_, input, output)| (*input, *output))
+}
+
+fn monthly_budget() -> Option<f64> {
+    env_price("JP_MONTHLY_BUDGET").filter(|b| *b > 0.0)
+}
+
+// 1970-01-01 起的天数转换为 (年, 月, 日)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn format_date(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// 本月第一天 0 点（UTC）
+pub fn month_start(now: u64) -> u64 {
+    let (_, _, day) = civil_from_days((now / 86400) as i64);
+    (now / 86400 - (day as u64 - 1)) * 86400
+}
+
+// 解析 "7d"、"24h"、"2w"、"30m" 这样的时长，返回秒数
+pub fn parse_duration(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (expected e.g. 7d, 24h, 2w)", text))?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" | "" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("invalid duration unit '{}' (use m, h, d or w)", unit)),
+    };
+    Ok(number * unit_secs)
+}
+
+fn load_log() -> UsageLog {
+    storage::load_json(USAGE_FILE).unwrap_or_else(|e| {
+        eprintln!("⚠️  Ignoring usage log: {}", e);
+        UsageLog::default()
+    })
+}
+
+// 记录一次调用；usage 为 None 时用字符数估算 token
+pub fn record(feature: &str, model: &str, usage: Option<TokenUsage>, prompt_chars: usize, completion_chars: usize, latency_ms: u64) {
+    let (usage, estimated) = match usage {
+        Some(usage) => (usage, false),
+        None => (
+            TokenUsage {
+                prompt_tokens: prompt_chars as u64,
+                completion_tokens: completion_chars as u64,
+            },
+            true,
+        ),
+    };
+    let cost = price(model).map(|(input, output)| {
+        (usage.prompt_tokens as f64 * input + usage.completion_tokens as f64 * output) / 1_000_000.0
+    });
+
+    let mut log = load_log();
+    log.records.push(UsageRecord {
+        timestamp: storage::now_secs(),
+        model: model.to_string(),
+        feature: feature.to_string(),
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        estimated,
+        latency_ms,
+        cost,
+    });
+    if let Err(e) = storage::save_json(USAGE_FILE, &log) {
+        eprintln!("⚠️  Failed to save usage log: {}", e);
+    }
+}
+
+// 本月的费用（美元）
+pub fn month_cost() -> f64 {
+    let start = month_start(storage::now_secs());
+    load_log()
+        .records
+        .iter()
+        .filter(|r| r.timestamp >= start)
+        .filter_map(|r| r.cost)
+        .fold(0.0, |sum, cost| sum + cost)
+}
+
+// 超出每月预算（JP_MONTHLY_BUDGET）时拒绝新的 LLM 调用；
+// 模型价格未知时无法计算费用，设置了预算就拒绝调用，避免预算形同虚设
+pub fn check_budget(model: &str) -> Result<(), LlmError> {
+    let Some(budget) = monthly_budget() else {
+        return Ok(());
+    };
+    if price(model).is_none() {
+        return Err(LlmError::Budget(format!(
+            "model '{}' has no known price, so the ${} budget cannot be enforced",
+            model, budget
+        )));
+    }
+    let spent = month_cost();
+    if spent >= budget {
+        return Err(LlmError::Budget(format!("exceeded, spent ${:.4} of ${} this month", spent, budget)));
+    }
+    Ok(())
+}
+
+// 汇总 since 之后的调用
+pub fn summarize(since: u64) -> UsageSummary {
+    let mut summary = UsageSummary {
+        total: UsageTotals::default(),
+        models: Vec::new(),
+        features: Vec::new(),
+    };
+
+    for record in load_log().records.iter().filter(|r| r.timestamp >= since) {
+        summary.total.add(record);
+        for (groups, key) in [(&mut summary.models, &record.model), (&mut summary.features, &record.feature)] {
+            match groups.iter_mut().find(|(k, _)| k == key) {
+                Some((_, totals)) => totals.add(record),
+                None => {
+                    let mut totals = UsageTotals::default();
+                    totals.add(record);
+                    groups.push((key.clone(), totals));
+                }
+            }
+        }
+    }
+    summary.models.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(&b.0)));
+    summary.features.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(&b.0)));
+    summary
+}
+
+// 预算状态：(本月费用, 预算)
+pub fn budget_status() -> Option<(f64, f64)> {
+    monthly_budget().map(|budget| (month_cost(), budget))
+}
+
+pub fn usage_path() -> std::path::PathBuf {
+    storage::data_file(USAGE_FILE)
+}
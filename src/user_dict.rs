@@ -0,0 +1,88 @@
+// 用户词典：保存从 LLM 翻译中收录的词条，与内置的 init_chinese_map 一起参与查询
+use crate::storage;
+use crate::Translation;
+use serde::{Deserialize, Serialize};
+
+const USER_DICT_FILE: &str = "user_dictionary.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum EntrySource {
+    Llm,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserEntry {
+    pub chinese: String,
+    pub translation: Translation,
+    pub source: EntrySource,
+    // LLM 生成的词条需要人工确认后才算可靠
+    pub review_needed: bool,
+    pub added_at: u64,
+}
+
+impl UserEntry {
+    // 与 init_chinese_map 相同的显示格式
+    pub fn japanese(&self) -> String {
+        self.translation.display()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct UserDictionary {
+    entries: Vec<UserEntry>,
+}
+
+impl UserDictionary {
+    pub fn load() -> Self {
+        storage::load_json(USER_DICT_FILE).unwrap_or_else(|e| {
+            eprintln!("⚠️  Ignoring user dictionary: {}", e);
+            UserDictionary::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        storage::save_json(USER_DICT_FILE, self)
+    }
+
+    pub fn get(&self, chinese: &str) -> Option<&UserEntry> {
+        self.entries.iter().find(|e| e.chinese == chinese)
+    }
+
+    // 收录一条 LLM 翻译，已存在的同名词条会被替换
+    pub fn add_llm_translation(&mut self, chinese: &str, translation: Translation) {
+        self.entries.retain(|e| e.chinese != chinese);
+        self.entries.push(UserEntry {
+            chinese: chinese.to_string(),
+            translation,
+            source: EntrySource::Llm,
+            review_needed: true,
+            added_at: storage::now_secs(),
+        });
+    }
+
+    // 确认词条无误，返回是否找到该词条
+    pub fn approve(&mut self, chinese: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.chinese == chinese) {
+            Some(entry) => {
+                entry.review_needed = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, chinese: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.chinese != chinese);
+        self.entries.len() != before
+    }
+
+    pub fn entries(&self) -> &[UserEntry] {
+        &self.entries
+    }
+}
+
+pub fn user_dict_path() -> std::path::PathBuf {
+    storage::data_file(USER_DICT_FILE)
+}
@@ -288,8 +288,30 @@ function displayTranslation(chinese, japanese) {
             <div style="font-size: 2rem; margin: 20px 0;">↓</div>
             <div class="japanese-text">${japanese.split('(')[0].trim()}</div>
             <div class="reading-text">${japanese.match(/\((.*?)\)/)[1]}</div>
+            <div id="accentResult" class="accent-result"></div>
         </div>
     `;
+    loadAccent(japanese.split('(')[0].trim(), document.getElementById('accentResult'));
+}
+
+// Load the pitch accent graph from the jp serve API (/api/accent).
+// Nothing is shown when the page is opened without the server or the word has no accent data.
+async function loadAccent(word, container) {
+    try {
+        const response = await fetch(`/api/accent?word=${encodeURIComponent(word)}`);
+        if (!response.ok) {
+            return;
+        }
+        const entries = await response.json();
+        container.innerHTML = entries.map(entry => entry.patterns.map(p => `
+            <div class="accent-item">
+                <div class="accent-label">${entry.reading} [${p.pattern}] ${p.name}</div>
+                ${p.svg}
+            </div>
+        `).join('')).join('');
+    } catch (e) {
+        // Static page without the API
+    }
 }
 
 // Display error message